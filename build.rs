@@ -0,0 +1,50 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Dossiers dont les fichiers sont intégrés à l'exécutable avec la fonctionnalité
+/// `embedded-assets`.
+const ASSET_DIRS: [&str; 2] = ["sprite", "audio"];
+
+/// Génère `embedded_assets.rs` dans `OUT_DIR` : la liste des fichiers des dossiers de
+/// ressources, chacun associé à son contenu via `include_bytes!`.
+///
+/// La liste est construite à partir des fichiers réellement présents, pour qu'une ressource
+/// absente du dépôt ne fasse pas échouer la compilation (elle sera remplacée au lancement).
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let embed = env::var_os("CARGO_FEATURE_EMBEDDED_ASSETS").is_some();
+
+    let mut entries = String::new();
+    for dir in ASSET_DIRS {
+        println!("cargo:rerun-if-changed={}", dir);
+        if !embed {
+            continue;
+        }
+        let Ok(files) = fs::read_dir(Path::new(&manifest_dir).join(dir)) else {
+            continue;
+        };
+        let mut files: Vec<_> = files
+            .filter_map(Result::ok)
+            .filter(|file| file.path().is_file())
+            .collect();
+        files.sort_by_key(|file| file.file_name());
+        for file in files {
+            entries.push_str(&format!(
+                "    ({:?}, include_bytes!({:?})),\n",
+                format!("{}/{}", dir, file.file_name().to_string_lossy()),
+                file.path().to_string_lossy()
+            ));
+        }
+    }
+
+    fs::write(
+        Path::new(&out_dir).join("embedded_assets.rs"),
+        format!(
+            "pub static EMBEDDED_ASSETS: &[(&str, &[u8])] = &[\n{}];\n",
+            entries
+        ),
+    )
+    .unwrap();
+}
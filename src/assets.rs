@@ -0,0 +1,391 @@
+#[cfg(feature = "embedded-assets")]
+use crate::embedded;
+use crate::manifest::{AssetKind, AssetPaths};
+use macroquad::audio::{load_sound, load_sound_from_bytes, Sound};
+use macroquad::prelude::*;
+
+/// Fréquence d'échantillonnage des sons générés.
+pub const SAMPLE_RATE: u32 = 44_100;
+
+/// Taille (en pixels) des textures de remplacement.
+const PLACEHOLDER_SIZE: u16 = 64;
+
+/// Forme dessinée à la place d'une texture introuvable.
+#[derive(Clone, Copy, Debug)]
+pub enum TexturePlaceholder {
+    /// Disque plein (astéroïdes, missiles).
+    Circle(Color),
+    /// Triangle pointant vers le haut (vaisseau).
+    Triangle(Color),
+    /// Couleur unie (arrière-plan).
+    Solid(Color),
+}
+
+/// Son joué à la place d'un son introuvable.
+#[derive(Clone, Copy, Debug)]
+pub enum SoundPlaceholder {
+    /// Note dont la fréquence glisse de `start` à `end` Hz, avec un volume décroissant.
+    Tone { start: f32, end: f32, duration: f32 },
+    /// Son produit par une fonction du synthétiseur (couches de musique).
+    Synth(fn() -> Vec<f32>),
+}
+
+/// Charge les ressources du jeu décrites par le manifeste, sans jamais interrompre le démarrage.
+///
+/// Chaque ressource introuvable ou illisible est remplacée par une ressource générée
+/// (forme colorée ou son synthétisé) et notée dans la liste des ressources manquantes.
+pub struct AssetLoader {
+    paths: AssetPaths,
+    missing: Vec<String>,
+}
+
+impl AssetLoader {
+    /// Crée un chargeur sans ressource manquante.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - L'emplacement des ressources (racine, pack et manifeste).
+    pub fn new(paths: AssetPaths) -> Self {
+        Self {
+            paths,
+            missing: Vec::new(),
+        }
+    }
+
+    /// Retourne le chemin d'une ressource, ou la note comme manquante si elle n'a pas de
+    /// chemin dans le manifeste.
+    fn path(&mut self, kind: AssetKind, id: &str) -> Option<String> {
+        let path = self.paths.resolve_path(kind, id);
+        if path.is_none() {
+            self.missing
+                .push(format!("{} {} (absente du manifeste)", kind.key(), id));
+        }
+        path
+    }
+
+    /// Retourne le contenu d'une ressource intégrée à l'exécutable, sauf si le pack de
+    /// ressources la remplace.
+    #[cfg(feature = "embedded-assets")]
+    fn embedded(&self, kind: AssetKind, id: &str) -> Option<&'static [u8]> {
+        if self.paths.pack_override(kind, id).is_some() {
+            return None;
+        }
+        embedded::get(self.paths.relative_path(kind, id)?)
+    }
+
+    /// Charge une texture, ou génère une texture de remplacement.
+    ///
+    /// Avec la fonctionnalité `embedded-assets`, la texture intégrée à l'exécutable est
+    /// utilisée en priorité (sauf si le pack de ressources la remplace).
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - L'identifiant de la texture dans le manifeste.
+    /// * `placeholder` - La forme à générer si l'image est introuvable.
+    ///
+    /// # Retour
+    ///
+    /// La texture chargée ou générée.
+    pub async fn texture(&mut self, id: &str, placeholder: TexturePlaceholder) -> Texture2D {
+        #[cfg(feature = "embedded-assets")]
+        if let Some(image) = self
+            .embedded(AssetKind::Texture, id)
+            .and_then(|bytes| Image::from_file_with_format(bytes, None).ok())
+        {
+            return Texture2D::from_image(&image);
+        }
+
+        if let Some(path) = self.path(AssetKind::Texture, id) {
+            match load_texture(&path).await {
+                Ok(texture) => return texture,
+                Err(_) => self.missing.push(path),
+            }
+        }
+        Texture2D::from_image(&placeholder_image(placeholder))
+    }
+
+    /// Charge un son, ou génère un son de remplacement.
+    ///
+    /// Avec la fonctionnalité `embedded-assets`, le son intégré à l'exécutable est utilisé
+    /// en priorité (sauf si le pack de ressources le remplace).
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - L'identifiant du son dans le manifeste.
+    /// * `placeholder` - Le son à générer si le fichier est introuvable.
+    ///
+    /// # Retour
+    ///
+    /// Le son chargé ou généré.
+    pub async fn sound(&mut self, id: &str, placeholder: SoundPlaceholder) -> Sound {
+        #[cfg(feature = "embedded-assets")]
+        if let Some(bytes) = self.embedded(AssetKind::Sound, id) {
+            if let Ok(sound) = load_sound_from_bytes(bytes).await {
+                return sound;
+            }
+        }
+
+        if let Some(path) = self.path(AssetKind::Sound, id) {
+            match load_sound(&path).await {
+                Ok(sound) => return sound,
+                Err(_) => self.missing.push(path),
+            }
+        }
+        sound_from_samples(&placeholder_samples(placeholder)).await
+    }
+
+    /// Charge un son WAV sous forme d'échantillons mono, pour pouvoir le transformer avant
+    /// de le jouer (par exemple pour le spatialiser), ou génère un son de remplacement.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - L'identifiant du son dans le manifeste.
+    /// * `placeholder` - Le son à générer si le fichier est introuvable ou n'est pas un WAV.
+    ///
+    /// # Retour
+    ///
+    /// Les échantillons et leur fréquence d'échantillonnage, en Hz.
+    pub async fn sound_samples(
+        &mut self,
+        id: &str,
+        placeholder: SoundPlaceholder,
+    ) -> (Vec<f32>, u32) {
+        #[cfg(feature = "embedded-assets")]
+        if let Some(decoded) = self.embedded(AssetKind::Sound, id).and_then(decode_wav) {
+            return decoded;
+        }
+
+        if let Some(path) = self.path(AssetKind::Sound, id) {
+            match load_file(&path)
+                .await
+                .ok()
+                .and_then(|bytes| decode_wav(&bytes))
+            {
+                Some(decoded) => return decoded,
+                None => self.missing.push(path),
+            }
+        }
+        (placeholder_samples(placeholder), SAMPLE_RATE)
+    }
+
+    /// Charge un son facultatif : sans chemin dans le manifeste, le son de remplacement est
+    /// utilisé sans être signalé comme manquant.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - L'identifiant du son dans le manifeste.
+    /// * `placeholder` - Le son à générer si le manifeste ne fournit pas de fichier.
+    ///
+    /// # Retour
+    ///
+    /// Le son chargé ou généré.
+    pub async fn optional_sound(&mut self, id: &str, placeholder: SoundPlaceholder) -> Sound {
+        if self.paths.resolve_path(AssetKind::Sound, id).is_some() {
+            return self.sound(id, placeholder).await;
+        }
+        sound_from_samples(&placeholder_samples(placeholder)).await
+    }
+
+    /// Charge une police facultative : sans chemin dans le manifeste, la police par défaut
+    /// est utilisée sans être signalée comme manquante.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - L'identifiant de la police dans le manifeste.
+    ///
+    /// # Retour
+    ///
+    /// La police chargée, ou `None` pour la police par défaut.
+    pub async fn font(&mut self, id: &str) -> Option<Font> {
+        #[cfg(feature = "embedded-assets")]
+        if let Some(bytes) = self.embedded(AssetKind::Font, id) {
+            if let Ok(font) = load_ttf_font_from_bytes(bytes) {
+                return Some(font);
+            }
+        }
+
+        let path = self.paths.resolve_path(AssetKind::Font, id)?;
+        match load_ttf_font(&path).await {
+            Ok(font) => Some(font),
+            Err(_) => {
+                self.missing.push(path);
+                None
+            }
+        }
+    }
+
+    /// Retourne les chemins des ressources qui ont dû être remplacées.
+    pub fn missing(&self) -> &[String] {
+        &self.missing
+    }
+}
+
+/// Génère l'image de remplacement d'une texture.
+fn placeholder_image(placeholder: TexturePlaceholder) -> Image {
+    if let TexturePlaceholder::Solid(color) = placeholder {
+        return Image::gen_image_color(1, 1, color);
+    }
+
+    let size = PLACEHOLDER_SIZE as f32;
+    let mut image = Image::gen_image_color(PLACEHOLDER_SIZE, PLACEHOLDER_SIZE, BLANK);
+    for y in 0..PLACEHOLDER_SIZE as u32 {
+        for x in 0..PLACEHOLDER_SIZE as u32 {
+            // Coordonnées du centre du pixel, entre 0 et 1
+            let u = (x as f32 + 0.5) / size;
+            let v = (y as f32 + 0.5) / size;
+            let (inside, color) = match placeholder {
+                TexturePlaceholder::Circle(color) => {
+                    ((u - 0.5).powi(2) + (v - 0.5).powi(2) <= 0.25, color)
+                }
+                // Pointe en haut au centre, base sur le bord inférieur
+                TexturePlaceholder::Triangle(color) => ((u - 0.5).abs() <= v / 2., color),
+                TexturePlaceholder::Solid(color) => (true, color),
+            };
+            if inside {
+                image.set_pixel(x, y, color);
+            }
+        }
+    }
+    image
+}
+
+/// Génère les échantillons d'un son de remplacement.
+fn placeholder_samples(placeholder: SoundPlaceholder) -> Vec<f32> {
+    match placeholder {
+        SoundPlaceholder::Tone {
+            start,
+            end,
+            duration,
+        } => tone(start, end, duration),
+        SoundPlaceholder::Synth(generate) => generate(),
+    }
+}
+
+/// Charge un son généré à partir de ses échantillons, encodés en WAV en mémoire.
+///
+/// # Arguments
+///
+/// * `samples` - Les échantillons mono à `SAMPLE_RATE` Hz, entre -1 et 1.
+///
+/// # Retour
+///
+/// Le son, prêt à être joué.
+pub async fn sound_from_samples(samples: &[f32]) -> Sound {
+    load_sound_from_bytes(&encode_wav(samples, SAMPLE_RATE))
+        .await
+        .expect("le son généré doit être un WAV valide")
+}
+
+/// Synthétise une note dont la fréquence glisse linéairement, avec une décroissance
+/// exponentielle du volume pour éviter un son trop sec.
+///
+/// # Arguments
+///
+/// * `start` - Fréquence de départ, en Hz.
+/// * `end` - Fréquence d'arrivée, en Hz.
+/// * `duration` - Durée de la note, en secondes.
+///
+/// # Retour
+///
+/// Les échantillons, entre -1 et 1.
+fn tone(start: f32, end: f32, duration: f32) -> Vec<f32> {
+    let count = (duration * SAMPLE_RATE as f32) as usize;
+    let mut phase = 0.0f32;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / count as f32;
+            phase += (start + (end - start) * t) / SAMPLE_RATE as f32;
+            (phase * std::f32::consts::TAU).sin() * (-4. * t).exp() * 0.5
+        })
+        .collect()
+}
+
+/// Encode des échantillons mono au format WAV (PCM 16 bits).
+///
+/// # Arguments
+///
+/// * `samples` - Les échantillons, entre -1 et 1 (les valeurs au-delà sont écrêtées).
+/// * `sample_rate` - La fréquence d'échantillonnage, en Hz.
+///
+/// # Retour
+///
+/// Le contenu d'un fichier WAV, lisible par `load_sound_from_bytes`.
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    encode_pcm16(samples, 1, sample_rate)
+}
+
+/// Encode des échantillons stéréo au format WAV (PCM 16 bits).
+///
+/// # Arguments
+///
+/// * `frames` - Les échantillons gauche et droit, entre -1 et 1.
+/// * `sample_rate` - La fréquence d'échantillonnage, en Hz.
+///
+/// # Retour
+///
+/// Le contenu d'un fichier WAV, lisible par `load_sound_from_bytes`.
+pub fn encode_wav_stereo(frames: &[[f32; 2]], sample_rate: u32) -> Vec<u8> {
+    encode_pcm16(frames.as_flattened(), 2, sample_rate)
+}
+
+/// Encode des échantillons entrelacés (un échantillon par canal et par instant) au format
+/// WAV PCM 16 bits.
+fn encode_pcm16(samples: &[f32], channels: u16, sample_rate: u32) -> Vec<u8> {
+    let block_align = channels * 2;
+    let data_size = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_size as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_size).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+
+    // Bloc de format : PCM, 16 bits
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    bytes.extend_from_slice(&block_align.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        let value = (sample.clamp(-1., 1.) * i16::MAX as f32) as i16;
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+/// Décode un fichier WAV en échantillons mono (les canaux sont mélangés).
+///
+/// # Arguments
+///
+/// * `bytes` - Le contenu du fichier.
+///
+/// # Retour
+///
+/// Les échantillons entre -1 et 1 et la fréquence d'échantillonnage, ou `None` si le fichier
+/// n'est pas un WAV lisible.
+pub fn decode_wav(bytes: &[u8]) -> Option<(Vec<f32>, u32)> {
+    let reader = hound::WavReader::new(std::io::Cursor::new(bytes)).ok()?;
+    let spec = reader.spec();
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1u32 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|sample| sample.map(|value| value as f32 / scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .ok()?;
+
+    let channels = spec.channels.max(1) as usize;
+    let samples = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+    Some((samples, spec.sample_rate))
+}
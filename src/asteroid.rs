@@ -1,11 +1,13 @@
+use crate::rng::{self, GameRng};
 use crate::stellarobject::StellarObject;
-use ::rand::{thread_rng, Rng};
+use ::rand::Rng;
 use macroquad::prelude::*;
 
 /// Représente un astéroïde dans le jeu.
 /// Les astéroïdes se déplacent, peuvent entrer en collision avec d'autres objets,
 /// et se divisent en deux astéroïdes plus petits lors d'une collision avec un missile
 /// si leur niveau est supérieur à 1.
+#[derive(Clone)]
 pub struct Asteroid {
     /// Position actuelle de l'astéroïde dans l'espace de jeu.
     position: Vec2,
@@ -38,7 +40,15 @@ impl Asteroid {
         position: Option<Vec2>,
     ) -> Self {
         Self {
-            position: position.unwrap_or_else(|| Self::random_position(level, level_size)),
+            position: position.unwrap_or_else(|| {
+                let screen = vec2(screen_width(), screen_height());
+                Self::random_position(
+                    level,
+                    level_size,
+                    screen,
+                    &mut GameRng::new(rng::random_seed()),
+                )
+            }),
             speed,
             level,
             has_collided: false,
@@ -63,18 +73,25 @@ impl Asteroid {
         self.has_collided
     }
 
-    /// Génère une position aléatoire autour des bords de l'écran pour un nouvel astéroïde.
+    /// Génère une position aléatoire autour des bords de l'arène pour un nouvel astéroïde.
     ///
     /// # Arguments
     ///
     /// * `level` - Niveau de l'astéroïde, utilisé pour déterminer sa taille.
     /// * `level_size` - Tuple des tailles des astéroïdes pour les niveaux 3, 2 et 1.
+    /// * `arena` - La taille de l'arène.
+    /// * `rng` - Le générateur aléatoire à utiliser (celui de la partie, pour qu'une graine
+    ///   donnée produise toujours les mêmes positions).
     ///
     /// # Retour
     ///
-    /// Une position `Vec2` autour des bords de l'écran.
-    fn random_position(level: u8, level_size: (f32, f32, f32)) -> Vec2 {
-        let mut rng = thread_rng();
+    /// Une position `Vec2` autour des bords de l'arène.
+    pub fn random_position(
+        level: u8,
+        level_size: (f32, f32, f32),
+        arena: Vec2,
+        rng: &mut impl Rng,
+    ) -> Vec2 {
         let size = match level {
             3 => level_size.0,
             2 => level_size.1,
@@ -83,29 +100,30 @@ impl Asteroid {
         };
         let side = rng.gen_range(0..4);
         match side {
-            0 => vec2(rng.gen_range(0.0..screen_width()), -size),
-            1 => vec2(screen_width() + size, rng.gen_range(0.0..screen_height())),
-            2 => vec2(rng.gen_range(0.0..screen_width()), screen_height() + size),
-            3 => vec2(-size, rng.gen_range(0.0..screen_height())),
+            0 => vec2(rng.gen_range(0.0..arena.x), -size),
+            1 => vec2(arena.x + size, rng.gen_range(0.0..arena.y)),
+            2 => vec2(rng.gen_range(0.0..arena.x), arena.y + size),
+            3 => vec2(-size, rng.gen_range(0.0..arena.y)),
             _ => unreachable!(),
         }
     }
 
-    /// Contraint la position de l'astéroïde à rester dans les limites de l'écran.
+    /// Contraint la position de l'astéroïde à rester dans les limites de l'arène.
     ///
-    /// Si la position dépasse les limites, elle est ramenée de l'autre côté de l'écran.
+    /// Si la position dépasse les limites, elle est ramenée de l'autre côté de l'arène.
     ///
     /// # Arguments
     ///
     /// * `pos` - Position actuelle de l'astéroïde.
+    /// * `arena` - La taille de l'arène.
     ///
     /// # Retour
     ///
-    /// Une nouvelle position contrainte à l'écran.
-    fn bound_position(pos: Vec2) -> Vec2 {
+    /// Une nouvelle position contrainte à l'arène.
+    fn bound_position(pos: Vec2, arena: Vec2) -> Vec2 {
         Vec2::new(
-            Self::wrap_position(pos.x, screen_width()),
-            Self::wrap_position(pos.y, screen_height()),
+            Self::wrap_position(pos.x, arena.x),
+            Self::wrap_position(pos.y, arena.y),
         )
     }
 
@@ -206,10 +224,10 @@ impl StellarObject for Asteroid {
 
     /// Met à jour la position de l'astéroïde en fonction de sa vitesse.
     ///
-    /// La position est contrainte aux limites de l'écran grâce à l'effet "wrap-around".
-    fn update_position(&mut self) {
+    /// La position est contrainte aux limites de l'arène grâce à l'effet "wrap-around".
+    fn update_position(&mut self, arena: Vec2) {
         self.position += self.speed;
-        self.position = Self::bound_position(self.position);
+        self.position = Self::bound_position(self.position, arena);
     }
 
     /// Gère une collision impliquant l'astéroïde.
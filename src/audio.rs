@@ -0,0 +1,600 @@
+use crate::events::{EventListener, GameEvent};
+use crate::settings::Settings;
+use crate::Sounds;
+use macroquad::audio::{load_sound_from_bytes, set_sound_volume, stop_sound, Sound};
+use macroquad::prelude::*;
+
+/// Durée (en secondes) des fondus d'entrée et de sortie de la musique.
+const MUSIC_FADE_DURATION: f32 = 1.5;
+
+/// Nombre de couches de la musique : base, rythme et tension.
+pub const MUSIC_LAYERS: usize = 3;
+
+/// Durée (en secondes) d'un fondu enchaîné complet d'une couche de musique.
+const LAYER_CROSSFADE_DURATION: f32 = 2.0;
+
+/// Durée (en secondes) pendant laquelle le grondement du réacteur continue après le dernier
+/// événement `ShipThrust` (le réacteur en publie un à chaque pas de simulation où il est allumé).
+const THRUST_HOLD: f64 = 0.1;
+
+/// Nombre de positions stéréo pré-calculées pour chaque son spatialisé, de l'extrême
+/// gauche à l'extrême droite.
+pub const PAN_STEPS: usize = 5;
+
+/// Atténuation maximale d'un son dont la source est à l'autre bout de l'écran.
+const MAX_DISTANCE_ATTENUATION: f32 = 0.5;
+
+/// Son décliné en plusieurs positions stéréo.
+///
+/// macroquad ne sait pas répartir un son entre les haut-parleurs : chaque position est donc
+/// rendue à l'avance dans un WAV stéréo, et `get` choisit la plus proche.
+pub struct PannedSound {
+    variants: Vec<Sound>,
+}
+
+impl PannedSound {
+    /// Rend les `PAN_STEPS` positions stéréo d'un son mono.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Les échantillons mono, entre -1 et 1.
+    /// * `sample_rate` - La fréquence d'échantillonnage, en Hz.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `PannedSound`.
+    pub async fn new(samples: &[f32], sample_rate: u32) -> Self {
+        let mut variants = Vec::with_capacity(PAN_STEPS);
+        for step in 0..PAN_STEPS {
+            let (left, right) = pan_gains(pan_of_step(step));
+            let frames: Vec<[f32; 2]> = samples.iter().map(|s| [s * left, s * right]).collect();
+            let bytes = crate::assets::encode_wav_stereo(&frames, sample_rate);
+            variants.push(
+                load_sound_from_bytes(&bytes)
+                    .await
+                    .expect("le son spatialisé doit être un WAV valide"),
+            );
+        }
+        Self { variants }
+    }
+
+    /// Retourne la variante la plus proche d'une position stéréo.
+    ///
+    /// # Arguments
+    ///
+    /// * `pan` - La position, de -1 (gauche) à 1 (droite).
+    pub fn get(&self, pan: f32) -> &Sound {
+        &self.variants[pan_step(pan)]
+    }
+
+    /// Arrête toutes les variantes.
+    fn stop(&self) {
+        for sound in &self.variants {
+            stop_sound(sound);
+        }
+    }
+}
+
+/// Retourne l'indice de la variante la plus proche d'une position stéréo.
+pub fn pan_step(pan: f32) -> usize {
+    ((pan.clamp(-1., 1.) + 1.) / 2. * (PAN_STEPS - 1) as f32).round() as usize
+}
+
+/// Retourne la position stéréo d'une variante.
+fn pan_of_step(step: usize) -> f32 {
+    step as f32 / (PAN_STEPS - 1) as f32 * 2. - 1.
+}
+
+/// Calcule les volumes gauche et droit d'une position stéréo, à puissance constante (un son
+/// au centre n'est pas plus faible qu'un son sur le côté).
+///
+/// # Arguments
+///
+/// * `pan` - La position, de -1 (gauche) à 1 (droite).
+///
+/// # Retour
+///
+/// Les volumes gauche et droit, entre 0 et 1.
+pub fn pan_gains(pan: f32) -> (f32, f32) {
+    let angle = (pan.clamp(-1., 1.) + 1.) * std::f32::consts::FRAC_PI_4;
+    (angle.cos(), angle.sin())
+}
+
+/// Calcule la position stéréo et l'atténuation d'un son selon la position de sa source par
+/// rapport à l'auditeur (le vaisseau).
+///
+/// # Arguments
+///
+/// * `source` - La position de l'événement.
+/// * `listener` - La position de l'auditeur.
+/// * `arena` - La taille de l'arène.
+///
+/// # Retour
+///
+/// La position stéréo (-1 à gauche, 1 à droite) et le facteur de volume (entre
+/// `1 - MAX_DISTANCE_ATTENUATION` et 1).
+pub fn spatialize(source: Vec2, listener: Vec2, arena: Vec2) -> (f32, f32) {
+    let offset = source - listener;
+    let pan = (offset.x / (arena.x / 2.)).clamp(-1., 1.);
+    let distance = (offset.length() / arena.length()).min(1.);
+    (pan, 1. - MAX_DISTANCE_ATTENUATION * distance)
+}
+
+/// Calcule l'intensité de la partie, qui pilote les couches de la musique.
+///
+/// # Arguments
+///
+/// * `remaining` - Part des astéroïdes de la vague encore à détruire (0.0 à 1.0).
+/// * `shield` - Part du bouclier restant (0.0 à 1.0).
+/// * `final_wave` - `true` pendant la dernière vague, plus intense.
+///
+/// # Retour
+///
+/// L'intensité, de 0.0 (calme) à 1.0 (tension maximale).
+pub fn music_intensity(remaining: f32, shield: f32, final_wave: bool) -> f32 {
+    let progress = 1. - remaining.clamp(0., 1.);
+    let danger = 1. - shield.clamp(0., 1.);
+    let boss = if final_wave { 1. } else { 0. };
+    (0.5 * progress + 0.3 * danger + 0.2 * boss).clamp(0., 1.)
+}
+
+/// Volumes relatifs des couches de musique, qui suivent l'intensité de la partie par des
+/// fondus enchaînés.
+///
+/// La couche de base est toujours jouée ; le rythme arrive avec une intensité moyenne et la
+/// tension avec une intensité forte.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MusicLayers {
+    levels: [f32; MUSIC_LAYERS],
+    targets: [f32; MUSIC_LAYERS],
+}
+
+impl Default for MusicLayers {
+    fn default() -> Self {
+        let targets = Self::targets(0.);
+        Self {
+            levels: targets,
+            targets,
+        }
+    }
+}
+
+impl MusicLayers {
+    /// Retourne les volumes visés pour chaque couche à une intensité donnée.
+    fn targets(intensity: f32) -> [f32; MUSIC_LAYERS] {
+        let intensity = intensity.clamp(0., 1.);
+        [
+            1.,
+            ((intensity - 0.2) / 0.3).clamp(0., 1.),
+            ((intensity - 0.6) / 0.3).clamp(0., 1.),
+        ]
+    }
+
+    /// Change l'intensité visée ; les couches la rejoignent progressivement dans `update`.
+    ///
+    /// # Arguments
+    ///
+    /// * `intensity` - L'intensité, de 0.0 à 1.0 (voir `music_intensity`).
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.targets = Self::targets(intensity);
+    }
+
+    /// Rapproche le volume de chaque couche de son volume visé.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - Durée de la frame, en secondes.
+    pub fn update(&mut self, dt: f32) {
+        let step = dt / LAYER_CROSSFADE_DURATION;
+        for (level, target) in self.levels.iter_mut().zip(self.targets) {
+            *level += (target - *level).clamp(-step, step);
+        }
+    }
+
+    /// Retourne le volume relatif de chaque couche (0.0 à 1.0).
+    pub fn get_levels(&self) -> [f32; MUSIC_LAYERS] {
+        self.levels
+    }
+}
+
+/// Effets sonores joués sur le canal des effets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundEffect {
+    /// Tir d'un missile.
+    Shoot,
+    /// Astéroïde détruit (son du fichier WAV).
+    AsteroidHit,
+    /// Astéroïde détruit (explosion synthétisée selon le niveau de l'astéroïde).
+    Explosion(u8),
+    /// Vaisseau touché.
+    ShipHit,
+    /// Fin de partie gagnée.
+    Win,
+    /// Fin de partie perdue.
+    Lose,
+}
+
+impl SoundEffect {
+    /// Retourne le nombre maximal d'exemplaires de cet effet joués en même temps.
+    ///
+    /// Au-delà, le nouvel exemplaire n'est pas joué (par exemple lorsque beaucoup
+    /// d'astéroïdes explosent dans la même frame).
+    pub fn max_voices(self) -> usize {
+        match self {
+            SoundEffect::Shoot => 3,
+            SoundEffect::AsteroidHit | SoundEffect::Explosion(_) => 4,
+            SoundEffect::ShipHit => 2,
+            SoundEffect::Win | SoundEffect::Lose => 1,
+        }
+    }
+
+    /// Retourne la durée (en secondes) pendant laquelle un exemplaire est considéré comme
+    /// actif. macroquad ne permet pas de savoir si un son est terminé : on se fie donc à
+    /// la durée approximative de chaque son.
+    pub fn voice_duration(self) -> f64 {
+        match self {
+            SoundEffect::Shoot => 0.3,
+            SoundEffect::AsteroidHit => 0.6,
+            SoundEffect::Explosion(level) => 0.2 + 0.25 * level as f64,
+            SoundEffect::ShipHit => 0.5,
+            SoundEffect::Win | SoundEffect::Lose => 3.,
+        }
+    }
+}
+
+/// Limite le nombre d'exemplaires simultanés de chaque effet sonore.
+#[derive(Debug, Default)]
+pub struct VoiceLimiter {
+    /// Effets en cours : effet et heure de fin estimée.
+    voices: Vec<(SoundEffect, f64)>,
+}
+
+impl VoiceLimiter {
+    /// Réserve un exemplaire d'un effet, si le nombre maximal n'est pas atteint.
+    ///
+    /// # Arguments
+    ///
+    /// * `effect` - L'effet à jouer.
+    /// * `now` - L'heure actuelle, en secondes.
+    ///
+    /// # Retour
+    ///
+    /// `true` si l'effet peut être joué.
+    pub fn try_start(&mut self, effect: SoundEffect, now: f64) -> bool {
+        self.voices.retain(|&(_, end)| end > now);
+        let active = self.voices.iter().filter(|(e, _)| *e == effect).count();
+        if active >= effect.max_voices() {
+            return false;
+        }
+        self.voices.push((effect, now + effect.voice_duration()));
+        true
+    }
+
+    /// Libère tous les exemplaires d'un effet (lorsqu'il est arrêté).
+    ///
+    /// # Arguments
+    ///
+    /// * `effect` - L'effet arrêté.
+    pub fn release(&mut self, effect: SoundEffect) {
+        self.voices.retain(|(e, _)| *e != effect);
+    }
+}
+
+/// Gestionnaire audio : canaux musique et effets, volumes, sourdine, limitation du nombre
+/// d'exemplaires simultanés de chaque effet et fondus de la musique.
+///
+/// Le volume effectif d'un son est `général × canal`, ou zéro si la sourdine est activée.
+/// Le grondement du réacteur et le rythme de fond sont joués sur le canal des effets.
+pub struct AudioMixer {
+    sounds: Sounds,
+    master_volume: f32,
+    music_volume: f32,
+    sfx_volume: f32,
+    muted: bool,
+    /// `true` pour jouer les tirs et explosions synthétisés plutôt que les fichiers WAV.
+    synth_sounds: bool,
+    voices: VoiceLimiter,
+    /// `true` si la musique doit être jouée (fondu d'entrée), `false` pour un fondu de sortie.
+    music_wanted: bool,
+    /// `true` tant que la musique est lancée (y compris pendant le fondu de sortie).
+    music_playing: bool,
+    /// Niveau du fondu de la musique, de 0 (silence) à 1 (volume du canal).
+    music_fade: f32,
+    /// Volumes relatifs des couches de la musique.
+    layers: MusicLayers,
+    /// Heure jusqu'à laquelle le réacteur est considéré comme allumé.
+    thrust_until: f64,
+    /// `true` tant que la boucle du réacteur est jouée.
+    thrust_playing: bool,
+    /// Intervalle entre deux battements, ou `None` si le rythme de fond est arrêté.
+    heartbeat_interval: Option<f64>,
+    /// Position de l'auditeur (le vaisseau), ou `None` pour jouer tous les sons au centre.
+    listener: Option<Vec2>,
+    /// Joueur dont le vaisseau est l'auditeur : ses propres tirs sont joués au centre, ceux
+    /// des autres joueurs du côté où ils ont lieu.
+    listening_player: Option<usize>,
+    /// Taille de l'arène dans laquelle se trouve l'auditeur.
+    arena: Vec2,
+    /// Heure du prochain battement.
+    next_beat: f64,
+    /// Note du prochain battement (0 = basse, 1 = haute).
+    beat_note: usize,
+}
+
+impl AudioMixer {
+    /// Crée le gestionnaire audio avec les volumes des préférences.
+    ///
+    /// # Arguments
+    ///
+    /// * `sounds` - Les sons chargés au démarrage.
+    /// * `settings` - Les préférences du joueur.
+    ///
+    /// # Retour
+    ///
+    /// Une instance d'`AudioMixer`, sans musique en cours.
+    pub fn new(sounds: Sounds, settings: &Settings) -> Self {
+        Self {
+            sounds,
+            master_volume: settings.master_volume,
+            music_volume: settings.music_volume,
+            sfx_volume: settings.sfx_volume,
+            muted: settings.muted,
+            synth_sounds: settings.synth_sounds,
+            voices: VoiceLimiter::default(),
+            music_wanted: false,
+            music_playing: false,
+            music_fade: 0.,
+            layers: MusicLayers::default(),
+            thrust_until: 0.,
+            thrust_playing: false,
+            heartbeat_interval: None,
+            listener: None,
+            listening_player: None,
+            arena: Vec2::ONE,
+            next_beat: 0.,
+            beat_note: 0,
+        }
+    }
+
+    /// Change les volumes et la sourdine ; le volume de la musique en cours est mis à jour
+    /// immédiatement.
+    ///
+    /// # Arguments
+    ///
+    /// * `master` - Volume général (0.0 à 1.0).
+    /// * `music` - Volume de la musique (0.0 à 1.0).
+    /// * `sfx` - Volume des effets (0.0 à 1.0).
+    /// * `muted` - `true` pour couper tous les sons.
+    pub fn set_volumes(&mut self, master: f32, music: f32, sfx: f32, muted: bool) {
+        self.master_volume = master;
+        self.music_volume = music;
+        self.sfx_volume = sfx;
+        self.muted = muted;
+        if self.music_playing {
+            self.apply_music_volumes();
+        }
+        if self.thrust_playing {
+            set_sound_volume(&self.sounds.thrust, self.channel_volume(self.sfx_volume));
+        }
+    }
+
+    /// Retourne le volume effectif d'un canal.
+    fn channel_volume(&self, channel: f32) -> f32 {
+        if self.muted {
+            0.
+        } else {
+            self.master_volume * channel
+        }
+    }
+
+    /// Applique à chaque couche de la musique son volume actuel (canal, fondu et couche).
+    fn apply_music_volumes(&self) {
+        let volume = self.channel_volume(self.music_volume) * self.music_fade;
+        for (sound, level) in self.sounds.music.iter().zip(self.layers.get_levels()) {
+            set_sound_volume(sound, volume * level);
+        }
+    }
+
+    /// Retourne le son spatialisé associé à un effet, s'il en a un.
+    fn panned_sound(&self, effect: SoundEffect) -> Option<&PannedSound> {
+        match effect {
+            SoundEffect::AsteroidHit => Some(&self.sounds.asteroid_hit),
+            SoundEffect::Explosion(level) => {
+                Some(&self.sounds.explosions[(level.clamp(1, 3) - 1) as usize])
+            }
+            SoundEffect::ShipHit => Some(&self.sounds.ship_hit),
+            _ => None,
+        }
+    }
+
+    /// Retourne le son associé à un effet, à la position stéréo demandée.
+    fn sound(&self, effect: SoundEffect, pan: f32) -> &Sound {
+        if let Some(panned) = self.panned_sound(effect) {
+            return panned.get(pan);
+        }
+        match effect {
+            SoundEffect::Shoot if self.synth_sounds => &self.sounds.laser,
+            SoundEffect::Lose => &self.sounds.lose,
+            SoundEffect::Win => &self.sounds.win,
+            _ => &self.sounds.shoot,
+        }
+    }
+
+    /// Change la position de l'auditeur, à partir de laquelle les sons sont spatialisés.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - La position du vaisseau, ou `None` hors partie.
+    /// * `player` - Le joueur de ce vaisseau, ou `None` si l'auditeur est entre plusieurs
+    ///   vaisseaux : tous les tirs sont alors spatialisés.
+    /// * `arena` - La taille de l'arène.
+    pub fn set_listener(&mut self, listener: Option<Vec2>, player: Option<usize>, arena: Vec2) {
+        self.listener = listener;
+        self.listening_player = player;
+        self.arena = arena;
+    }
+
+    /// Joue un effet sonore, sauf si le nombre maximal d'exemplaires simultanés est atteint.
+    ///
+    /// # Arguments
+    ///
+    /// * `effect` - L'effet à jouer.
+    /// * `now` - L'heure actuelle (`get_time()`).
+    /// * `position` - La position de l'événement, pour spatialiser le son, ou `None` pour
+    ///   le jouer au centre.
+    ///
+    /// # Retour
+    ///
+    /// `true` si l'effet a été joué.
+    pub fn play(&mut self, effect: SoundEffect, now: f64, position: Option<Vec2>) -> bool {
+        if !self.voices.try_start(effect, now) {
+            return false;
+        }
+        let (pan, gain) = match (position, self.listener) {
+            (Some(source), Some(listener)) => spatialize(source, listener, self.arena),
+            _ => (0., 1.),
+        };
+        crate::play_game_sound(
+            self.sound(effect, pan),
+            false,
+            self.channel_volume(self.sfx_volume) * gain,
+        );
+        true
+    }
+
+    /// Arrête tous les exemplaires d'un effet.
+    ///
+    /// # Arguments
+    ///
+    /// * `effect` - L'effet à arrêter.
+    pub fn stop(&mut self, effect: SoundEffect) {
+        match self.panned_sound(effect) {
+            Some(panned) => panned.stop(),
+            None => stop_sound(self.sound(effect, 0.)),
+        }
+        self.voices.release(effect);
+    }
+
+    /// Lance la musique avec un fondu d'entrée (sans effet si elle est déjà demandée).
+    pub fn start_music(&mut self) {
+        self.music_wanted = true;
+        if !self.music_playing {
+            self.music_playing = true;
+            self.music_fade = 0.;
+            // Toutes les couches démarrent ensemble pour rester synchronisées
+            for sound in &self.sounds.music {
+                crate::play_game_sound(sound, true, 0.);
+            }
+        }
+    }
+
+    /// Arrête la musique avec un fondu de sortie.
+    pub fn stop_music(&mut self) {
+        self.music_wanted = false;
+    }
+
+    /// Change l'intensité de la partie, que les couches de la musique suivent en fondu.
+    ///
+    /// # Arguments
+    ///
+    /// * `intensity` - L'intensité, de 0.0 à 1.0 (voir `music_intensity`).
+    pub fn set_music_intensity(&mut self, intensity: f32) {
+        self.layers.set_intensity(intensity);
+    }
+
+    /// Règle le rythme de fond qui accompagne la partie.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - L'intervalle entre deux battements, en secondes, ou `None` pour
+    ///   arrêter le rythme (hors partie).
+    pub fn set_heartbeat(&mut self, interval: Option<f64>) {
+        self.heartbeat_interval = interval;
+    }
+
+    /// Démarre ou arrête la boucle du réacteur, et joue les battements du rythme de fond.
+    fn update_effects(&mut self, now: f64) {
+        if self.thrust_playing && now > self.thrust_until {
+            stop_sound(&self.sounds.thrust);
+            self.thrust_playing = false;
+        } else if !self.thrust_playing && now <= self.thrust_until {
+            crate::play_game_sound(
+                &self.sounds.thrust,
+                true,
+                self.channel_volume(self.sfx_volume),
+            );
+            self.thrust_playing = true;
+        }
+
+        match self.heartbeat_interval {
+            Some(interval) if now >= self.next_beat => {
+                crate::play_game_sound(
+                    &self.sounds.heartbeat[self.beat_note],
+                    false,
+                    self.channel_volume(self.sfx_volume),
+                );
+                self.beat_note = 1 - self.beat_note;
+                self.next_beat = now + interval;
+            }
+            Some(interval) => {
+                // Le rythme accélère : rapprocher le prochain battement sans attendre
+                self.next_beat = self.next_beat.min(now + interval);
+            }
+            None => self.next_beat = now,
+        }
+    }
+
+    /// Fait avancer le fondu de la musique (la musique est arrêtée à la fin du fondu de
+    /// sortie) et de ses couches, la boucle du réacteur et le rythme de fond.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - Durée de la frame, en secondes.
+    pub fn update(&mut self, dt: f32) {
+        self.update_effects(get_time());
+        if !self.music_playing {
+            return;
+        }
+        self.layers.update(dt);
+        let step = dt / MUSIC_FADE_DURATION;
+        if self.music_wanted {
+            self.music_fade = (self.music_fade + step).min(1.);
+        } else {
+            self.music_fade = (self.music_fade - step).max(0.);
+            if self.music_fade == 0. {
+                for sound in &self.sounds.music {
+                    stop_sound(sound);
+                }
+                self.music_playing = false;
+                return;
+            }
+        }
+        self.apply_music_volumes();
+    }
+}
+
+impl EventListener for AudioMixer {
+    fn on_event(&mut self, event: &GameEvent) {
+        let (effect, position) = match *event {
+            GameEvent::MissileFired { player, position } => {
+                let own = self.listening_player == Some(player);
+                (SoundEffect::Shoot, (!own).then_some(position))
+            }
+            GameEvent::AsteroidDestroyed {
+                level, position, ..
+            } if self.synth_sounds => (SoundEffect::Explosion(level), Some(position)),
+            GameEvent::AsteroidDestroyed { position, .. } => {
+                (SoundEffect::AsteroidHit, Some(position))
+            }
+            GameEvent::ShipThrust { .. } => {
+                self.thrust_until = get_time() + THRUST_HOLD;
+                return;
+            }
+            GameEvent::ShipHit { impact, .. } => (SoundEffect::ShipHit, Some(impact)),
+            GameEvent::GameOver { victory: true } => (SoundEffect::Win, None),
+            GameEvent::GameOver { victory: false } => (SoundEffect::Lose, None),
+            _ => return,
+        };
+        self.play(effect, get_time(), position);
+    }
+}
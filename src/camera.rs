@@ -0,0 +1,149 @@
+use macroquad::prelude::*;
+
+/// Hauteur de l'arène, en unités du monde, quel que soit son format.
+pub const ARENA_HEIGHT: f32 = 600.;
+
+/// Format de l'arène : le rapport entre sa largeur et sa hauteur.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArenaAspect {
+    /// 4:3, l'arène de 800 × 600 d'origine.
+    Standard,
+    /// 16:9, pour les écrans larges.
+    Wide,
+    /// 1:1.
+    Square,
+}
+
+impl ArenaAspect {
+    /// Les formats proposés.
+    pub const ALL: [ArenaAspect; 3] = [
+        ArenaAspect::Standard,
+        ArenaAspect::Wide,
+        ArenaAspect::Square,
+    ];
+
+    /// Retourne l'identifiant du format utilisé dans le fichier de préférences.
+    pub fn key(self) -> &'static str {
+        match self {
+            ArenaAspect::Standard => "4:3",
+            ArenaAspect::Wide => "16:9",
+            ArenaAspect::Square => "1:1",
+        }
+    }
+
+    /// Retrouve un format à partir de son identifiant.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - L'identifiant lu dans le fichier de préférences.
+    ///
+    /// # Retour
+    ///
+    /// Le format correspondant, ou `None` si l'identifiant est inconnu.
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|aspect| aspect.key() == key)
+    }
+
+    /// Retourne la taille de l'arène dans ce format : `ARENA_HEIGHT` de haut, et une largeur
+    /// arrondie à l'unité.
+    pub fn get_size(self) -> Vec2 {
+        let (width, height) = match self {
+            ArenaAspect::Standard => (4., 3.),
+            ArenaAspect::Wide => (16., 9.),
+            ArenaAspect::Square => (1., 1.),
+        };
+        vec2((ARENA_HEIGHT * width / height).round(), ARENA_HEIGHT)
+    }
+}
+
+/// Caméra qui affiche toute l'arène dans la fenêtre.
+///
+/// L'arène garde sa taille en unités du monde : la simulation est donc la même à toutes les
+/// résolutions. La caméra l'agrandit autant que la fenêtre le permet sans la déformer, et la
+/// centre ; les bandes qui restent sur les côtés ou en haut et en bas sont noires.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ArenaCamera {
+    arena: Vec2,
+    screen: Vec2,
+    /// Nombre de pixels par unité du monde.
+    scale: f32,
+    /// Position à l'écran du coin supérieur gauche de l'arène.
+    offset: Vec2,
+}
+
+impl ArenaCamera {
+    /// Crée la caméra d'une arène pour une taille de fenêtre.
+    ///
+    /// # Arguments
+    ///
+    /// * `arena` - La taille de l'arène, en unités du monde.
+    /// * `screen` - La taille de la fenêtre, en pixels.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `ArenaCamera`.
+    pub fn new(arena: Vec2, screen: Vec2) -> Self {
+        let scale = (screen.x / arena.x).min(screen.y / arena.y);
+        Self {
+            arena,
+            screen,
+            scale,
+            offset: (screen - arena * scale) / 2.,
+        }
+    }
+
+    /// Crée la caméra d'une arène pour la fenêtre actuelle.
+    ///
+    /// # Arguments
+    ///
+    /// * `arena` - La taille de l'arène, en unités du monde.
+    pub fn for_screen(arena: Vec2) -> Self {
+        Self::new(arena, vec2(screen_width(), screen_height()))
+    }
+
+    /// Retourne le rectangle de la fenêtre occupé par l'arène, en pixels.
+    pub fn get_viewport(&self) -> Rect {
+        Rect::new(
+            self.offset.x,
+            self.offset.y,
+            self.arena.x * self.scale,
+            self.arena.y * self.scale,
+        )
+    }
+
+    /// Convertit une position de l'arène en position à l'écran.
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - La position, en unités du monde.
+    ///
+    /// # Retour
+    ///
+    /// La position à l'écran, en pixels.
+    pub fn world_to_screen(&self, point: Vec2) -> Vec2 {
+        self.offset + point * self.scale
+    }
+
+    /// Retourne la caméra de macroquad correspondante, pour dessiner en unités du monde.
+    pub fn to_camera(self) -> Camera2D {
+        Camera2D {
+            target: self.arena / 2.,
+            zoom: vec2(
+                2. * self.scale / self.screen.x,
+                2. * self.scale / self.screen.y,
+            ),
+            ..Default::default()
+        }
+    }
+
+    /// Dessine en noir les bandes de la fenêtre qui sont hors de l'arène, par-dessus les
+    /// objets qui débordent de ses bords. À appeler avec la caméra par défaut.
+    pub fn draw_letterbox(&self) {
+        let viewport = self.get_viewport();
+        let (right, bottom) = (viewport.right(), viewport.bottom());
+        draw_rectangle(0., 0., self.screen.x, viewport.y, BLACK);
+        draw_rectangle(0., bottom, self.screen.x, self.screen.y - bottom, BLACK);
+        draw_rectangle(0., 0., viewport.x, self.screen.y, BLACK);
+        draw_rectangle(right, 0., self.screen.x - right, self.screen.y, BLACK);
+    }
+}
@@ -0,0 +1,438 @@
+use crate::camera::ArenaCamera;
+use crate::controls::{self, ControlScheme, Gamepads};
+use crate::net::{Message, Snapshot, MAX_DATAGRAM, PROTOCOL_VERSION, TICK_RATE};
+use crate::pacing::{FixedStep, FramePacer};
+use crate::players::PLAYER_COLORS;
+use crate::settings::Settings;
+use crate::stellarobject::StellarObject;
+use crate::text::{draw_ui_text, measure_ui_text};
+use crate::vaisseau::{ShipInput, Vaisseau};
+use crate::world::World;
+use crate::Textures;
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind};
+use std::net::{ToSocketAddrs, UdpSocket};
+
+/// Retard de l'affichage des autres objets, en secondes : le client dessine l'état de la
+/// partie tel qu'il était il y a `INTERPOLATION_DELAY`, entre deux instantanés déjà reçus.
+const INTERPOLATION_DELAY: f64 = 0.1;
+
+/// Nombre de commandes envoyées dans chaque trame d'entrée (la nouvelle et les précédentes).
+const INPUT_REDUNDANCY: usize = 4;
+
+/// Nombre maximal de commandes en attente d'acquittement.
+const MAX_PENDING_INPUTS: usize = 120;
+
+/// Nombre d'instantanés gardés pour l'interpolation.
+const MAX_SNAPSHOTS: usize = 32;
+
+/// Nombre de pas entre deux demandes de connexion tant que le serveur n'a pas répondu.
+const HELLO_INTERVAL: u32 = 30;
+
+/// Client d'une partie en réseau.
+///
+/// Le client envoie une trame d'entrée par pas de simulation et reçoit les instantanés du
+/// serveur. Son propre vaisseau est prédit : les commandes sont appliquées immédiatement,
+/// puis rejouées sur chaque état reçu à partir de la dernière commande acquittée. Les
+/// autres objets sont interpolés entre deux instantanés.
+pub struct Client {
+    socket: UdpSocket,
+    player: Option<usize>,
+    arena: Vec2,
+    refusal: Option<String>,
+    sequence: u32,
+    hello_ticks: u32,
+    pending: VecDeque<(u32, ShipInput)>,
+    snapshots: VecDeque<(f64, Snapshot)>,
+    predicted: Option<Vaisseau>,
+}
+
+impl Client {
+    /// Ouvre un port local et demande à rejoindre la partie d'un serveur.
+    ///
+    /// # Arguments
+    ///
+    /// * `server` - L'adresse du serveur.
+    ///
+    /// # Retour
+    ///
+    /// Le client, ou une erreur d'entrée/sortie si l'adresse est invalide ou injoignable.
+    pub fn connect(server: impl ToSocketAddrs) -> io::Result<Self> {
+        let server = server
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "adresse introuvable"))?;
+        let local = if server.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(server)?;
+        socket.set_nonblocking(true)?;
+        let client = Self {
+            socket,
+            player: None,
+            arena: Vec2::ZERO,
+            refusal: None,
+            sequence: 0,
+            hello_ticks: 0,
+            pending: VecDeque::new(),
+            snapshots: VecDeque::new(),
+            predicted: None,
+        };
+        client.send(&Message::Hello {
+            version: PROTOCOL_VERSION,
+        })?;
+        Ok(client)
+    }
+
+    /// Retourne le joueur attribué par le serveur.
+    ///
+    /// # Retour
+    ///
+    /// Le numéro du joueur, ou `None` tant que le serveur n'a pas répondu.
+    pub fn get_player(&self) -> Option<usize> {
+        self.player
+    }
+
+    /// Retourne la taille de l'arène du serveur (nulle tant qu'il n'a pas répondu).
+    pub fn get_arena(&self) -> Vec2 {
+        self.arena
+    }
+
+    /// Retourne la raison pour laquelle le serveur a refusé le client, s'il l'a refusé.
+    pub fn get_refusal(&self) -> Option<&str> {
+        self.refusal.as_deref()
+    }
+
+    /// Retourne le dernier instantané reçu.
+    pub fn get_latest(&self) -> Option<&Snapshot> {
+        self.snapshots.back().map(|(_, snapshot)| snapshot)
+    }
+
+    /// Retourne le vaisseau du joueur tel que le client le prédit.
+    ///
+    /// # Retour
+    ///
+    /// Le vaisseau prédit, ou `None` si le joueur n'a pas de vaisseau en jeu.
+    pub fn get_predicted(&self) -> Option<&Vaisseau> {
+        self.predicted.as_ref()
+    }
+
+    /// Envoie les commandes du joueur pour le prochain pas de simulation et les applique
+    /// aussitôt au vaisseau prédit. Tant que le serveur n'a pas répondu, la demande de
+    /// connexion est renvoyée régulièrement à la place.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Les commandes du joueur.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur d'entrée/sortie si l'envoi a échoué.
+    pub fn send_input(&mut self, input: ShipInput) -> io::Result<()> {
+        if self.player.is_none() {
+            self.hello_ticks += 1;
+            if self.hello_ticks.is_multiple_of(HELLO_INTERVAL) {
+                self.send(&Message::Hello {
+                    version: PROTOCOL_VERSION,
+                })?;
+            }
+            return Ok(());
+        }
+
+        self.sequence += 1;
+        self.pending.push_back((self.sequence, input));
+        if self.pending.len() > MAX_PENDING_INPUTS {
+            self.pending.pop_front();
+        }
+        if let Some(vaisseau) = &mut self.predicted {
+            vaisseau.set_input(input);
+            vaisseau.update_position(self.arena);
+        }
+
+        let inputs = self
+            .pending
+            .iter()
+            .rev()
+            .take(INPUT_REDUNDANCY)
+            .map(|&(_, input)| input)
+            .collect();
+        self.send(&Message::Input {
+            sequence: self.sequence,
+            inputs,
+        })
+    }
+
+    /// Lit tous les messages reçus du serveur depuis le dernier appel.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - L'heure actuelle en secondes, associée aux instantanés reçus.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur d'entrée/sortie si la lecture a échoué.
+    pub fn poll(&mut self, now: f64) -> io::Result<()> {
+        let mut buffer = vec![0; MAX_DATAGRAM];
+        loop {
+            let length = match self.socket.recv(&mut buffer) {
+                Ok(length) => length,
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return Ok(()),
+                // Le serveur n'est pas (encore) ouvert : la demande de connexion sera renvoyée
+                Err(error) if error.kind() == ErrorKind::ConnectionRefused => return Ok(()),
+                Err(error) => return Err(error),
+            };
+            let text = String::from_utf8_lossy(&buffer[..length]);
+            match Message::decode(&text) {
+                Ok(Message::Welcome { player, arena }) => {
+                    self.player = Some(player);
+                    self.arena = arena;
+                }
+                Ok(Message::Refused { reason }) => self.refusal = Some(reason),
+                Ok(Message::Snapshot(snapshot)) => self.receive_snapshot(now, snapshot),
+                Ok(_) => {}
+                Err(error) => eprintln!("Paquet du serveur ignoré : {}", error),
+            }
+        }
+    }
+
+    /// Garde un instantané pour l'interpolation et corrige le vaisseau prédit.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - L'heure de réception.
+    /// * `snapshot` - L'instantané reçu.
+    fn receive_snapshot(&mut self, now: f64, snapshot: Snapshot) {
+        // Un instantané arrivé en retard est ignoré
+        if self
+            .get_latest()
+            .is_some_and(|latest| latest.tick >= snapshot.tick)
+        {
+            return;
+        }
+        if let Some(player) = self.player {
+            // Repartir de l'état du serveur et rejouer les commandes qu'il n'a pas encore appliquées
+            self.pending.retain(|&(number, _)| number > snapshot.ack);
+            self.predicted = snapshot
+                .ships
+                .iter()
+                .find(|ship| ship.player == player)
+                .map(|ship| {
+                    let mut vaisseau = ship.to_vaisseau();
+                    for &(_, input) in &self.pending {
+                        vaisseau.set_input(input);
+                        vaisseau.update_position(self.arena);
+                    }
+                    vaisseau
+                });
+        }
+        self.snapshots.push_back((now, snapshot));
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Met le monde à afficher dans l'état de la partie : les objets interpolés avec un retard
+    /// de `INTERPOLATION_DELAY`, et le vaisseau du joueur tel qu'il est prédit.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - L'heure actuelle en secondes.
+    /// * `world` - Le monde à mettre à jour.
+    ///
+    /// # Retour
+    ///
+    /// `false` si aucun instantané n'a encore été reçu.
+    pub fn view(&self, now: f64, world: &mut World) -> bool {
+        let render_time = now - INTERPOLATION_DELAY;
+        let next = self
+            .snapshots
+            .iter()
+            .position(|&(time, _)| time > render_time);
+        let snapshot = match next {
+            Some(index) if index > 0 => {
+                let (start, previous) = &self.snapshots[index - 1];
+                let (end, next) = &self.snapshots[index];
+                let t = (render_time - start) / (end - start).max(f64::EPSILON);
+                previous.interpolate(next, t as f32, self.arena)
+            }
+            Some(index) => self.snapshots[index].1.clone(),
+            None => match self.snapshots.back() {
+                Some((_, latest)) => latest.clone(),
+                None => return false,
+            },
+        };
+        snapshot.restore(world);
+        if let Some(player) = self.player {
+            world
+                .ships
+                .retain(|vaisseau| vaisseau.get_player() != player);
+            if let Some(vaisseau) = self.get_predicted() {
+                world.ships.push(vaisseau.clone());
+            }
+        }
+        true
+    }
+
+    /// Prévient le serveur que le joueur quitte la partie.
+    pub fn disconnect(&self) {
+        let _ = self.send(&Message::Bye);
+    }
+
+    /// Envoie un message au serveur.
+    fn send(&self, message: &Message) -> io::Result<()> {
+        match self.socket.send(message.encode().as_bytes()) {
+            Err(error)
+                if matches!(
+                    error.kind(),
+                    ErrorKind::WouldBlock | ErrorKind::ConnectionRefused
+                ) =>
+            {
+                Ok(())
+            }
+            result => result.map(|_| ()),
+        }
+    }
+}
+
+/// Boucle d'une partie en réseau côté client, dans la fenêtre du jeu : les commandes sont
+/// lues au clavier avec les touches du joueur 1, à la première manette et à la souris ou à
+/// l'écran tactile selon les préférences, et la touche de pause quitte la partie.
+///
+/// # Arguments
+///
+/// * `address` - L'adresse du serveur.
+/// * `textures` - Les textures chargées au démarrage.
+/// * `settings` - Les préférences du joueur.
+pub async fn play(address: &str, textures: Textures, settings: Settings) {
+    let mut client = match Client::connect(address) {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("Impossible de joindre le serveur {} : {}", address, error);
+            return;
+        }
+    };
+    let keys = settings.keys;
+    let mut gamepads = Gamepads::new();
+    let mut world = World::new(settings.arena_aspect);
+    world.ships.clear();
+    let mut fixed_step = FixedStep::new(TICK_RATE);
+    let mut welcomed = false;
+    let touch = settings.control_scheme == ControlScheme::Touch;
+    let mut pacer = FramePacer::new(settings.frame_limit, settings.show_frame_graph);
+    prevent_quit();
+
+    loop {
+        gamepads.update();
+        pacer.update();
+        if is_key_pressed(keys.pause)
+            || gamepads.is_pressed(0, settings.pad_buttons.pause)
+            || (touch && controls::is_virtual_pause_pressed())
+            || is_quit_requested()
+        {
+            client.disconnect();
+            break;
+        }
+        let now = get_time();
+        if let Err(error) = client.poll(now) {
+            eprintln!("Connexion au serveur perdue : {}", error);
+            break;
+        }
+        if !welcomed && client.get_player().is_some() {
+            // La caméra affiche l'arène du serveur, quel que soit son format
+            welcomed = true;
+            world.arena = client.get_arena();
+        }
+        let camera = ArenaCamera::for_screen(world.arena);
+
+        // Une trame d'entrée par pas de simulation du serveur
+        for _ in 0..fixed_step.advance(get_frame_time() as f64) {
+            let ship = client
+                .get_predicted()
+                .map_or(Vec2::ZERO, |vaisseau| vaisseau.get_position());
+            let ship = camera.world_to_screen(ship);
+            let input = gamepads.read_input(0, &keys, ship, &settings);
+            if let Err(error) = client.send_input(input) {
+                eprintln!("Envoi impossible : {}", error);
+            }
+        }
+
+        crate::draw_background(&textures.sprite_background);
+        if client.view(now, &mut world) {
+            set_camera(&camera.to_camera());
+            for vaisseau in &world.ships {
+                crate::draw_vaisseau(vaisseau, &textures.sprite_vaisseau, world.hauteur_vaisseau);
+            }
+            crate::draw_missiles(
+                &world.missiles,
+                world.rayon_missile,
+                &textures.sprite_meteor,
+            );
+            crate::draw_asteroids_batched(
+                &mut world.asteroids,
+                world.level_size,
+                [
+                    &textures.sprite_asteroid_1,
+                    &textures.sprite_asteroid_2,
+                    &textures.sprite_asteroid_3,
+                ],
+            );
+            set_default_camera();
+            camera.draw_letterbox();
+            draw_network_hud(&client, &world);
+            if touch {
+                controls::draw_virtual_buttons();
+            }
+        } else {
+            let text = match client.get_refusal() {
+                Some(reason) => format!("Connexion refusée : {}", reason),
+                None => format!("Connexion à {}...", address),
+            };
+            let width = measure_ui_text(&text, 30).width;
+            draw_ui_text(
+                &text,
+                screen_width() / 2. - width / 2.,
+                screen_height() / 2.,
+                30.,
+                WHITE,
+            );
+        }
+        pacer.draw();
+        next_frame().await;
+        pacer.end_frame();
+    }
+}
+
+/// Affiche la vague, puis le score et le bouclier de chaque joueur en jeu, dans sa couleur.
+///
+/// # Arguments
+///
+/// * `client` - Le client, pour les scores du dernier instantané.
+/// * `world` - Le monde affiché.
+fn draw_network_hud(client: &Client, world: &World) {
+    let Some(snapshot) = client.get_latest() else {
+        return;
+    };
+    draw_ui_text(&format!("Vague: {}", world.wave), 10., 30., 30., WHITE);
+    let mut y = 70.;
+    for vaisseau in &world.ships {
+        let player = vaisseau.get_player();
+        let mut text = format!(
+            "J{} : {} pts, bouclier {:.0}",
+            player + 1,
+            snapshot.scores[player],
+            vaisseau.get_shield().max(0.)
+        );
+        if snapshot.frags[player] != 0 {
+            text += &format!(", {} frags", snapshot.frags[player]);
+        }
+        if Some(player) == client.get_player() {
+            text += " (vous)";
+        }
+        draw_ui_text(&text, 10., y, 25., PLAYER_COLORS[player]);
+        y += 30.;
+    }
+}
@@ -1,36 +1,202 @@
+use crate::controls::ControlScheme;
+use crate::particles::ParticleQuality;
+use crate::settings::Settings;
+use crate::text::draw_ui_text;
+use crate::versus::VersusRules;
 use macroquad::prelude::*;
 
 /// Structure représentant l'écran de configuration du jeu.
 pub struct ConfigScreen {
-    asteroid_count: i32,         // Nombre d'astéroïdes
-    asteroid_speed: f32,         // Vitesse des astéroïdes
-    slider_width: f32,           // Largeur des sliders
-    button_size: Vec2,           // Taille des boutons
-    end_message: Option<String>, // Message de fin (optionnel)
+    asteroid_count: i32,               // Nombre d'astéroïdes
+    asteroid_speed: f32,               // Vitesse des astéroïdes
+    slider_width: f32,                 // Largeur des sliders
+    button_size: Vec2,                 // Taille des boutons
+    particle_quality: ParticleQuality, // Qualité des effets de particules
+    control_scheme: ControlScheme,     // Schéma de contrôle du premier joueur
+    save_available: bool,              // Vrai si une partie sauvegardée peut être reprise
+    volumes: [f32; 3],                 // Volumes général, musique et effets (0.0 à 1.0)
+    muted: bool,                       // Sourdine
+    players: usize,                    // Nombre de vaisseaux dans l'arène (1 à 4)
+    versus: bool,                      // Les joueurs s'affrontent au lieu de coopérer
+    shared_lives: bool,                // Vies communes en coopération
+    friendly_fire: bool,               // Tir ami en coopération
+    versus_rules: VersusRules,         // Limites de frags et de temps en versus
 }
 
+/// Noms affichés des sliders de volume, dans l'ordre du tableau `volumes`.
+const VOLUME_LABELS: [&str; 3] = ["Volume général", "Musique", "Effets"];
+
+/// Largeur des sliders de volume (colonne de gauche).
+const VOLUME_SLIDER_WIDTH: f32 = 160.0;
+
+/// Configurations proposées par le bouton des joueurs, dans l'ordre : nombre de joueurs et
+/// `true` pour un match en versus.
+const MATCH_TYPES: [(usize, bool); 5] = [(1, false), (2, false), (2, true), (3, true), (4, true)];
+
+/// Limites de frags proposées en versus.
+const FRAG_LIMITS: [u32; 5] = [5, 10, 15, 20, 30];
+
+/// Durées de match proposées en versus, en secondes (0 : pas de limite).
+const TIME_LIMITS: [u32; 5] = [120, 180, 300, 600, 0];
+
 impl ConfigScreen {
-    /// Crée un nouvel écran de configuration avec les valeurs par défaut.
+    /// Crée un nouvel écran de configuration à partir des préférences du joueur.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - Les préférences chargées au démarrage.
     ///
     /// # Retour
     ///
-    /// Une instance de `ConfigScreen` initialisée avec des valeurs par défaut.
-    pub fn new() -> Self {
+    /// Une instance de `ConfigScreen` initialisée avec les valeurs des préférences.
+    pub fn new(settings: &Settings) -> Self {
         ConfigScreen {
-            asteroid_count: 10,  // Valeur par défaut pour le nombre d'astéroïdes
-            asteroid_speed: 1.0, // Valeur par défaut pour la vitesse des astéroïdes
-            slider_width: 300.0, // Largeur par défaut des sliders
-            button_size: Vec2::new(200.0, 50.0), // Taille par défaut des boutons
-            end_message: None,   // Aucun message de fin par défaut
+            asteroid_count: settings.asteroid_count, // Nombre d'astéroïdes enregistré
+            asteroid_speed: settings.asteroid_speed, // Vitesse des astéroïdes enregistrée
+            slider_width: 300.0,                     // Largeur par défaut des sliders
+            button_size: Vec2::new(200.0, 50.0),     // Taille par défaut des boutons
+            particle_quality: settings.particle_quality, // Qualité des particules enregistrée
+            control_scheme: settings.control_scheme,
+            save_available: false,
+            volumes: [
+                settings.master_volume,
+                settings.music_volume,
+                settings.sfx_volume,
+            ],
+            muted: settings.muted,
+            players: settings.players,
+            versus: settings.versus,
+            shared_lives: settings.shared_lives,
+            friendly_fire: settings.friendly_fire,
+            versus_rules: VersusRules {
+                frag_limit: settings.frag_limit,
+                time_limit: settings.time_limit,
+            },
         }
     }
 
+    /// Recopie les valeurs choisies sur l'écran dans les préférences du joueur.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - Les préférences à mettre à jour.
+    pub fn store_settings(&self, settings: &mut Settings) {
+        settings.asteroid_count = self.asteroid_count;
+        settings.asteroid_speed = self.asteroid_speed;
+        settings.particle_quality = self.particle_quality;
+        settings.control_scheme = self.control_scheme;
+        settings.master_volume = self.volumes[0];
+        settings.music_volume = self.volumes[1];
+        settings.sfx_volume = self.volumes[2];
+        settings.muted = self.muted;
+        settings.players = self.players;
+        settings.versus = self.versus;
+        settings.shared_lives = self.shared_lives;
+        settings.friendly_fire = self.friendly_fire;
+        settings.frag_limit = self.versus_rules.frag_limit;
+        settings.time_limit = self.versus_rules.time_limit;
+    }
+
     /// Met à jour l'état de l'écran de configuration en fonction des interactions de l'utilisateur.
+    ///
+    /// Cette méthode ne dessine rien : les sliders sont dessinés par `draw`.
     pub fn update(&mut self) {
         // Définir la position du slider pour le nombre d'astéroïdes
         let asteroid_slider_x = screen_width() * 0.5 - self.slider_width / 2.0;
         let asteroid_slider_y = screen_height() * 0.4;
 
+        // Détecter si la souris est au-dessus du slider pour le nombre d'astéroïdes
+        let mouse_pos = mouse_position();
+        let is_mouse_on_asteroid_slider = mouse_pos.0 >= asteroid_slider_x
+            && mouse_pos.0 <= asteroid_slider_x + self.slider_width
+            && (mouse_pos.1 - asteroid_slider_y).abs() <= 10.0;
+
+        // Interaction avec le slider pour ajuster le nombre d'astéroïdes
+        if is_mouse_button_down(MouseButton::Left) && is_mouse_on_asteroid_slider {
+            let mouse_x = mouse_position().0;
+            if mouse_x >= asteroid_slider_x && mouse_x <= asteroid_slider_x + self.slider_width {
+                self.asteroid_count =
+                    ((mouse_x - asteroid_slider_x) / self.slider_width * (25. - 1.) + 1.) as i32;
+                self.asteroid_count = self.asteroid_count.clamp(1, 25); // Limiter à 1-25 astéroïdes
+            }
+        }
+
+        // Définir la position du slider pour la vitesse des astéroïdes
+        let speed_slider_x = screen_width() * 0.5 - self.slider_width / 2.0;
+        let speed_slider_y = screen_height() * 0.5 + 50.0; // Position un peu plus bas
+
+        // Détecter si la souris est au-dessus du slider pour la vitesse des astéroïdes
+        let is_mouse_on_speed_slider = mouse_pos.0 >= speed_slider_x
+            && mouse_pos.0 <= speed_slider_x + self.slider_width
+            && (mouse_pos.1 - speed_slider_y).abs() <= 10.0;
+
+        // Interaction avec le slider pour ajuster la vitesse des astéroïdes
+        if is_mouse_button_down(MouseButton::Left) && is_mouse_on_speed_slider {
+            let mouse_x = mouse_position().0;
+            if mouse_x >= speed_slider_x && mouse_x <= speed_slider_x + self.slider_width {
+                self.asteroid_speed = ((mouse_x - speed_slider_x) / self.slider_width * (5. - 0.3)
+                    + 0.3)
+                    .clamp(0.3, 5.0); // Limiter la vitesse entre 0.3 et 5.0
+            }
+        }
+
+        // Changer la qualité des particules à chaque clic sur le bouton
+        if self.is_particles_pressed() {
+            self.particle_quality = self.particle_quality.next();
+        }
+
+        // Interaction avec les sliders de volume de la colonne de gauche
+        if is_mouse_button_down(MouseButton::Left) {
+            for index in 0..self.volumes.len() {
+                let slider = self.volume_slider_position(index);
+                if mouse_pos.0 >= slider.x
+                    && mouse_pos.0 <= slider.x + VOLUME_SLIDER_WIDTH
+                    && (mouse_pos.1 - slider.y).abs() <= 10.0
+                {
+                    self.volumes[index] =
+                        ((mouse_pos.0 - slider.x) / VOLUME_SLIDER_WIDTH).clamp(0.0, 1.0);
+                }
+            }
+        }
+
+        // Passer au schéma de contrôle suivant à chaque clic sur le bouton
+        if self.is_controls_pressed() {
+            self.control_scheme = self.control_scheme.next();
+        }
+
+        // Activer ou couper la sourdine à chaque clic sur le bouton
+        if self.is_mute_pressed() {
+            self.muted = !self.muted;
+        }
+
+        // Options des parties à plusieurs : chaque clic passe à la valeur suivante
+        if self.is_match_option_pressed(0) {
+            let (players, versus) = next_in(&MATCH_TYPES, (self.players, self.versus));
+            self.players = players;
+            self.versus = versus;
+        }
+        if self.is_match_option_pressed(1) {
+            if self.versus {
+                self.versus_rules.frag_limit = next_in(&FRAG_LIMITS, self.versus_rules.frag_limit);
+            } else {
+                self.shared_lives = !self.shared_lives;
+            }
+        }
+        if self.is_match_option_pressed(2) {
+            if self.versus {
+                self.versus_rules.time_limit = next_in(&TIME_LIMITS, self.versus_rules.time_limit);
+            } else {
+                self.friendly_fire = !self.friendly_fire;
+            }
+        }
+    }
+
+    /// Dessine les barres des sliders du nombre et de la vitesse des astéroïdes.
+    fn draw_sliders(&self) {
+        // Définir la position du slider pour le nombre d'astéroïdes
+        let asteroid_slider_x = screen_width() * 0.5 - self.slider_width / 2.0;
+        let asteroid_slider_y = screen_height() * 0.4;
+
         // Dessiner la barre du slider pour le nombre d'astéroïdes
         let asteroid_value_x = asteroid_slider_x
             + ((self.asteroid_count as f32 - 1.) / (25. - 1.)) * self.slider_width;
@@ -51,22 +217,6 @@ impl ConfigScreen {
             GREEN, // Partie verte de la barre (vide)
         );
 
-        // Détecter si la souris est au-dessus du slider pour le nombre d'astéroïdes
-        let mouse_pos = mouse_position();
-        let is_mouse_on_asteroid_slider = mouse_pos.0 >= asteroid_slider_x
-            && mouse_pos.0 <= asteroid_slider_x + self.slider_width
-            && (mouse_pos.1 - asteroid_slider_y).abs() <= 10.0;
-
-        // Interaction avec le slider pour ajuster le nombre d'astéroïdes
-        if is_mouse_button_down(MouseButton::Left) && is_mouse_on_asteroid_slider {
-            let mouse_x = mouse_position().0;
-            if mouse_x >= asteroid_slider_x && mouse_x <= asteroid_slider_x + self.slider_width {
-                self.asteroid_count =
-                    ((mouse_x - asteroid_slider_x) / self.slider_width * (25. - 1.) + 1.) as i32;
-                self.asteroid_count = self.asteroid_count.clamp(1, 25); // Limiter à 1-25 astéroïdes
-            }
-        }
-
         // Définir la position du slider pour la vitesse des astéroïdes
         let speed_slider_x = screen_width() * 0.5 - self.slider_width / 2.0;
         let speed_slider_y = screen_height() * 0.5 + 50.0; // Position un peu plus bas
@@ -91,28 +241,36 @@ impl ConfigScreen {
             GREEN, // Partie verte de la barre (vide)
         );
 
-        // Détecter si la souris est au-dessus du slider pour la vitesse des astéroïdes
-        let is_mouse_on_speed_slider = mouse_pos.0 >= speed_slider_x
-            && mouse_pos.0 <= speed_slider_x + self.slider_width
-            && (mouse_pos.1 - speed_slider_y).abs() <= 10.0;
-
-        // Interaction avec le slider pour ajuster la vitesse des astéroïdes
-        if is_mouse_button_down(MouseButton::Left) && is_mouse_on_speed_slider {
-            let mouse_x = mouse_position().0;
-            if mouse_x >= speed_slider_x && mouse_x <= speed_slider_x + self.slider_width {
-                self.asteroid_speed = ((mouse_x - speed_slider_x) / self.slider_width * (5. - 0.3)
-                    + 0.3)
-                    .clamp(0.3, 5.0); // Limiter la vitesse entre 0.3 et 5.0
-            }
+        // Dessiner les sliders de volume avec leur nom et leur valeur
+        for (index, label) in VOLUME_LABELS.iter().enumerate() {
+            let slider = self.volume_slider_position(index);
+            let value_x = slider.x + self.volumes[index] * VOLUME_SLIDER_WIDTH;
+            draw_ui_text(
+                &format!("{} : {:.0} %", label, self.volumes[index] * 100.),
+                slider.x,
+                slider.y - 15.0,
+                20.0,
+                WHITE,
+            );
+            draw_line(slider.x, slider.y, value_x, slider.y, 5.0, RED);
+            draw_line(
+                value_x,
+                slider.y,
+                slider.x + VOLUME_SLIDER_WIDTH,
+                slider.y,
+                5.0,
+                GREEN,
+            );
         }
     }
 
     /// Dessine l'écran de configuration avec tous les éléments graphiques.
     pub fn draw(&self) {
         clear_background(BLACK); // Fond noir pour l'écran
+        self.draw_sliders();
 
         // Afficher le texte pour le nombre d'astéroïdes
-        draw_text(
+        draw_ui_text(
             "Choisissez le nombre d'astéroïdes :",
             screen_width() * 0.5 - 200.0,
             screen_height() * 0.3,
@@ -121,7 +279,7 @@ impl ConfigScreen {
         );
 
         // Afficher la valeur actuelle du nombre d'astéroïdes
-        draw_text(
+        draw_ui_text(
             &format!("Astéroïdes : {}", self.asteroid_count),
             screen_width() * 0.5 - 50.0,
             screen_height() * 0.5,
@@ -130,7 +288,7 @@ impl ConfigScreen {
         );
 
         // Afficher la valeur actuelle de la vitesse des astéroïdes
-        draw_text(
+        draw_ui_text(
             &format!("Vitesse des astéroïdes : {:.1}", self.asteroid_speed),
             screen_width() * 0.5 - 100.0,
             screen_height() * 0.55,
@@ -150,7 +308,7 @@ impl ConfigScreen {
             self.button_size.y,
             GRAY, // Bouton gris
         );
-        draw_text(
+        draw_ui_text(
             "Commencer",
             button_position.x + 50.0,
             button_position.y + 30.0,
@@ -158,6 +316,25 @@ impl ConfigScreen {
             WHITE, // Texte en blanc
         );
 
+        // Dessiner le bouton "Continuer" à droite de "Commencer" si une sauvegarde existe
+        if self.save_available {
+            let continue_button_position = self.continue_button_position();
+            draw_rectangle(
+                continue_button_position.x,
+                continue_button_position.y,
+                self.button_size.x,
+                self.button_size.y,
+                DARKGREEN,
+            );
+            draw_ui_text(
+                "Continuer",
+                continue_button_position.x + 50.0,
+                continue_button_position.y + 30.0,
+                25.0,
+                WHITE,
+            );
+        }
+
         // Dessiner le bouton "Exit"
         let exit_button_position = Vec2::new(
             button_position.x,
@@ -170,7 +347,7 @@ impl ConfigScreen {
             self.button_size.y,
             RED, // Bouton rouge
         );
-        draw_text(
+        draw_ui_text(
             "Exit",
             exit_button_position.x + 70.0,
             exit_button_position.y + 30.0,
@@ -178,15 +355,127 @@ impl ConfigScreen {
             WHITE, // Texte en blanc
         );
 
-        // Afficher le message de fin si défini
-        if let Some(ref message) = self.end_message {
-            draw_text(
-                message,
-                screen_width() * 0.5 - message.len() as f32 * 5.0,
-                screen_height() * 0.2,
-                30.0,
-                YELLOW, // Texte en jaune
+        // Dessiner le bouton de qualité des particules sous le bouton "Exit"
+        let particles_button_position = self.particles_button_position();
+        draw_rectangle(
+            particles_button_position.x,
+            particles_button_position.y,
+            self.button_size.x,
+            self.button_size.y,
+            DARKGRAY, // Bouton gris foncé
+        );
+        draw_ui_text(
+            &format!("Particules : {}", self.particle_quality.label()),
+            particles_button_position.x + 10.0,
+            particles_button_position.y + 30.0,
+            22.0,
+            WHITE,
+        );
+
+        // Dessiner le bouton du schéma de contrôle sous le bouton "Continuer"
+        let controls_button_position = self.controls_button_position();
+        draw_rectangle(
+            controls_button_position.x,
+            controls_button_position.y,
+            self.button_size.x,
+            self.button_size.y,
+            DARKGRAY,
+        );
+        draw_ui_text(
+            &format!("Contrôles : {}", self.control_scheme.label()),
+            controls_button_position.x + 10.0,
+            controls_button_position.y + 30.0,
+            22.0,
+            WHITE,
+        );
+
+        // Dessiner le bouton du tableau des meilleurs scores
+        let leaderboard_button_position = self.leaderboard_button_position();
+        draw_rectangle(
+            leaderboard_button_position.x,
+            leaderboard_button_position.y,
+            self.button_size.x,
+            self.button_size.y,
+            DARKBLUE,
+        );
+        draw_ui_text(
+            "Meilleurs scores",
+            leaderboard_button_position.x + 20.0,
+            leaderboard_button_position.y + 30.0,
+            25.0,
+            WHITE,
+        );
+
+        // Dessiner le bouton de sourdine sous les sliders de volume
+        let mute_button_position = self.mute_button_position();
+        draw_rectangle(
+            mute_button_position.x,
+            mute_button_position.y,
+            VOLUME_SLIDER_WIDTH,
+            self.button_size.y,
+            if self.muted { MAROON } else { DARKGRAY },
+        );
+        draw_ui_text(
+            if self.muted {
+                "Son : coupé"
+            } else {
+                "Son : activé"
+            },
+            mute_button_position.x + 10.0,
+            mute_button_position.y + 30.0,
+            22.0,
+            WHITE,
+        );
+
+        // Dessiner les options des parties à plusieurs, grisées pour une partie à un joueur
+        let labels = if self.players == 1 {
+            [
+                "Solo".to_string(),
+                "Vies : -".to_string(),
+                "Tir ami : -".to_string(),
+            ]
+        } else if self.versus {
+            let time = match self.versus_rules.time_limit {
+                0 => "illimité".to_string(),
+                seconds => format!("{} min", seconds / 60),
+            };
+            [
+                format!("Versus : {} joueurs", self.players),
+                format!("Frags : {}", self.versus_rules.frag_limit),
+                format!("Temps : {}", time),
+            ]
+        } else {
+            [
+                format!("Coop : {} joueurs", self.players),
+                format!(
+                    "Vies : {}",
+                    if self.shared_lives {
+                        "communes"
+                    } else {
+                        "séparées"
+                    }
+                ),
+                format!(
+                    "Tir ami : {}",
+                    if self.friendly_fire { "oui" } else { "non" }
+                ),
+            ]
+        };
+        for (index, label) in labels.iter().enumerate() {
+            let position = self.match_option_position(index);
+            let color = if index == 0 || self.players > 1 {
+                DARKPURPLE
+            } else {
+                DARKGRAY
+            };
+            draw_rectangle(
+                position.x,
+                position.y,
+                VOLUME_SLIDER_WIDTH,
+                self.button_size.y,
+                color,
             );
+            draw_ui_text(label, position.x + 10.0, position.y + 30.0, 22.0, WHITE);
         }
     }
 
@@ -228,6 +517,216 @@ impl ConfigScreen {
             && mouse.1 < exit_button_position.y + self.button_size.y
     }
 
+    /// Retourne la position du bouton de qualité des particules (sous le bouton "Exit").
+    fn particles_button_position(&self) -> Vec2 {
+        Vec2::new(
+            screen_width() * 0.5 - self.button_size.x / 2.0,
+            screen_height() * 0.6 + 2.0 * (self.button_size.y + 10.0),
+        )
+    }
+
+    /// Vérifie si le bouton de qualité des particules a été pressé.
+    ///
+    /// # Retour
+    ///
+    /// `true` si le bouton a été pressé, sinon `false`.
+    fn is_particles_pressed(&self) -> bool {
+        let mouse = mouse_position();
+        let position = self.particles_button_position();
+
+        is_mouse_button_pressed(MouseButton::Left)
+            && mouse.0 > position.x
+            && mouse.0 < position.x + self.button_size.x
+            && mouse.1 > position.y
+            && mouse.1 < position.y + self.button_size.y
+    }
+
+    /// Retourne la position du bouton "Continuer" (à droite du bouton "Commencer").
+    fn continue_button_position(&self) -> Vec2 {
+        Vec2::new(
+            screen_width() * 0.5 + self.button_size.x / 2.0 + 10.0,
+            screen_height() * 0.6,
+        )
+    }
+
+    /// Vérifie si le bouton "Continuer" a été pressé.
+    ///
+    /// # Retour
+    ///
+    /// `true` si une sauvegarde existe et que le bouton a été pressé, sinon `false`.
+    pub fn is_continue_pressed(&self) -> bool {
+        let mouse = mouse_position();
+        let position = self.continue_button_position();
+
+        self.save_available
+            && is_mouse_button_pressed(MouseButton::Left)
+            && mouse.0 > position.x
+            && mouse.0 < position.x + self.button_size.x
+            && mouse.1 > position.y
+            && mouse.1 < position.y + self.button_size.y
+    }
+
+    /// Retourne la position du bouton du schéma de contrôle (sous le bouton "Continuer").
+    fn controls_button_position(&self) -> Vec2 {
+        let continue_button_position = self.continue_button_position();
+        Vec2::new(
+            continue_button_position.x,
+            continue_button_position.y + self.button_size.y + 10.0,
+        )
+    }
+
+    /// Vérifie si le bouton du schéma de contrôle a été pressé.
+    ///
+    /// # Retour
+    ///
+    /// `true` si le bouton a été pressé, sinon `false`.
+    fn is_controls_pressed(&self) -> bool {
+        let mouse = mouse_position();
+        let position = self.controls_button_position();
+
+        is_mouse_button_pressed(MouseButton::Left)
+            && mouse.0 > position.x
+            && mouse.0 < position.x + self.button_size.x
+            && mouse.1 > position.y
+            && mouse.1 < position.y + self.button_size.y
+    }
+
+    /// Retourne le schéma de contrôle choisi.
+    ///
+    /// # Retour
+    ///
+    /// Le schéma de contrôle du premier joueur.
+    pub fn get_control_scheme(&self) -> ControlScheme {
+        self.control_scheme
+    }
+
+    /// Définit le schéma de contrôle (modifié depuis le menu pause).
+    ///
+    /// # Paramètres
+    ///
+    /// * `scheme` - Le nouveau schéma de contrôle.
+    pub fn set_control_scheme(&mut self, scheme: ControlScheme) {
+        self.control_scheme = scheme;
+    }
+
+    /// Indique à l'écran si une partie sauvegardée peut être reprise.
+    ///
+    /// # Arguments
+    ///
+    /// * `available` - `true` pour afficher le bouton "Continuer".
+    pub fn set_save_available(&mut self, available: bool) {
+        self.save_available = available;
+    }
+
+    /// Retourne la position du bouton des meilleurs scores (sous le bouton des particules).
+    fn leaderboard_button_position(&self) -> Vec2 {
+        Vec2::new(
+            screen_width() * 0.5 - self.button_size.x / 2.0,
+            screen_height() * 0.6 + 3.0 * (self.button_size.y + 10.0),
+        )
+    }
+
+    /// Vérifie si le bouton "Meilleurs scores" a été pressé.
+    ///
+    /// # Retour
+    ///
+    /// `true` si le bouton a été pressé, sinon `false`.
+    pub fn is_leaderboard_pressed(&self) -> bool {
+        let mouse = mouse_position();
+        let position = self.leaderboard_button_position();
+
+        is_mouse_button_pressed(MouseButton::Left)
+            && mouse.0 > position.x
+            && mouse.0 < position.x + self.button_size.x
+            && mouse.1 > position.y
+            && mouse.1 < position.y + self.button_size.y
+    }
+
+    /// Retourne la position du slider de volume `index` (colonne de gauche).
+    fn volume_slider_position(&self, index: usize) -> Vec2 {
+        Vec2::new(20.0, screen_height() * (0.3 + 0.1 * index as f32))
+    }
+
+    /// Retourne la position du bouton de sourdine (sous les sliders de volume).
+    fn mute_button_position(&self) -> Vec2 {
+        Vec2::new(20.0, screen_height() * 0.6)
+    }
+
+    /// Vérifie si le bouton de sourdine a été pressé.
+    ///
+    /// # Retour
+    ///
+    /// `true` si le bouton a été pressé, sinon `false`.
+    fn is_mute_pressed(&self) -> bool {
+        let mouse = mouse_position();
+        let position = self.mute_button_position();
+
+        is_mouse_button_pressed(MouseButton::Left)
+            && mouse.0 > position.x
+            && mouse.0 < position.x + VOLUME_SLIDER_WIDTH
+            && mouse.1 > position.y
+            && mouse.1 < position.y + self.button_size.y
+    }
+
+    /// Retourne la position du bouton d'option de coopération `index` (sous le bouton de sourdine).
+    fn match_option_position(&self, index: usize) -> Vec2 {
+        let mute = self.mute_button_position();
+        Vec2::new(
+            mute.x,
+            mute.y + (index + 1) as f32 * (self.button_size.y + 10.0),
+        )
+    }
+
+    /// Vérifie si le bouton d'option de coopération `index` a été pressé.
+    ///
+    /// # Retour
+    ///
+    /// `true` si le bouton a été pressé, sinon `false`.
+    fn is_match_option_pressed(&self, index: usize) -> bool {
+        let mouse = mouse_position();
+        let position = self.match_option_position(index);
+
+        is_mouse_button_pressed(MouseButton::Left)
+            && mouse.0 > position.x
+            && mouse.0 < position.x + VOLUME_SLIDER_WIDTH
+            && mouse.1 > position.y
+            && mouse.1 < position.y + self.button_size.y
+    }
+
+    /// Retourne les options de la coopération.
+    ///
+    /// # Retour
+    ///
+    /// Le nombre de joueurs, puis `true` si les vies sont communes et `true` si le tir ami
+    /// est activé.
+    pub fn get_coop_options(&self) -> (usize, bool, bool) {
+        (self.players, self.shared_lives, self.friendly_fire)
+    }
+
+    /// Retourne les options du versus.
+    ///
+    /// # Retour
+    ///
+    /// `true` si les joueurs s'affrontent, puis les règles de fin du match.
+    pub fn get_versus_options(&self) -> (bool, VersusRules) {
+        (self.versus && self.players > 1, self.versus_rules)
+    }
+
+    /// Retourne les volumes choisis et la sourdine.
+    ///
+    /// # Retour
+    ///
+    /// Les volumes général, de la musique et des effets (0.0 à 1.0), puis `true` si la
+    /// sourdine est activée.
+    pub fn get_volumes(&self) -> (f32, f32, f32, bool) {
+        (
+            self.volumes[0],
+            self.volumes[1],
+            self.volumes[2],
+            self.muted,
+        )
+    }
+
     /// Retourne le nombre actuel d'astéroïdes.
     ///
     /// # Retour
@@ -246,12 +745,36 @@ impl ConfigScreen {
         self.asteroid_speed
     }
 
-    /// Définit le message de fin à afficher.
+    /// Retourne la qualité des particules choisie.
+    ///
+    /// # Retour
+    ///
+    /// Le niveau de qualité des particules.
+    pub fn get_particle_quality(&self) -> ParticleQuality {
+        self.particle_quality
+    }
+
+    /// Définit la qualité des particules (modifiée depuis le menu pause).
     ///
     /// # Paramètres
     ///
-    /// * `message` - Le message de fin à afficher.
-    pub fn set_end_message(&mut self, message: &str) {
-        self.end_message = Some(message.to_string());
+    /// * `quality` - Le nouveau niveau de qualité.
+    pub fn set_particle_quality(&mut self, quality: ParticleQuality) {
+        self.particle_quality = quality;
     }
 }
+
+/// Retourne la valeur qui suit `current` dans une liste de choix, en revenant au début après
+/// la dernière (ou la première valeur si `current` n'est pas dans la liste).
+///
+/// # Arguments
+///
+/// * `choices` - Les valeurs proposées, dans l'ordre.
+/// * `current` - La valeur actuelle.
+fn next_in<T: Copy + PartialEq>(choices: &[T], current: T) -> T {
+    let index = choices
+        .iter()
+        .position(|&choice| choice == current)
+        .map_or(0, |index| index + 1);
+    choices[index % choices.len()]
+}
@@ -0,0 +1,568 @@
+use crate::asteroid::Asteroid;
+use crate::debug::DebugTools;
+use crate::text::draw_ui_text;
+use crate::vaisseau::MAX_SHIELD;
+use crate::world::World;
+use ::rand::Rng;
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+use std::str::FromStr;
+
+/// Touche qui ouvre ou ferme la console (accent grave).
+pub const CONSOLE_KEY: KeyCode = KeyCode::GraveAccent;
+
+/// Nombre de commandes gardées dans l'historique.
+const HISTORY_LEN: usize = 50;
+
+/// Nombre de lignes de résultat gardées par la console.
+const OUTPUT_LEN: usize = 100;
+
+/// Demande d'une commande que seul le jeu peut exécuter, parce qu'elle porte sur plus que
+/// la simulation (score, statistiques, horloge de la partie).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConsoleRequest {
+    /// Enregistrer la partie dans l'emplacement nommé.
+    Save(String),
+    /// Reprendre la partie de l'emplacement nommé.
+    Load(String),
+}
+
+/// État du jeu sur lequel agissent les commandes.
+///
+/// Les commandes ne touchent qu'à la simulation et aux outils de débogage : elles peuvent donc
+/// être exécutées sans fenêtre, sur un `World` créé avec `World::with_arena`.
+pub struct ConsoleContext<'a> {
+    /// La partie en cours.
+    pub world: &'a mut World,
+    /// Les outils de débogage, pour l'échelle de temps.
+    pub debug: &'a mut DebugTools,
+    /// Le nombre d'astéroïdes de la première vague.
+    pub asteroid_count: i32,
+    /// La vitesse des astéroïdes.
+    pub asteroid_speed: f32,
+    /// Les demandes à exécuter par le jeu après les commandes.
+    pub requests: Vec<ConsoleRequest>,
+    /// La partie a utilisé la triche : passe à `true` après une commande de triche réussie.
+    pub cheated: bool,
+}
+
+/// Une commande de la console.
+pub struct Command {
+    /// Le nom de la commande, premier mot de la ligne.
+    pub name: &'static str,
+    /// La syntaxe de la commande, affichée par `help`.
+    pub usage: &'static str,
+    /// Les mots-clés des arguments, proposés par la complétion.
+    pub keywords: &'static [&'static str],
+    /// La commande change le cours de la partie : elle la marque comme trichée.
+    pub cheat: bool,
+    /// Exécute la commande avec les mots qui suivent son nom, et retourne le message à
+    /// afficher ou un message d'erreur.
+    pub run: fn(&[&str], &mut ConsoleContext) -> Result<String, String>,
+}
+
+/// Registre des commandes de la console.
+pub struct CommandRegistry {
+    commands: Vec<Command>,
+}
+
+impl CommandRegistry {
+    /// Crée un registre avec les commandes du jeu.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `CommandRegistry`.
+    pub fn new() -> Self {
+        let mut registry = Self {
+            commands: Vec::new(),
+        };
+        registry.register(Command {
+            name: "spawn",
+            usage: "spawn asteroid <niveau 1-3> [at <x> <y>] [speed <vx> <vy>]",
+            keywords: &["asteroid", "at", "speed"],
+            cheat: true,
+            run: spawn_command,
+        });
+        registry.register(Command {
+            name: "set",
+            usage: "set shield <points>",
+            keywords: &["shield"],
+            cheat: true,
+            run: set_command,
+        });
+        registry.register(Command {
+            name: "wave",
+            usage: "wave <numéro>",
+            keywords: &[],
+            cheat: true,
+            run: wave_command,
+        });
+        registry.register(Command {
+            name: "timescale",
+            usage: "timescale <échelle>",
+            keywords: &[],
+            cheat: true,
+            run: timescale_command,
+        });
+        registry.register(Command {
+            name: "god",
+            usage: "god [on|off]",
+            keywords: &["on", "off"],
+            cheat: true,
+            run: god_command,
+        });
+        registry.register(Command {
+            name: "seed",
+            usage: "seed <graine>",
+            keywords: &[],
+            cheat: true,
+            run: seed_command,
+        });
+        registry.register(Command {
+            name: "save",
+            usage: "save <emplacement>",
+            keywords: &[],
+            cheat: false,
+            run: |args, context| {
+                let slot = single_word(args, "emplacement")?;
+                context
+                    .requests
+                    .push(ConsoleRequest::Save(slot.to_string()));
+                Ok(format!("sauvegarde dans {}", slot))
+            },
+        });
+        registry.register(Command {
+            name: "load",
+            usage: "load <emplacement>",
+            keywords: &[],
+            // Recharger un emplacement permet d'annuler une mort
+            cheat: true,
+            run: |args, context| {
+                let slot = single_word(args, "emplacement")?;
+                context
+                    .requests
+                    .push(ConsoleRequest::Load(slot.to_string()));
+                Ok(format!("chargement de {}", slot))
+            },
+        });
+        registry
+    }
+
+    /// Ajoute une commande au registre, ou remplace la commande du même nom.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - La commande.
+    pub fn register(&mut self, command: Command) {
+        self.commands
+            .retain(|existing| existing.name != command.name);
+        self.commands.push(command);
+    }
+
+    /// Exécute une ligne de commande. `help` liste les commandes du registre. Une commande de
+    /// triche réussie marque la partie comme trichée.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - La ligne : le nom de la commande suivi de ses arguments.
+    /// * `context` - L'état du jeu.
+    ///
+    /// # Retour
+    ///
+    /// Le message de la commande, ou un message d'erreur si la commande est inconnue ou ses
+    /// arguments invalides.
+    pub fn execute(&self, line: &str, context: &mut ConsoleContext) -> Result<String, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            return Ok(String::new());
+        };
+        if name == "help" {
+            let usages: Vec<&str> = self.commands.iter().map(|command| command.usage).collect();
+            return Ok(usages.join("\n"));
+        }
+        let command = self
+            .commands
+            .iter()
+            .find(|command| command.name == name)
+            .ok_or_else(|| format!("commande inconnue : {} (voir help)", name))?;
+        let result = (command.run)(args, context);
+        if command.cheat && result.is_ok() {
+            context.cheated = true;
+        }
+        result
+    }
+
+    /// Exécute un script : une commande par ligne ou entre deux `;`. Les lignes vides et
+    /// celles commençant par `#` sont ignorées ; une erreur n'arrête pas le script.
+    ///
+    /// # Arguments
+    ///
+    /// * `script` - Les commandes.
+    /// * `context` - L'état du jeu.
+    ///
+    /// # Retour
+    ///
+    /// Le résultat de chaque commande exécutée, dans l'ordre.
+    pub fn run_script(
+        &self,
+        script: &str,
+        context: &mut ConsoleContext,
+    ) -> Vec<Result<String, String>> {
+        script
+            .lines()
+            .flat_map(|line| line.split(';'))
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| self.execute(line, context))
+            .collect()
+    }
+
+    /// Complète le dernier mot d'une ligne : le nom d'une commande, ou un mot-clé de ses
+    /// arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - La ligne en cours de saisie.
+    ///
+    /// # Retour
+    ///
+    /// La ligne complétée (jusqu'au préfixe commun s'il y a plusieurs possibilités), et les
+    /// possibilités quand il y en a plusieurs.
+    pub fn complete(&self, input: &str) -> (String, Vec<&'static str>) {
+        let split = input.rfind(' ').map_or(0, |index| index + 1);
+        let (head, last) = input.split_at(split);
+        let candidates: Vec<&'static str> = match head.split_whitespace().next() {
+            None => self.commands.iter().map(|command| command.name).collect(),
+            Some(name) => self
+                .commands
+                .iter()
+                .find(|command| command.name == name)
+                .map_or(Vec::new(), |command| command.keywords.to_vec()),
+        };
+        let candidates: Vec<&'static str> = candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(last))
+            .collect();
+        match candidates.as_slice() {
+            [] => (input.to_string(), Vec::new()),
+            [single] => (format!("{}{} ", head, single), Vec::new()),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.len(), |length, candidate| {
+                    first
+                        .bytes()
+                        .zip(candidate.bytes())
+                        .take(length)
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                });
+                (format!("{}{}", head, &first[..common]), candidates)
+            }
+        }
+    }
+}
+
+/// Lit un argument numérique.
+///
+/// # Arguments
+///
+/// * `word` - Le mot, ou `None` s'il manque.
+/// * `what` - Le nom de l'argument, pour le message d'erreur.
+fn parse_arg<T: FromStr>(word: Option<&str>, what: &str) -> Result<T, String> {
+    let word = word.ok_or_else(|| format!("{} manquant", what))?;
+    word.parse()
+        .map_err(|_| format!("{} invalide : \"{}\"", what, word))
+}
+
+/// Retourne le seul argument d'une commande.
+///
+/// # Arguments
+///
+/// * `args` - Les arguments de la commande.
+/// * `what` - Le nom de l'argument, pour le message d'erreur.
+fn single_word<'a>(args: &[&'a str], what: &str) -> Result<&'a str, String> {
+    match args {
+        [word] => Ok(word),
+        [] => Err(format!("{} manquant", what)),
+        _ => Err("trop d'arguments".to_string()),
+    }
+}
+
+/// `spawn asteroid <niveau> [at <x> <y>] [speed <vx> <vy>]` : fait apparaître un astéroïde,
+/// par défaut au bord de l'arène et dans une direction au hasard.
+fn spawn_command(args: &[&str], context: &mut ConsoleContext) -> Result<String, String> {
+    let mut words = args.iter().copied();
+    if words.next() != Some("asteroid") {
+        return Err("seuls les astéroïdes peuvent apparaître : spawn asteroid <niveau>".into());
+    }
+    let level: u8 = parse_arg(words.next(), "niveau")?;
+    if !(1..=3).contains(&level) {
+        return Err(format!("niveau invalide : {} (1 à 3)", level));
+    }
+    let (mut position, mut speed) = (None, None);
+    while let Some(word) = words.next() {
+        let target = match word {
+            "at" => &mut position,
+            "speed" => &mut speed,
+            _ => return Err(format!("mot inattendu : \"{}\"", word)),
+        };
+        *target = Some(vec2(
+            parse_arg(words.next(), word)?,
+            parse_arg(words.next(), word)?,
+        ));
+    }
+
+    let world = &mut *context.world;
+    let position = position.unwrap_or_else(|| {
+        Asteroid::random_position(level, world.level_size, world.arena, &mut world.rng)
+    });
+    let speed = speed.unwrap_or_else(|| {
+        Vec2::from_angle(world.rng.gen_range(0.0..TAU)) * context.asteroid_speed
+    });
+    world.asteroids.push(Asteroid::new(
+        level,
+        speed,
+        world.level_size,
+        Some(position),
+    ));
+    Ok(format!(
+        "astéroïde A{} de niveau {} en ({:.0}, {:.0})",
+        world.asteroids.len() - 1,
+        level,
+        position.x,
+        position.y
+    ))
+}
+
+/// `set shield <points>` : remplit le bouclier de chaque vaisseau, entre 0 et `MAX_SHIELD`.
+fn set_command(args: &[&str], context: &mut ConsoleContext) -> Result<String, String> {
+    let [property, value] = args else {
+        return Err("usage : set shield <points>".to_string());
+    };
+    if *property != "shield" {
+        return Err(format!("propriété inconnue : \"{}\"", property));
+    }
+    let shield: f32 = parse_arg(Some(value), "bouclier")?;
+    let shield = shield.clamp(0., MAX_SHIELD);
+    for vaisseau in context.world.ships.iter_mut() {
+        vaisseau.set_shield(shield);
+    }
+    Ok(format!("bouclier : {}", shield))
+}
+
+/// `wave <numéro>` : remplace les astéroïdes par ceux de la vague demandée.
+fn wave_command(args: &[&str], context: &mut ConsoleContext) -> Result<String, String> {
+    let wave: u32 = parse_arg(Some(single_word(args, "vague")?), "vague")?;
+    if wave == 0 {
+        return Err("les vagues commencent à 1".to_string());
+    }
+    context.world.asteroids.clear();
+    context
+        .world
+        .start_wave(wave, context.asteroid_count, context.asteroid_speed);
+    Ok(format!(
+        "vague {} : {} astéroïdes",
+        wave,
+        context.world.asteroids.len()
+    ))
+}
+
+/// `timescale <échelle>` : change la vitesse de la simulation par rapport au temps réel.
+fn timescale_command(args: &[&str], context: &mut ConsoleContext) -> Result<String, String> {
+    let scale: f32 = parse_arg(Some(single_word(args, "échelle")?), "échelle")?;
+    if !scale.is_finite() {
+        return Err(format!("échelle invalide : {}", scale));
+    }
+    context.debug.set_time_scale(scale);
+    Ok(format!(
+        "échelle de temps : {}",
+        context.debug.get_time_scale()
+    ))
+}
+
+/// `god [on|off]` : active, désactive ou inverse l'invincibilité des vaisseaux.
+fn god_command(args: &[&str], context: &mut ConsoleContext) -> Result<String, String> {
+    let world = &mut *context.world;
+    world.god_mode = match args {
+        [] => !world.god_mode,
+        ["on"] => true,
+        ["off"] => false,
+        _ => return Err("usage : god [on|off]".to_string()),
+    };
+    Ok(format!(
+        "invincibilité {}",
+        if world.god_mode {
+            "activée"
+        } else {
+            "désactivée"
+        }
+    ))
+}
+
+/// `seed <graine>` : réinitialise le générateur aléatoire de la partie.
+fn seed_command(args: &[&str], context: &mut ConsoleContext) -> Result<String, String> {
+    let seed: u64 = parse_arg(Some(single_word(args, "graine")?), "graine")?;
+    context.world.reseed(seed);
+    Ok(format!("graine : {}", seed))
+}
+
+/// Console du jeu, ouverte avec `CONSOLE_KEY` pendant la partie.
+///
+/// `Tab` complète la commande, les flèches haut et bas parcourent l'historique, `Entrée`
+/// exécute la ligne et `Échap` ferme la console. La partie est figée tant que la console est
+/// ouverte.
+pub struct Console {
+    registry: CommandRegistry,
+    open: bool,
+    input: String,
+    history: Vec<String>,
+    /// Position dans l'historique pendant son parcours (`None` : nouvelle ligne).
+    history_index: Option<usize>,
+    output: VecDeque<String>,
+}
+
+impl Console {
+    /// Crée une console fermée, avec les commandes du jeu.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `Console`.
+    pub fn new() -> Self {
+        Self {
+            registry: CommandRegistry::new(),
+            open: false,
+            input: String::new(),
+            history: Vec::new(),
+            history_index: None,
+            output: VecDeque::new(),
+        }
+    }
+
+    /// Indique si la console est ouverte.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Ajoute une ligne aux résultats affichés.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - Le texte à afficher.
+    pub fn print(&mut self, line: &str) {
+        for line in line.lines() {
+            if self.output.len() == OUTPUT_LEN {
+                self.output.pop_front();
+            }
+            self.output.push_back(line.to_string());
+        }
+    }
+
+    /// Ouvre ou ferme la console avec `CONSOLE_KEY`, puis lit la saisie si elle est ouverte.
+    /// À appeler une fois par frame de jeu.
+    ///
+    /// # Retour
+    ///
+    /// La ligne validée avec `Entrée` pendant cette frame, à passer à `execute`.
+    pub fn update(&mut self) -> Option<String> {
+        let toggled = is_key_pressed(CONSOLE_KEY);
+        if toggled || (self.open && is_key_pressed(KeyCode::Escape)) {
+            self.open = !self.open && toggled;
+            // Le caractère de la touche d'ouverture ne doit pas arriver dans la saisie
+            while get_char_pressed().is_some() {}
+            return None;
+        }
+        if !self.open {
+            return None;
+        }
+        while let Some(c) = get_char_pressed() {
+            if !c.is_control() && c != '`' {
+                self.input.push(c);
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            self.input.pop();
+        }
+        if is_key_pressed(KeyCode::Tab) {
+            let (completed, candidates) = self.registry.complete(&self.input);
+            if !candidates.is_empty() {
+                self.print(&candidates.join("  "));
+            }
+            self.input = completed;
+        }
+        if is_key_pressed(KeyCode::Up) && !self.history.is_empty() {
+            let index = self
+                .history_index
+                .map_or(self.history.len() - 1, |index| index.saturating_sub(1));
+            self.history_index = Some(index);
+            self.input = self.history[index].clone();
+        }
+        if is_key_pressed(KeyCode::Down) {
+            if let Some(index) = self.history_index {
+                self.history_index = (index + 1 < self.history.len()).then_some(index + 1);
+                self.input = self
+                    .history_index
+                    .map_or(String::new(), |index| self.history[index].clone());
+            }
+        }
+        if !is_key_pressed(KeyCode::Enter) {
+            return None;
+        }
+        let line = std::mem::take(&mut self.input);
+        self.history_index = None;
+        if line.trim().is_empty() {
+            return None;
+        }
+        if self.history.last() != Some(&line) {
+            if self.history.len() == HISTORY_LEN {
+                self.history.remove(0);
+            }
+            self.history.push(line.clone());
+        }
+        Some(line)
+    }
+
+    /// Exécute une ligne validée (une ou plusieurs commandes séparées par `;`) et affiche
+    /// leurs résultats.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - La ligne.
+    /// * `context` - L'état du jeu.
+    pub fn execute(&mut self, line: &str, context: &mut ConsoleContext) {
+        self.print(&format!("> {}", line));
+        for result in self.registry.run_script(line, context) {
+            match result {
+                Ok(message) => self.print(&message),
+                Err(error) => self.print(&format!("erreur : {}", error)),
+            }
+        }
+    }
+
+    /// Dessine la console sur la moitié haute de l'écran, si elle est ouverte.
+    pub fn draw(&self) {
+        if !self.open {
+            return;
+        }
+        let height = screen_height() * 0.5;
+        draw_rectangle(0., 0., screen_width(), height, Color::new(0., 0., 0., 0.8));
+        let line_height = 20.;
+        let visible = ((height - 40.) / line_height).max(0.) as usize;
+        let first = self.output.len().saturating_sub(visible);
+        for (row, line) in self.output.iter().skip(first).enumerate() {
+            draw_ui_text(line, 10., 25. + row as f32 * line_height, 18., LIGHTGRAY);
+        }
+        // Curseur clignotant
+        let cursor = if (get_time() * 2.) as i64 % 2 == 0 {
+            "_"
+        } else {
+            ""
+        };
+        draw_ui_text(
+            &format!("> {}{}", self.input, cursor),
+            10.,
+            height - 12.,
+            20.,
+            WHITE,
+        );
+    }
+}
@@ -0,0 +1,743 @@
+use crate::settings::{KeyBindings, Settings};
+use crate::text::{draw_ui_text, measure_ui_text};
+use crate::vaisseau::ShipInput;
+#[cfg(not(target_arch = "wasm32"))]
+use gilrs::{Axis, Button, EventType, Gamepad, GamepadId, Gilrs};
+use macroquad::prelude::*;
+
+/// Zone morte des sticks : en dessous de cette inclinaison, le stick est considéré au repos.
+pub const STICK_DEADZONE: f32 = 0.25;
+
+/// Schéma de contrôle du premier joueur, en plus de la manette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlScheme {
+    /// Rotation et accélération au clavier.
+    Keyboard,
+    /// Le vaisseau vise le pointeur de la souris ; clic gauche pour tirer, clic droit pour
+    /// accélérer. Les touches du clavier restent actives.
+    MouseAim,
+    /// Commandes à la souris ou à l'écran tactile seuls, avec des boutons affichés à l'écran :
+    /// le vaisseau vise le pointeur ou le doigt posé hors des boutons.
+    Touch,
+}
+
+impl ControlScheme {
+    /// Retourne l'identifiant du schéma utilisé dans le fichier de préférences.
+    pub fn key(self) -> &'static str {
+        match self {
+            ControlScheme::Keyboard => "keyboard",
+            ControlScheme::MouseAim => "mouse_aim",
+            ControlScheme::Touch => "touch",
+        }
+    }
+
+    /// Retrouve un schéma de contrôle à partir de son identifiant.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - L'identifiant lu dans le fichier de préférences.
+    ///
+    /// # Retour
+    ///
+    /// Le schéma correspondant, ou `None` si l'identifiant est inconnu.
+    pub fn from_key(key: &str) -> Option<Self> {
+        [
+            ControlScheme::Keyboard,
+            ControlScheme::MouseAim,
+            ControlScheme::Touch,
+        ]
+        .into_iter()
+        .find(|scheme| scheme.key() == key)
+    }
+
+    /// Retourne le nom du schéma affiché dans le menu.
+    pub fn label(self) -> &'static str {
+        match self {
+            ControlScheme::Keyboard => "Clavier",
+            ControlScheme::MouseAim => "Visée souris",
+            ControlScheme::Touch => "Tactile",
+        }
+    }
+
+    /// Retourne le schéma suivant, pour le bouton du menu.
+    pub fn next(self) -> Self {
+        match self {
+            ControlScheme::Keyboard => ControlScheme::MouseAim,
+            ControlScheme::MouseAim => ControlScheme::Touch,
+            ControlScheme::Touch => ControlScheme::Keyboard,
+        }
+    }
+}
+
+/// Rôle du stick gauche de la manette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StickMode {
+    /// Le vaisseau tourne vers la direction indiquée par le stick.
+    Absolute,
+    /// Le stick fait tourner le vaisseau vers la gauche ou la droite, comme un tank.
+    Tank,
+}
+
+impl StickMode {
+    /// Retourne l'identifiant du mode utilisé dans le fichier de préférences.
+    pub fn key(self) -> &'static str {
+        match self {
+            StickMode::Absolute => "absolute",
+            StickMode::Tank => "tank",
+        }
+    }
+
+    /// Retrouve un mode de stick à partir de son identifiant.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - L'identifiant lu dans le fichier de préférences.
+    ///
+    /// # Retour
+    ///
+    /// Le mode correspondant, ou `None` si l'identifiant est inconnu.
+    pub fn from_key(key: &str) -> Option<Self> {
+        [StickMode::Absolute, StickMode::Tank]
+            .into_iter()
+            .find(|mode| mode.key() == key)
+    }
+
+    /// Retourne le nom du mode affiché dans le menu.
+    pub fn label(self) -> &'static str {
+        match self {
+            StickMode::Absolute => "direction",
+            StickMode::Tank => "tank",
+        }
+    }
+
+    /// Retourne le mode suivant, pour le bouton du menu.
+    pub fn next(self) -> Self {
+        match self {
+            StickMode::Absolute => StickMode::Tank,
+            StickMode::Tank => StickMode::Absolute,
+        }
+    }
+}
+
+/// Bouton d'une manette, dans la disposition d'une manette Xbox : `South` est le bouton A.
+///
+/// Les manettes ne sont lues que par la version native ; la version web garde les boutons
+/// des préférences sans les utiliser.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PadButton {
+    South,
+    East,
+    North,
+    West,
+    C,
+    Z,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Correspondance entre les boutons du jeu et ceux de gilrs.
+#[cfg(not(target_arch = "wasm32"))]
+const GILRS_BUTTONS: [(PadButton, Button); 19] = [
+    (PadButton::South, Button::South),
+    (PadButton::East, Button::East),
+    (PadButton::North, Button::North),
+    (PadButton::West, Button::West),
+    (PadButton::C, Button::C),
+    (PadButton::Z, Button::Z),
+    (PadButton::LeftTrigger, Button::LeftTrigger),
+    (PadButton::LeftTrigger2, Button::LeftTrigger2),
+    (PadButton::RightTrigger, Button::RightTrigger),
+    (PadButton::RightTrigger2, Button::RightTrigger2),
+    (PadButton::Select, Button::Select),
+    (PadButton::Start, Button::Start),
+    (PadButton::Mode, Button::Mode),
+    (PadButton::LeftThumb, Button::LeftThumb),
+    (PadButton::RightThumb, Button::RightThumb),
+    (PadButton::DPadUp, Button::DPadUp),
+    (PadButton::DPadDown, Button::DPadDown),
+    (PadButton::DPadLeft, Button::DPadLeft),
+    (PadButton::DPadRight, Button::DPadRight),
+];
+
+#[cfg(not(target_arch = "wasm32"))]
+impl PadButton {
+    /// Retourne le bouton gilrs correspondant.
+    fn to_gilrs(self) -> Button {
+        GILRS_BUTTONS
+            .iter()
+            .find(|(button, _)| *button == self)
+            .map_or(Button::Unknown, |(_, code)| *code)
+    }
+
+    /// Retrouve le bouton du jeu correspondant à un bouton gilrs.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Le bouton gilrs.
+    ///
+    /// # Retour
+    ///
+    /// Le bouton, ou `None` pour un bouton que le jeu ne connaît pas.
+    fn from_gilrs(code: Button) -> Option<Self> {
+        GILRS_BUTTONS
+            .iter()
+            .find(|(_, gilrs_code)| *gilrs_code == code)
+            .map(|(button, _)| *button)
+    }
+}
+
+/// Boutons de la manette, communs à toutes les manettes.
+///
+/// Le bouton d'accélération peut être une gâchette analogique : l'accélération est alors
+/// proportionnelle à la pression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GamepadBindings {
+    /// Accélérer.
+    pub thrust: PadButton,
+    /// Reculer.
+    pub brake: PadButton,
+    /// Tirer un missile.
+    pub fire: PadButton,
+    /// Mettre la partie en pause.
+    pub pause: PadButton,
+}
+
+impl Default for GamepadBindings {
+    fn default() -> Self {
+        Self {
+            thrust: PadButton::RightTrigger2,
+            brake: PadButton::LeftTrigger2,
+            fire: PadButton::South,
+            pause: PadButton::Start,
+        }
+    }
+}
+
+impl GamepadBindings {
+    /// Retourne tous les boutons, pour vérifier qu'aucun n'est attribué deux fois.
+    pub fn all(&self) -> [PadButton; 4] {
+        [self.thrust, self.brake, self.fire, self.pause]
+    }
+}
+
+/// État d'une manette pendant une frame, avant sa conversion en commandes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PadState {
+    /// Inclinaison du stick gauche (x vers la droite, y vers le haut, de -1.0 à 1.0).
+    pub stick: Vec2,
+    /// Pression du bouton d'accélération (0.0 à 1.0).
+    pub thrust: f32,
+    /// Bouton de recul enfoncé.
+    pub brake: bool,
+    /// Bouton de tir enfoncé.
+    pub fire: bool,
+    /// Croix directionnelle vers la gauche.
+    pub rotate_left: bool,
+    /// Croix directionnelle vers la droite.
+    pub rotate_right: bool,
+}
+
+impl PadState {
+    /// Lit l'état d'une manette.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamepad` - La manette.
+    /// * `bindings` - Les boutons choisis par le joueur.
+    ///
+    /// # Retour
+    ///
+    /// L'état des sticks et des boutons utiles au pilotage.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read(gamepad: &Gamepad, bindings: &GamepadBindings) -> Self {
+        Self {
+            stick: vec2(
+                gamepad.value(Axis::LeftStickX),
+                gamepad.value(Axis::LeftStickY),
+            ),
+            thrust: gamepad
+                .button_data(bindings.thrust.to_gilrs())
+                .map_or(0., |data| data.value()),
+            brake: gamepad.is_pressed(bindings.brake.to_gilrs()),
+            fire: gamepad.is_pressed(bindings.fire.to_gilrs()),
+            rotate_left: gamepad.is_pressed(Button::DPadLeft),
+            rotate_right: gamepad.is_pressed(Button::DPadRight),
+        }
+    }
+
+    /// Convertit l'état de la manette en commandes du vaisseau.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - Le rôle du stick gauche.
+    ///
+    /// # Retour
+    ///
+    /// Les commandes : direction visée (mode absolu) ou rotation analogique (mode tank),
+    /// accélération proportionnelle à la gâchette, recul, tir et rotation à la croix.
+    pub fn to_input(self, mode: StickMode) -> ShipInput {
+        let stick = apply_deadzone(self.stick);
+        let (turn, heading) = match mode {
+            // Le stick a l'axe y vers le haut, l'écran vers le bas
+            StickMode::Absolute if stick != Vec2::ZERO => {
+                (0., Some(heading_towards(vec2(stick.x, -stick.y))))
+            }
+            StickMode::Absolute => (0., None),
+            StickMode::Tank => (stick.x, None),
+        };
+        ShipInput {
+            brake: self.brake,
+            rotate_left: self.rotate_left,
+            rotate_right: self.rotate_right,
+            fire: self.fire,
+            throttle: self.thrust.clamp(0., 1.),
+            turn,
+            heading,
+            ..Default::default()
+        }
+    }
+}
+
+/// Retire la zone morte d'un stick, et remet l'inclinaison restante à l'échelle.
+///
+/// # Arguments
+///
+/// * `stick` - L'inclinaison brute du stick.
+///
+/// # Retour
+///
+/// Une inclinaison nulle dans la zone morte, sinon une inclinaison de même direction dont
+/// la longueur va de 0.0 au bord de la zone morte à 1.0 en butée.
+pub fn apply_deadzone(stick: Vec2) -> Vec2 {
+    let length = stick.length();
+    if length <= STICK_DEADZONE {
+        return Vec2::ZERO;
+    }
+    let scaled = ((length - STICK_DEADZONE) / (1. - STICK_DEADZONE)).min(1.);
+    stick / length * scaled
+}
+
+/// Calcule l'angle de rotation de le vaisseau qui pointe dans une direction.
+///
+/// # Arguments
+///
+/// * `direction` - La direction, en coordonnées de l'écran (y vers le bas).
+///
+/// # Retour
+///
+/// L'angle de rotation en radians (0 vers le haut, dans le sens des aiguilles d'une montre).
+pub fn heading_towards(direction: Vec2) -> f32 {
+    direction.x.atan2(-direction.y)
+}
+
+/// Lit les commandes de la visée à la souris.
+///
+/// # Arguments
+///
+/// * `ship` - La position de le vaisseau à l'écran.
+///
+/// # Retour
+///
+/// Une direction visée vers le pointeur, le tir au clic gauche et l'accélération au clic
+/// droit.
+pub fn mouse_aim_input(ship: Vec2) -> ShipInput {
+    let mouse = Vec2::from(mouse_position());
+    ShipInput {
+        thrust: is_mouse_button_down(MouseButton::Right),
+        fire: is_mouse_button_down(MouseButton::Left),
+        heading: (mouse != ship).then(|| heading_towards(mouse - ship)),
+        ..Default::default()
+    }
+}
+
+/// Bouton affiché à l'écran pour les commandes tactiles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VirtualButton {
+    /// Accélérer tant que le bouton est maintenu (en bas à gauche).
+    Thrust,
+    /// Tirer tant que le bouton est maintenu (en bas à droite).
+    Fire,
+    /// Mettre la partie en pause (en haut, au centre).
+    Pause,
+}
+
+impl VirtualButton {
+    /// Tous les boutons, dans l'ordre d'affichage.
+    pub const ALL: [VirtualButton; 3] = [
+        VirtualButton::Thrust,
+        VirtualButton::Fire,
+        VirtualButton::Pause,
+    ];
+
+    /// Retourne le libellé affiché sur le bouton.
+    pub fn label(self) -> &'static str {
+        match self {
+            VirtualButton::Thrust => "Poussée",
+            VirtualButton::Fire => "Tir",
+            VirtualButton::Pause => "II",
+        }
+    }
+
+    /// Retourne la zone du bouton à l'écran.
+    ///
+    /// # Arguments
+    ///
+    /// * `screen` - La taille de l'écran.
+    ///
+    /// # Retour
+    ///
+    /// Le centre et le rayon du bouton, assez grand pour un pouce.
+    pub fn area(self, screen: Vec2) -> (Vec2, f32) {
+        let radius = (screen.min_element() * 0.09).max(40.);
+        match self {
+            VirtualButton::Thrust => (vec2(radius * 1.5, screen.y - radius * 1.5), radius),
+            VirtualButton::Fire => (screen - Vec2::splat(radius * 1.5), radius),
+            VirtualButton::Pause => (vec2(screen.x / 2., radius * 0.7), radius * 0.5),
+        }
+    }
+
+    /// Retrouve le bouton sous un point de l'écran.
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - Le point touché ou cliqué.
+    /// * `screen` - La taille de l'écran.
+    ///
+    /// # Retour
+    ///
+    /// Le bouton qui contient le point, s'il y en a un.
+    pub fn at(point: Vec2, screen: Vec2) -> Option<Self> {
+        Self::ALL.into_iter().find(|button| {
+            let (center, radius) = button.area(screen);
+            point.distance(center) <= radius
+        })
+    }
+}
+
+/// Points de contact de l'écran tactile ou de la souris pendant une frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Pointers {
+    /// Position du pointeur de la souris, qui vise sans clic.
+    pub hover: Option<Vec2>,
+    /// Positions des doigts posés sur l'écran, ou de la souris bouton gauche enfoncé.
+    pub held: Vec<Vec2>,
+    /// Le clic gauche hors des boutons tire (souris) ; un doigt posé hors des boutons ne
+    /// fait que viser.
+    pub click_fires: bool,
+    /// Bouton droit de la souris enfoncé : accélérer.
+    pub thrust: bool,
+}
+
+impl Pointers {
+    /// Lit l'écran tactile, ou la souris si aucun doigt n'est posé.
+    ///
+    /// macroquad simule aussi la souris avec le premier doigt : les doigts sont donc lus en
+    /// priorité pour ne pas compter deux fois le même contact.
+    ///
+    /// # Retour
+    ///
+    /// Les points de contact de la frame.
+    pub fn read() -> Self {
+        let fingers: Vec<Vec2> = touches()
+            .iter()
+            .filter(|touch| !matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled))
+            .map(|touch| touch.position)
+            .collect();
+        if !fingers.is_empty() {
+            return Self {
+                held: fingers,
+                ..Default::default()
+            };
+        }
+        let mouse = Vec2::from(mouse_position());
+        Self {
+            hover: Some(mouse),
+            held: if is_mouse_button_down(MouseButton::Left) {
+                vec![mouse]
+            } else {
+                Vec::new()
+            },
+            click_fires: true,
+            thrust: is_mouse_button_down(MouseButton::Right),
+        }
+    }
+
+    /// Convertit les points de contact en commandes du vaisseau.
+    ///
+    /// # Arguments
+    ///
+    /// * `ship` - La position de le vaisseau à l'écran.
+    /// * `screen` - La taille de l'écran, qui place les boutons.
+    ///
+    /// # Retour
+    ///
+    /// Les commandes : accélération et tir par les boutons, direction visée vers le dernier
+    /// point posé hors des boutons, ou vers le pointeur de la souris.
+    pub fn to_input(&self, ship: Vec2, screen: Vec2) -> ShipInput {
+        let mut input = ShipInput {
+            thrust: self.thrust,
+            ..Default::default()
+        };
+        let mut aim = self
+            .hover
+            .filter(|&hover| VirtualButton::at(hover, screen).is_none());
+        for &point in &self.held {
+            match VirtualButton::at(point, screen) {
+                Some(VirtualButton::Thrust) => input.thrust = true,
+                Some(VirtualButton::Fire) => input.fire = true,
+                Some(VirtualButton::Pause) => {}
+                None => {
+                    input.fire |= self.click_fires;
+                    aim = Some(point);
+                }
+            }
+        }
+        input.heading = aim
+            .filter(|&aim| aim != ship)
+            .map(|aim| heading_towards(aim - ship));
+        input
+    }
+}
+
+/// Indique si le bouton de pause affiché à l'écran vient d'être touché ou cliqué.
+///
+/// # Retour
+///
+/// `true` si un doigt s'est posé, ou si la souris a cliqué, sur le bouton de pause.
+pub fn is_virtual_pause_pressed() -> bool {
+    let screen = vec2(screen_width(), screen_height());
+    let pressed = |point: Vec2| VirtualButton::at(point, screen) == Some(VirtualButton::Pause);
+    let touches = touches();
+    if touches.is_empty() {
+        is_mouse_button_pressed(MouseButton::Left) && pressed(Vec2::from(mouse_position()))
+    } else {
+        touches
+            .iter()
+            .any(|touch| touch.phase == TouchPhase::Started && pressed(touch.position))
+    }
+}
+
+/// Dessine les boutons des commandes tactiles, éclairés lorsqu'ils sont enfoncés.
+pub fn draw_virtual_buttons() {
+    let screen = vec2(screen_width(), screen_height());
+    let pointers = Pointers::read();
+    for button in VirtualButton::ALL {
+        let (center, radius) = button.area(screen);
+        let active = pointers
+            .held
+            .iter()
+            .any(|&point| VirtualButton::at(point, screen) == Some(button));
+        let alpha = if active { 0.5 } else { 0.2 };
+        draw_circle(center.x, center.y, radius, Color::new(1., 1., 1., alpha));
+        draw_circle_lines(center.x, center.y, radius, 2., Color::new(1., 1., 1., 0.6));
+        let font_size = (radius * 0.45) as u16;
+        let size = measure_ui_text(button.label(), font_size);
+        draw_ui_text(
+            button.label(),
+            center.x - size.width / 2.,
+            center.y + size.height / 2.,
+            font_size as f32,
+            WHITE,
+        );
+    }
+}
+
+/// Manettes branchées, attribuées aux joueurs dans l'ordre de branchement.
+///
+/// Une manette débranchée libère sa place ; la prochaine manette branchée la reprend, si
+/// bien que le joueur qui rebranche sa manette retrouve son vaisseau.
+///
+/// Dans la version web, aucune manette n'est jamais branchée.
+pub struct Gamepads {
+    /// Le contexte gilrs, ou `None` si les manettes ne sont pas disponibles.
+    #[cfg(not(target_arch = "wasm32"))]
+    gilrs: Option<Gilrs>,
+    /// La manette de chaque joueur.
+    #[cfg(not(target_arch = "wasm32"))]
+    slots: Vec<Option<GamepadId>>,
+    /// Les boutons enfoncés pendant la frame, avec le joueur de la manette.
+    pressed: Vec<(usize, PadButton)>,
+}
+
+impl Gamepads {
+    /// Ouvre les manettes et attribue celles déjà branchées aux premiers joueurs.
+    ///
+    /// Si les manettes ne sont pas disponibles sur ce système, le jeu continue sans elles.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `Gamepads`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new() -> Self {
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(error) => {
+                eprintln!("Manettes indisponibles : {}", error);
+                None
+            }
+        };
+        let slots = gilrs.as_ref().map_or(Vec::new(), |gilrs| {
+            gilrs.gamepads().map(|(id, _)| Some(id)).collect()
+        });
+        Self {
+            gilrs,
+            slots,
+            pressed: Vec::new(),
+        }
+    }
+
+    /// Crée la liste des manettes de la version web, toujours vide.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `Gamepads`.
+    #[cfg(target_arch = "wasm32")]
+    pub fn new() -> Self {
+        Self {
+            pressed: Vec::new(),
+        }
+    }
+
+    /// Lit les événements des manettes : branchements, débranchements et boutons enfoncés.
+    ///
+    /// À appeler une fois par frame.
+    ///
+    /// # Retour
+    ///
+    /// Les changements de branchement de la frame : le joueur, puis `true` pour une manette
+    /// branchée ou `false` pour une manette débranchée.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn update(&mut self) -> Vec<(usize, bool)> {
+        self.pressed.clear();
+        let mut changes = Vec::new();
+        let Some(gilrs) = &mut self.gilrs else {
+            return changes;
+        };
+        while let Some(event) = gilrs.next_event() {
+            let slot = self.slots.iter().position(|&slot| slot == Some(event.id));
+            match (event.event, slot) {
+                (EventType::Connected, None) => {
+                    let free = self.slots.iter().position(Option::is_none);
+                    let player = free.unwrap_or(self.slots.len());
+                    if player == self.slots.len() {
+                        self.slots.push(None);
+                    }
+                    self.slots[player] = Some(event.id);
+                    changes.push((player, true));
+                }
+                (EventType::Disconnected, Some(player)) => {
+                    self.slots[player] = None;
+                    changes.push((player, false));
+                }
+                (EventType::ButtonPressed(code, _), Some(player)) => {
+                    if let Some(button) = PadButton::from_gilrs(code) {
+                        self.pressed.push((player, button));
+                    }
+                }
+                _ => {}
+            }
+        }
+        changes
+    }
+
+    /// Version web de `update` : aucune manette n'est jamais branchée.
+    ///
+    /// # Retour
+    ///
+    /// Aucun changement de branchement.
+    #[cfg(target_arch = "wasm32")]
+    pub fn update(&mut self) -> Vec<(usize, bool)> {
+        Vec::new()
+    }
+
+    /// Indique si le joueur vient d'enfoncer un bouton de sa manette.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur.
+    /// * `button` - Le bouton.
+    ///
+    /// # Retour
+    ///
+    /// `true` si le bouton a été enfoncé pendant cette frame.
+    pub fn is_pressed(&self, player: usize, button: PadButton) -> bool {
+        self.pressed.contains(&(player, button))
+    }
+
+    /// Lit l'état de la manette d'un joueur.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur.
+    /// * `bindings` - Les boutons choisis par le joueur.
+    ///
+    /// # Retour
+    ///
+    /// L'état de la manette, ou `None` si le joueur n'en a pas.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn get_state(&self, player: usize, bindings: &GamepadBindings) -> Option<PadState> {
+        let gilrs = self.gilrs.as_ref()?;
+        let id = (*self.slots.get(player)?)?;
+        gilrs
+            .connected_gamepad(id)
+            .map(|gamepad| PadState::read(&gamepad, bindings))
+    }
+
+    /// Version web de `get_state` : aucun joueur n'a de manette.
+    ///
+    /// # Retour
+    ///
+    /// Toujours `None`.
+    #[cfg(target_arch = "wasm32")]
+    pub fn get_state(&self, _player: usize, _bindings: &GamepadBindings) -> Option<PadState> {
+        None
+    }
+
+    /// Lit les commandes d'un joueur sur le clavier, sa manette et, pour le premier joueur,
+    /// la souris.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur sur cette machine.
+    /// * `keys` - Les touches du joueur.
+    /// * `ship` - La position de son vaisseau à l'écran, pour la visée à la souris.
+    /// * `settings` - Les préférences : schéma de contrôle, mode du stick et boutons.
+    ///
+    /// # Retour
+    ///
+    /// Les commandes combinées de toutes les sources.
+    pub fn read_input(
+        &self,
+        player: usize,
+        keys: &KeyBindings,
+        ship: Vec2,
+        settings: &Settings,
+    ) -> ShipInput {
+        let mut input = ShipInput::from_keys(keys);
+        if let Some(state) = self.get_state(player, &settings.pad_buttons) {
+            input = input.merge(state.to_input(settings.stick_mode));
+        }
+        if player == 0 {
+            match settings.control_scheme {
+                ControlScheme::Keyboard => {}
+                ControlScheme::MouseAim => input = input.merge(mouse_aim_input(ship)),
+                ControlScheme::Touch => {
+                    let screen = vec2(screen_width(), screen_height());
+                    input = input.merge(Pointers::read().to_input(ship, screen));
+                }
+            }
+        }
+        input
+    }
+}
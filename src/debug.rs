@@ -0,0 +1,338 @@
+use crate::players::PLAYER_COLORS;
+use crate::stellarobject::StellarObject;
+use crate::text::draw_ui_text;
+use crate::world::World;
+use macroquad::prelude::*;
+
+/// Touche qui affiche ou masque la surcouche de débogage.
+pub const OVERLAY_KEY: KeyCode = KeyCode::F10;
+
+/// Touche qui affiche ou masque le panneau de triche.
+pub const CHEAT_KEY: KeyCode = KeyCode::F11;
+
+/// Échelle de temps la plus basse : la simulation fait 16 fois moins de pas que d'habitude,
+/// soit 7,5 pas de simulation par seconde.
+pub const MIN_TIME_SCALE: f32 = 0.0625;
+
+/// Échelle de temps la plus haute : la simulation fait 4 fois plus de pas que d'habitude,
+/// soit 480 pas de simulation par seconde.
+pub const MAX_TIME_SCALE: f32 = 4.;
+
+/// Nombre de pas de simulation représentés par les vecteurs vitesse : la pointe de la flèche
+/// montre où sera l'objet dans ce nombre de pas.
+const VELOCITY_STEPS: f32 = 20.;
+
+/// Taille des boutons du panneau de triche.
+const BUTTON_SIZE: Vec2 = vec2(220., 34.);
+
+/// Action choisie dans le panneau de triche.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheatAction {
+    /// Faire apparaître un grand astéroïde au bord de l'arène.
+    SpawnAsteroid,
+    /// Retirer un point de bouclier à chaque vaisseau.
+    ShieldDown,
+    /// Ajouter un point de bouclier à chaque vaisseau.
+    ShieldUp,
+    /// Activer ou désactiver l'invincibilité des vaisseaux.
+    ToggleGodMode,
+    /// Diviser l'échelle de temps par deux.
+    SlowDown,
+    /// Multiplier l'échelle de temps par deux.
+    SpeedUp,
+}
+
+impl CheatAction {
+    /// Les actions, dans l'ordre des boutons du panneau.
+    pub const ALL: [CheatAction; 6] = [
+        CheatAction::SpawnAsteroid,
+        CheatAction::ShieldDown,
+        CheatAction::ShieldUp,
+        CheatAction::ToggleGodMode,
+        CheatAction::SlowDown,
+        CheatAction::SpeedUp,
+    ];
+}
+
+/// Outils de réglage du jeu : surcouche de débogage, panneau de triche et échelle de temps.
+///
+/// La surcouche dessine par-dessus la partie ce que la simulation utilise sans le montrer :
+/// cercles de collision, portée de la gravité, vecteurs vitesse, grille de l'arène, nombre
+/// d'objets et leurs identifiants (indice dans leur liste, ou joueur pour les vaisseaux).
+pub struct DebugTools {
+    overlay_visible: bool,
+    panel_visible: bool,
+    time_scale: f32,
+}
+
+impl DebugTools {
+    /// Crée les outils, masqués, avec une échelle de temps normale.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `DebugTools`.
+    pub fn new() -> Self {
+        Self {
+            overlay_visible: false,
+            panel_visible: false,
+            time_scale: 1.,
+        }
+    }
+
+    /// Retourne l'échelle de temps : la vitesse de la simulation par rapport au temps réel.
+    pub fn get_time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Change l'échelle de temps.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - La nouvelle échelle, ramenée entre `MIN_TIME_SCALE` et `MAX_TIME_SCALE`.
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale = scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+    }
+
+    /// Affiche ou masque la surcouche et le panneau avec `OVERLAY_KEY` et `CHEAT_KEY`, puis
+    /// lit les clics sur le panneau. À appeler une fois par frame de jeu.
+    ///
+    /// # Retour
+    ///
+    /// L'action choisie dans le panneau pendant cette frame, s'il y en a une.
+    pub fn update(&mut self) -> Option<CheatAction> {
+        if is_key_pressed(OVERLAY_KEY) {
+            self.overlay_visible = !self.overlay_visible;
+        }
+        if is_key_pressed(CHEAT_KEY) {
+            self.panel_visible = !self.panel_visible;
+        }
+        if !self.panel_visible || !is_mouse_button_pressed(MouseButton::Left) {
+            return None;
+        }
+        let mouse = Vec2::from(mouse_position());
+        CheatAction::ALL
+            .into_iter()
+            .enumerate()
+            .find_map(|(index, action)| {
+                let position = button_position(index);
+                Rect::new(position.x, position.y, BUTTON_SIZE.x, BUTTON_SIZE.y)
+                    .contains(mouse)
+                    .then_some(action)
+            })
+    }
+
+    /// Dessine la partie de la surcouche qui suit les objets, si elle est visible. À appeler
+    /// avec la caméra de l'arène.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - La partie en cours.
+    pub fn draw_overlay(&self, world: &World) {
+        if self.overlay_visible {
+            draw_overlay(world);
+        }
+    }
+
+    /// Dessine le nombre d'objets de la surcouche et le panneau, s'ils sont visibles. À
+    /// appeler avec la caméra par défaut.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - La partie en cours.
+    /// * `particles` - Le nombre de particules vivantes, affiché avec les autres objets.
+    pub fn draw(&self, world: &World, particles: usize) {
+        if self.overlay_visible {
+            draw_counts(world, particles);
+        }
+        if self.panel_visible {
+            self.draw_panel(world);
+        }
+    }
+
+    /// Dessine les boutons du panneau de triche sur le bord droit de l'écran.
+    fn draw_panel(&self, world: &World) {
+        let origin = button_position(0);
+        draw_ui_text(
+            &format!("Triche ({:?})  temps x{:.2}", CHEAT_KEY, self.time_scale),
+            origin.x,
+            origin.y - 8.,
+            18.,
+            YELLOW,
+        );
+        for (index, action) in CheatAction::ALL.into_iter().enumerate() {
+            let label = match action {
+                CheatAction::SpawnAsteroid => "Astéroïde +1".to_string(),
+                CheatAction::ShieldDown => "Bouclier -1".to_string(),
+                CheatAction::ShieldUp => "Bouclier +1".to_string(),
+                CheatAction::ToggleGodMode => format!(
+                    "Invincible : {}",
+                    if world.god_mode { "oui" } else { "non" }
+                ),
+                CheatAction::SlowDown => "Temps x0.5".to_string(),
+                CheatAction::SpeedUp => "Temps x2".to_string(),
+            };
+            let position = button_position(index);
+            draw_rectangle(
+                position.x,
+                position.y,
+                BUTTON_SIZE.x,
+                BUTTON_SIZE.y,
+                Color::new(0.3, 0.3, 0.3, 0.8),
+            );
+            draw_ui_text(&label, position.x + 10., position.y + 23., 20., WHITE);
+        }
+    }
+}
+
+/// Retourne le coin supérieur gauche du bouton d'indice `index` du panneau de triche, sous
+/// le graphe des images par seconde.
+fn button_position(index: usize) -> Vec2 {
+    vec2(
+        screen_width() - BUTTON_SIZE.x - 10.,
+        140. + index as f32 * (BUTTON_SIZE.y + 6.),
+    )
+}
+
+/// Retourne le côté des cellules de la grille de débogage : la plus grande portée
+/// d'interaction, celle de la gravité d'un astéroïde de niveau 3. Deux objets qui
+/// interagissent sont donc toujours dans la même cellule ou dans deux cellules voisines.
+///
+/// # Arguments
+///
+/// * `world` - La partie, pour la taille des objets.
+pub fn grid_cell_size(world: &World) -> f32 {
+    ((world.level_size.0 + world.gravite_dist) * 2.).max(1.)
+}
+
+/// Retourne le rayon de collision d'un astéroïde d'un niveau donné.
+fn asteroid_radius(level: u8, level_size: (f32, f32, f32)) -> f32 {
+    match level {
+        1 => level_size.2,
+        2 => level_size.1,
+        3 => level_size.0,
+        _ => 0.,
+    }
+}
+
+/// Retourne le nombre de colonnes et de lignes de la grille de débogage.
+fn grid_dimensions(world: &World) -> (usize, usize) {
+    let cell = grid_cell_size(world);
+    (
+        (world.arena.x / cell).ceil().max(1.) as usize,
+        (world.arena.y / cell).ceil().max(1.) as usize,
+    )
+}
+
+/// Dessine la surcouche de débogage dans l'arène : grille (cellules occupées en
+/// surbrillance), portée de la gravité et cercle de collision des astéroïdes, cercles de
+/// collision des vaisseaux et des missiles, vecteurs vitesse et identifiants.
+///
+/// # Arguments
+///
+/// * `world` - La partie en cours.
+fn draw_overlay(world: &World) {
+    // Grille : les cellules sont d'autant plus claires qu'elles contiennent d'objets
+    let cell = grid_cell_size(world);
+    let (columns, rows) = grid_dimensions(world);
+    let mut occupancy = vec![0u32; columns * rows];
+    let positions = world
+        .asteroids
+        .iter()
+        .map(|asteroid| asteroid.get_position())
+        .chain(world.ships.iter().map(|vaisseau| vaisseau.get_position()))
+        .chain(world.missiles.iter().map(|missile| missile.get_position()));
+    for position in positions {
+        let column = ((position.x / cell) as usize).min(columns - 1);
+        let row = ((position.y / cell) as usize).min(rows - 1);
+        occupancy[row * columns + column] += 1;
+    }
+    for (index, &count) in occupancy.iter().enumerate() {
+        let (x, y) = (
+            (index % columns) as f32 * cell,
+            (index / columns) as f32 * cell,
+        );
+        if count > 0 {
+            let alpha = (0.08 * count as f32).min(0.4);
+            draw_rectangle(x, y, cell, cell, Color::new(0.2, 0.6, 1., alpha));
+        }
+        draw_rectangle_lines(x, y, cell, cell, 1., Color::new(0.2, 0.6, 1., 0.25));
+    }
+
+    for (index, asteroid) in world.asteroids.iter().enumerate() {
+        let position = asteroid.get_position();
+        let radius = asteroid_radius(asteroid.get_level(), world.level_size);
+        draw_circle_lines(
+            position.x,
+            position.y,
+            radius + world.gravite_dist,
+            1.,
+            Color::new(0.8, 0.4, 1., 0.6),
+        );
+        draw_circle_lines(position.x, position.y, radius, 1., RED);
+        draw_velocity(position, asteroid.get_speed());
+        draw_ui_text(
+            &format!("A{} n{}", index, asteroid.get_level()),
+            position.x + radius,
+            position.y - radius,
+            14.,
+            WHITE,
+        );
+    }
+    for vaisseau in &world.ships {
+        let position = vaisseau.get_position();
+        let color = PLAYER_COLORS[vaisseau.get_player() % PLAYER_COLORS.len()];
+        draw_circle_lines(position.x, position.y, world.hauteur_vaisseau, 1., color);
+        draw_velocity(position, vaisseau.get_speed());
+        draw_ui_text(
+            &format!(
+                "J{} bouclier {:.1}",
+                vaisseau.get_player() + 1,
+                vaisseau.get_shield()
+            ),
+            position.x + world.hauteur_vaisseau,
+            position.y - world.hauteur_vaisseau,
+            14.,
+            color,
+        );
+    }
+    for (index, missile) in world.missiles.iter().enumerate() {
+        let position = missile.get_position();
+        draw_circle_lines(position.x, position.y, world.rayon_missile, 1., ORANGE);
+        draw_velocity(position, missile.get_speed());
+        draw_ui_text(
+            &format!("M{}", index),
+            position.x + world.rayon_missile,
+            position.y,
+            12.,
+            ORANGE,
+        );
+    }
+}
+
+/// Affiche en bas de l'écran le nombre d'objets de chaque sorte et la taille de la grille.
+///
+/// # Arguments
+///
+/// * `world` - La partie en cours.
+/// * `particles` - Le nombre de particules vivantes.
+fn draw_counts(world: &World, particles: usize) {
+    let (columns, rows) = grid_dimensions(world);
+    let counts = format!(
+        "Vaisseaux {}  Astéroïdes {}  Missiles {}  Particules {}  Grille {}x{} ({:.0} px)",
+        world.ships.len(),
+        world.asteroids.len(),
+        world.missiles.len(),
+        particles,
+        columns,
+        rows,
+        grid_cell_size(world)
+    );
+    draw_ui_text(&counts, 10., screen_height() - 10., 18., YELLOW);
+}
+
+/// Dessine le vecteur vitesse d'un objet, allongé de `VELOCITY_STEPS` pas de simulation.
+fn draw_velocity(position: Vec2, speed: Vec2) {
+    let tip = position + speed * VELOCITY_STEPS;
+    draw_line(position.x, position.y, tip.x, tip.y, 1., GREEN);
+    draw_circle(tip.x, tip.y, 2., GREEN);
+}
@@ -0,0 +1,18 @@
+// Table `EMBEDDED_ASSETS` générée par `build.rs` : (chemin relatif, contenu du fichier)
+include!(concat!(env!("OUT_DIR"), "/embedded_assets.rs"));
+
+/// Retourne le contenu d'une ressource intégrée à l'exécutable.
+///
+/// # Arguments
+///
+/// * `path` - Le chemin de la ressource relatif à la racine (par exemple `sprite/vaisseau.png`).
+///
+/// # Retour
+///
+/// Le contenu du fichier, ou `None` si la ressource n'a pas été intégrée.
+pub fn get(path: &str) -> Option<&'static [u8]> {
+    EMBEDDED_ASSETS
+        .iter()
+        .find(|(embedded_path, _)| *embedded_path == path)
+        .map(|(_, bytes)| *bytes)
+}
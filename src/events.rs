@@ -0,0 +1,120 @@
+use macroquad::prelude::Vec2;
+
+/// Événement émis par la simulation du jeu.
+///
+/// La simulation (collisions, tirs, fin de partie) ne joue ni son ni effet elle-même :
+/// elle publie des événements dans une `EventQueue`, et les différents abonnés
+/// (audio, particules, score, statistiques...) y réagissent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    /// Un astéroïde a été détruit par un missile.
+    AsteroidDestroyed {
+        /// Niveau de l'astéroïde détruit (3 = grand, 1 = petit).
+        level: u8,
+        /// Position de l'astéroïde au moment de sa destruction.
+        position: Vec2,
+        /// Joueur qui a tiré le missile.
+        player: usize,
+    },
+    /// Un missile a touché un astéroïde.
+    MissileImpact {
+        /// Position du missile au moment de l'impact.
+        position: Vec2,
+        /// Vitesse du missile au moment de l'impact.
+        direction: Vec2,
+    },
+    /// Un vaisseau a percuté un astéroïde ou a été touché par un missile.
+    ShipHit {
+        /// Joueur dont le vaisseau a été touché.
+        player: usize,
+        /// Points de bouclier perdus.
+        damage: f32,
+        /// Position de le vaisseau.
+        position: Vec2,
+        /// Point de contact entre le vaisseau et l'objet (utilisé pour spatialiser le son).
+        impact: Vec2,
+    },
+    /// Le réacteur de le vaisseau est allumé pendant ce pas de simulation.
+    ShipThrust {
+        /// Position de la tuyère (arrière de le vaisseau).
+        position: Vec2,
+        /// Direction de la flamme (opposée à l'avant de le vaisseau).
+        direction: Vec2,
+    },
+    /// Le bouclier d'un vaisseau est épuisé.
+    ShipDestroyed {
+        /// Joueur dont le vaisseau a été détruit.
+        player: usize,
+        /// Joueur dont le missile a porté le dernier coup, ou `None` si le vaisseau a été
+        /// détruit par un astéroïde.
+        killer: Option<usize>,
+        /// Position de le vaisseau.
+        position: Vec2,
+    },
+    /// Un vaisseau a tiré un missile.
+    MissileFired {
+        /// Joueur qui a tiré.
+        player: usize,
+        /// Position de départ du missile.
+        position: Vec2,
+    },
+    /// Tous les astéroïdes ont été détruits.
+    WaveCleared,
+    /// La partie est terminée.
+    GameOver {
+        /// `true` si le joueur a gagné, `false` s'il a perdu.
+        victory: bool,
+    },
+}
+
+/// Abonné aux événements du jeu.
+///
+/// Pour ajouter une nouvelle réaction (un son, un effet, un succès...), il suffit
+/// d'implémenter ce trait et de passer l'abonné à `EventQueue::dispatch`, sans toucher
+/// au code des collisions.
+pub trait EventListener {
+    /// Réagit à un événement.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - L'événement publié par la simulation.
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+/// File d'attente des événements émis pendant une frame.
+#[derive(Default)]
+pub struct EventQueue {
+    events: Vec<GameEvent>,
+}
+
+impl EventQueue {
+    /// Crée une file d'événements vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute un événement à la file.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - L'événement à publier.
+    pub fn push(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    /// Distribue tous les événements en attente à chaque abonné, puis vide la file.
+    ///
+    /// Les abonnés reçoivent les événements dans l'ordre de la liste, et chaque événement
+    /// est distribué à tous les abonnés avant de passer au suivant.
+    ///
+    /// # Arguments
+    ///
+    /// * `listeners` - Les abonnés qui doivent recevoir les événements.
+    pub fn dispatch(&mut self, listeners: &mut [&mut dyn EventListener]) {
+        for event in self.events.drain(..) {
+            for listener in listeners.iter_mut() {
+                listener.on_event(&event);
+            }
+        }
+    }
+}
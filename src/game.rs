@@ -0,0 +1,1295 @@
+use crate::audio::{self, AudioMixer, SoundEffect};
+use crate::camera::ArenaCamera;
+use crate::config_screen::ConfigScreen;
+use crate::console::{Console, ConsoleContext, ConsoleRequest};
+use crate::controls::{self, ControlScheme, Gamepads};
+use crate::debug::{CheatAction, DebugTools};
+use crate::events::{EventQueue, GameEvent};
+use crate::game_state::{GameMode, GameState};
+use crate::highscores::{self, HighScoreEntry, HighScoreTable};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::net::Snapshot;
+use crate::pacing::{FixedStep, FramePacer};
+use crate::particles::ParticleSystem;
+use crate::pause_menu::{self, PauseAction, PauseMenu};
+use crate::players::{Lives, COOP_EXTRA_LIVES, PLAYER_COLORS};
+use crate::rng;
+use crate::save::SaveGame;
+use crate::settings::Settings;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::spectator::SnapshotStream;
+use crate::stats::{Achievements, GameStats, Score};
+use crate::stellarobject::StellarObject;
+use crate::synth;
+use crate::text::{draw_ui_text, measure_ui_text};
+use crate::vaisseau::MAX_SHIELD;
+use crate::versus::{Scoreboard, VersusRules};
+use crate::world::{World, STEP_RATE};
+use crate::{Sounds, Textures};
+use macroquad::prelude::*;
+
+/// Durée (en secondes) d'une vague au-delà de laquelle elle ne rapporte plus de bonus de
+/// rapidité : le bonus vaut un point par seconde gagnée sur cette durée.
+const TIME_BONUS_DURATION: f64 = 120.;
+
+/// Durée (en secondes) de l'écran de transition entre deux vagues.
+const WAVE_TRANSITION_DURATION: f64 = 2.5;
+
+/// Touche maintenue pour afficher le tableau des frags pendant un match en versus.
+const SCOREBOARD_KEY: KeyCode = KeyCode::Tab;
+
+/// L'application complète : ressources, simulation, écrans et machine à états.
+///
+/// La boucle principale appelle `update` puis `draw` à chaque frame ; chacune de ces
+/// méthodes délègue à la fonction propre à l'état courant.
+pub struct Game {
+    state: GameState,
+    textures: Textures,
+    audio: AudioMixer,
+    world: World,
+    events: EventQueue,
+    gamepads: Gamepads,
+    particles: ParticleSystem,
+    score: Score,
+    scoreboard: Scoreboard,
+    versus_rules: VersusRules,
+    match_elapsed: f64,
+    stats: GameStats,
+    achievements: Achievements,
+    achievement_banner: Option<(&'static str, f64)>,
+    /// Message de branchement ou de débranchement d'une manette, et l'heure jusqu'à laquelle
+    /// l'afficher.
+    gamepad_notice: Option<(String, f64)>,
+    config_screen: ConfigScreen,
+    pause_menu: PauseMenu,
+    result_message: String,
+    /// Début de la vague en cours, sur l'horloge de la simulation : les pauses et la console
+    /// ouverte ne comptent pas dans sa durée.
+    wave_start_time: f64,
+    transition_until: f64,
+    mode: GameMode,
+    highscores: HighScoreTable,
+    /// Début de la partie, sur l'horloge de la simulation.
+    game_start_time: f64,
+    pending_entry: Option<HighScoreEntry>,
+    name_input: String,
+    highlighted_rank: Option<usize>,
+    settings: Settings,
+    asteroid_count: i32,
+    asteroid_speed: f32,
+    /// Nombre de vagues à terminer pour gagner la partie en cours.
+    waves: u32,
+    /// La partie en cours a utilisé la triche : son score n'entre pas au tableau des
+    /// meilleurs scores.
+    cheated: bool,
+    missing_assets: Vec<String>,
+    pacer: FramePacer,
+    /// Cadence fixe de la simulation, indépendante de celle des images.
+    fixed_step: FixedStep,
+    debug: DebugTools,
+    console: Console,
+    #[cfg(not(target_arch = "wasm32"))]
+    stream: Option<SnapshotStream>,
+    #[cfg(not(target_arch = "wasm32"))]
+    stream_tick: u64,
+}
+
+impl Game {
+    /// Crée le jeu sur l'écran titre.
+    ///
+    /// # Arguments
+    ///
+    /// * `textures` - Les textures chargées au démarrage.
+    /// * `sounds` - Les sons chargés au démarrage.
+    /// * `settings` - Les préférences du joueur.
+    /// * `missing_assets` - Les ressources introuvables, signalées sur l'écran titre.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `Game` dans l'état `GameState::Title`.
+    pub fn new(
+        textures: Textures,
+        sounds: Sounds,
+        settings: Settings,
+        missing_assets: Vec<String>,
+    ) -> Self {
+        let config_screen = ConfigScreen::new(&settings);
+        let pacer = FramePacer::new(settings.frame_limit, settings.show_frame_graph);
+        Self {
+            state: GameState::Title,
+            textures,
+            audio: AudioMixer::new(sounds, &settings),
+            world: World::new(settings.arena_aspect),
+            events: EventQueue::new(),
+            gamepads: Gamepads::new(),
+            particles: ParticleSystem::new(config_screen.get_particle_quality()),
+            score: Score::new(),
+            scoreboard: Scoreboard::new(),
+            versus_rules: VersusRules::default(),
+            match_elapsed: 0.,
+            stats: GameStats::new(),
+            achievements: Achievements::new(),
+            achievement_banner: None,
+            gamepad_notice: None,
+            config_screen,
+            pause_menu: PauseMenu::new(),
+            result_message: String::new(),
+            wave_start_time: 0.,
+            transition_until: 0.,
+            mode: GameMode::Classic,
+            highscores: HighScoreTable::load(),
+            game_start_time: 0.,
+            pending_entry: None,
+            name_input: String::new(),
+            highlighted_rank: None,
+            settings,
+            asteroid_count: 0,
+            asteroid_speed: 0.,
+            waves: 1,
+            cheated: false,
+            missing_assets,
+            pacer,
+            fixed_step: FixedStep::new(STEP_RATE),
+            debug: DebugTools::new(),
+            console: Console::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            stream: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            stream_tick: 0,
+        }
+    }
+
+    /// Publie l'état de la partie dans un flux pour les spectateurs, à chaque frame de jeu.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - Le flux, ouvert en TCP ou enregistré dans un fichier.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_stream(&mut self, stream: SnapshotStream) {
+        self.stream = Some(stream);
+    }
+
+    /// Enregistre les préférences du joueur, avec les dernières valeurs de l'écran de configuration.
+    pub fn save_settings(&mut self) {
+        self.config_screen.store_settings(&mut self.settings);
+        self.settings.show_frame_graph = self.pacer.is_graph_visible();
+        if let Err(error) = self.settings.save() {
+            eprintln!("Impossible d'enregistrer les préférences : {}", error);
+        }
+    }
+
+    /// Passe dans un nouvel état en appelant l'action de sortie de l'ancien état
+    /// puis l'action d'entrée du nouveau.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - L'état dans lequel passer.
+    fn transition(&mut self, next: GameState) {
+        let previous = self.state;
+        self.on_exit(previous);
+        self.state = next;
+        self.on_enter(next);
+    }
+
+    /// Action exécutée une seule fois en quittant un état.
+    fn on_exit(&mut self, state: GameState) {
+        match state {
+            GameState::Setup => {
+                self.audio.stop(SoundEffect::Lose);
+                self.audio.stop(SoundEffect::Win);
+                self.save_settings();
+            }
+            GameState::WaveTransition => self.start_next_wave(),
+            _ => {}
+        }
+    }
+
+    /// Action exécutée une seule fois en entrant dans un état.
+    fn on_enter(&mut self, state: GameState) {
+        // La musique démarre et s'arrête en fondu
+        if state.plays_music() {
+            self.audio.start_music();
+        } else {
+            self.audio.stop_music();
+        }
+
+        match state {
+            GameState::Setup => self.config_screen.set_save_available(SaveGame::exists()),
+            GameState::Paused => self.pause_menu.reset(),
+            GameState::WaveTransition => {
+                self.transition_until = get_time() + WAVE_TRANSITION_DURATION;
+            }
+            GameState::GameOver => {
+                let ranked = self.mode.has_leaderboard() && !self.cheated;
+                self.pending_entry = ranked.then(|| HighScoreEntry {
+                    name: String::new(),
+                    score: self.score.get(),
+                    wave: self.world.wave,
+                    duration: self.world.time - self.game_start_time,
+                    seed: self.world.seed,
+                    date: highscores::now_unix(),
+                });
+            }
+            GameState::HighScoreEntry => {
+                self.name_input.clear();
+                // Vider les caractères tapés avant l'ouverture de la saisie
+                while get_char_pressed().is_some() {}
+            }
+            _ => {}
+        }
+    }
+
+    /// Réinitialise la simulation, le score et les statistiques pour une nouvelle partie.
+    ///
+    /// En coopération (deux joueurs au plus), chaque joueur a `COOP_EXTRA_LIVES` vies
+    /// supplémentaires ; une partie à un joueur se termine dès que le vaisseau est détruit. En
+    /// versus, les vaisseaux réapparaissent sans limite jusqu'à la fin du match. Les triches
+    /// de la partie précédente (invincibilité, échelle de temps) sont annulées.
+    fn new_game(&mut self) {
+        self.asteroid_count = self.config_screen.get_asteroid_count();
+        self.asteroid_speed = self.config_screen.get_asteroid_speed();
+        self.waves = self.settings.waves;
+        self.cheated = false;
+        self.world.god_mode = false;
+        self.debug.set_time_scale(1.);
+        let (players, shared_lives, friendly_fire) = self.config_screen.get_coop_options();
+        let (versus, rules) = self.config_screen.get_versus_options();
+        let players = if versus { players } else { players.min(2) };
+        let extra_lives = if players > 1 && !versus {
+            COOP_EXTRA_LIVES
+        } else {
+            0
+        };
+        self.mode = GameMode::for_match(players, versus);
+        self.world.players = players;
+        self.world.lives = Lives::new(players, extra_lives, shared_lives);
+        self.world.friendly_fire = friendly_fire && !versus;
+        self.world.versus = versus;
+        self.world.respawns.clear();
+        self.versus_rules = rules;
+        self.match_elapsed = 0.;
+        self.scoreboard = Scoreboard::new();
+        self.world.wave = 1;
+        self.world.reseed(rng::random_seed());
+        self.world.time = 0.;
+        self.world.arena = self.settings.arena_aspect.get_size();
+        crate::reset_game(
+            &mut self.world.asteroids,
+            &mut self.world.ships,
+            players,
+            &mut self.world.missiles,
+            self.world.level_size,
+            self.world.arena,
+            self.asteroid_count,
+            self.asteroid_speed,
+            &mut self.score,
+            false,
+            &mut self.world.rng,
+        );
+        self.assign_key_bindings();
+        self.stats = GameStats::new();
+        self.achievements.start_game();
+        self.particles.clear();
+        self.wave_start_time = self.world.time;
+        self.game_start_time = self.world.time;
+    }
+
+    /// Attribue à chaque vaisseau, et à chaque vaisseau en attente de réapparition, les
+    /// touches de son joueur.
+    fn assign_key_bindings(&mut self) {
+        for vaisseau in self.world.ships.iter_mut() {
+            vaisseau.set_key_bindings(self.settings.player_keys(vaisseau.get_player()));
+        }
+        for respawn in self.world.respawns.iter_mut() {
+            respawn.keys = self.settings.player_keys(respawn.player);
+        }
+    }
+
+    /// Fait avancer la simulation du temps écoulé pendant la frame, par pas fixes de
+    /// `1 / STEP_RATE` seconde, avec les commandes lues au clavier, aux manettes et à la
+    /// souris. L'échelle de temps des outils de débogage accélère ou ralentit ce temps, ainsi
+    /// que le chronomètre du match en versus et les particules.
+    fn step_world(&mut self) {
+        if let Some(action) = self.debug.update() {
+            self.apply_cheat(action);
+        }
+        // La visée à la souris et au toucher compare des positions à l'écran
+        let camera = ArenaCamera::for_screen(self.world.arena);
+        let elapsed = get_frame_time() as f64 * self.debug.get_time_scale() as f64;
+        let steps = self.fixed_step.advance(elapsed);
+        for _ in 0..steps {
+            for vaisseau in self.world.ships.iter_mut() {
+                let input = self.gamepads.read_input(
+                    vaisseau.get_player(),
+                    &vaisseau.get_key_bindings(),
+                    camera.world_to_screen(vaisseau.get_position()),
+                    &self.settings,
+                );
+                vaisseau.set_input(input);
+            }
+            self.world.step(&mut self.events);
+            #[cfg(not(target_arch = "wasm32"))]
+            self.publish_snapshot();
+        }
+        let simulated = steps as f64 / STEP_RATE;
+        if self.world.versus {
+            self.match_elapsed += simulated;
+        }
+        self.particles.update(simulated as f32);
+    }
+
+    /// Applique une action du panneau de triche, et marque la partie comme trichée.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - L'action choisie.
+    fn apply_cheat(&mut self, action: CheatAction) {
+        self.cheated = true;
+        match action {
+            CheatAction::SpawnAsteroid => crate::spawn_asteroids(
+                &mut self.world.asteroids,
+                self.world.level_size,
+                self.world.arena,
+                1,
+                self.asteroid_speed,
+                None,
+                &mut self.world.rng,
+            ),
+            CheatAction::ShieldDown | CheatAction::ShieldUp => {
+                let delta = if action == CheatAction::ShieldUp {
+                    1.
+                } else {
+                    -1.
+                };
+                for vaisseau in self.world.ships.iter_mut() {
+                    vaisseau.set_shield((vaisseau.get_shield() + delta).clamp(0., MAX_SHIELD));
+                }
+            }
+            CheatAction::ToggleGodMode => self.world.god_mode = !self.world.god_mode,
+            CheatAction::SlowDown => self.debug.set_time_scale(self.debug.get_time_scale() / 2.),
+            CheatAction::SpeedUp => self.debug.set_time_scale(self.debug.get_time_scale() * 2.),
+        }
+    }
+
+    /// Publie l'état de la partie dans le flux des spectateurs, s'il y en a un. Le flux est
+    /// fermé en cas d'erreur, sans interrompre la partie.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn publish_snapshot(&mut self) {
+        let Some(stream) = &mut self.stream else {
+            return;
+        };
+        let mut snapshot = Snapshot::capture(&self.world, self.stream_tick);
+        for (player, score) in snapshot.scores.iter_mut().enumerate() {
+            *score = self.score.get_player(player);
+        }
+        snapshot.frags = self.scoreboard.get_frags();
+        self.stream_tick += 1;
+        if let Err(error) = stream.publish(self.world.arena, &snapshot) {
+            eprintln!("Flux des spectateurs fermé : {}", error);
+            self.stream = None;
+        }
+    }
+
+    /// Retourne la position moyenne des vaisseaux, d'où les sons sont entendus.
+    ///
+    /// # Retour
+    ///
+    /// La position moyenne, ou `None` s'il ne reste aucun vaisseau.
+    fn listener_position(&self) -> Option<Vec2> {
+        let ships = &self.world.ships;
+        if ships.is_empty() {
+            return None;
+        }
+        let sum: Vec2 = ships.iter().map(|vaisseau| vaisseau.get_position()).sum();
+        Some(sum / ships.len() as f32)
+    }
+
+    /// Lance la vague suivante : les vaisseaux gardent leur bouclier, et deux astéroïdes
+    /// supplémentaires apparaissent à chaque vague.
+    fn start_next_wave(&mut self) {
+        self.world.start_wave(
+            self.world.wave + 1,
+            self.asteroid_count,
+            self.asteroid_speed,
+        );
+        self.wave_start_time = self.world.time;
+    }
+
+    /// Retourne l'avancement de la vague en cours.
+    ///
+    /// # Retour
+    ///
+    /// Le nombre de tirs encore nécessaires pour détruire tous les astéroïdes, et ce même
+    /// nombre au début de la vague.
+    fn wave_progress(&self) -> (u32, u32) {
+        // Chaque vague commence avec des astéroïdes de niveau 3
+        let wave_size = (self.asteroid_count + 2 * (self.world.wave as i32 - 1)).max(0) as u32;
+        let remaining = self
+            .world
+            .asteroids
+            .iter()
+            .map(|asteroid| synth::hits_to_clear(asteroid.get_level()))
+            .sum();
+        (remaining, wave_size * synth::hits_to_clear(3))
+    }
+
+    /// Calcule l'intervalle du rythme de fond, qui accélère à mesure que la vague se vide.
+    ///
+    /// # Retour
+    ///
+    /// L'intervalle entre deux battements, ou `None` hors partie.
+    fn heartbeat_interval(&self) -> Option<f64> {
+        if self.state != GameState::Playing {
+            return None;
+        }
+        let (remaining, total) = self.wave_progress();
+        Some(synth::heartbeat_interval(remaining, total))
+    }
+
+    /// Calcule l'intensité de la partie qui pilote les couches de la musique : elle monte
+    /// quand la vague se vide, quand le bouclier baisse et pendant la dernière vague.
+    fn music_intensity(&self) -> f32 {
+        if self.state != GameState::Playing {
+            return 0.;
+        }
+        let (remaining, total) = self.wave_progress();
+        let remaining = if total == 0 {
+            0.
+        } else {
+            remaining as f32 / total as f32
+        };
+        // Le bouclier le plus bas de l'équipe donne le niveau de danger
+        let shield = self
+            .world
+            .ships
+            .iter()
+            .map(|vaisseau| vaisseau.get_shield())
+            .fold(MAX_SHIELD, f32::min);
+        audio::music_intensity(
+            remaining,
+            shield / MAX_SHIELD,
+            self.world.wave >= self.waves,
+        )
+    }
+
+    /// Enregistre la partie en cours pour pouvoir la reprendre plus tard.
+    fn save_game(&self) {
+        if let Err(error) = self.capture_save().save() {
+            eprintln!("Impossible d'enregistrer la partie : {}", error);
+        }
+    }
+
+    /// Capture la partie en cours dans une sauvegarde.
+    ///
+    /// # Retour
+    ///
+    /// La sauvegarde, avec le score, les statistiques et l'avancement du match.
+    fn capture_save(&self) -> SaveGame {
+        let now = self.world.time;
+        let mut save = SaveGame::capture(&self.world);
+        save.score = self.score.get();
+        for (player, score) in save.player_scores.iter_mut().enumerate() {
+            *score = self.score.get_player(player);
+        }
+        save.elapsed = now - self.game_start_time;
+        save.wave_elapsed = now - self.wave_start_time;
+        save.asteroid_count = self.asteroid_count;
+        save.asteroid_speed = self.asteroid_speed;
+        save.waves = self.waves;
+        save.cheated = self.cheated;
+        save.stats = self.stats.clone();
+        if self.world.versus {
+            save.versus = Some(self.versus_rules);
+        }
+        save.match_elapsed = self.match_elapsed;
+        save.scoreboard = self.scoreboard.clone();
+        save
+    }
+
+    /// Reprend la partie sauvegardée. La sauvegarde est supprimée une fois chargée.
+    ///
+    /// # Retour
+    ///
+    /// `true` si la partie a été restaurée, `false` si la sauvegarde est illisible.
+    fn continue_game(&mut self) -> bool {
+        let save = match SaveGame::load() {
+            Ok(save) => save,
+            Err(error) => {
+                eprintln!("Impossible de reprendre la partie : {}", error);
+                self.config_screen.set_save_available(false);
+                return false;
+            }
+        };
+        // La partie reprise ne garde que la triche enregistrée dans la sauvegarde
+        self.cheated = false;
+        self.restore_game(save);
+        SaveGame::delete();
+        true
+    }
+
+    /// Remplace la partie en cours par une partie sauvegardée. Une partie déjà trichée le
+    /// reste, même si la sauvegarde ne l'était pas.
+    ///
+    /// # Arguments
+    ///
+    /// * `save` - La sauvegarde.
+    fn restore_game(&mut self, save: SaveGame) {
+        let now = self.world.time;
+        save.restore(&mut self.world);
+        self.assign_key_bindings();
+        self.mode = GameMode::for_match(save.players, save.versus.is_some());
+        self.versus_rules = save.versus.unwrap_or_default();
+        self.match_elapsed = save.match_elapsed;
+        self.scoreboard = save.scoreboard.clone();
+        self.asteroid_count = save.asteroid_count;
+        self.asteroid_speed = save.asteroid_speed;
+        self.waves = save.waves;
+        self.cheated |= save.cheated;
+        self.world.god_mode = false;
+        self.debug.set_time_scale(1.);
+        self.score.reset();
+        for (player, &score) in save.player_scores.iter().enumerate() {
+            self.score.add_player(player, score);
+        }
+        // Le reste du score total vient des bonus communs
+        self.score
+            .add(save.score - save.player_scores.iter().sum::<i32>());
+        self.stats = save.stats;
+        self.achievements.start_game();
+        self.particles.clear();
+        self.game_start_time = now - save.elapsed;
+        self.wave_start_time = now - save.wave_elapsed;
+    }
+
+    /// Exécute une ligne de la console, puis les demandes de ses commandes qui portent sur
+    /// toute la partie (sauvegarde et chargement d'un emplacement).
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - La ligne validée dans la console.
+    fn run_console_line(&mut self, line: &str) {
+        let mut context = ConsoleContext {
+            world: &mut self.world,
+            debug: &mut self.debug,
+            asteroid_count: self.asteroid_count,
+            asteroid_speed: self.asteroid_speed,
+            requests: Vec::new(),
+            cheated: self.cheated,
+        };
+        self.console.execute(line, &mut context);
+        self.cheated = context.cheated;
+        for request in context.requests {
+            let result = match request {
+                ConsoleRequest::Save(slot) => self.capture_save().save_slot(&slot),
+                ConsoleRequest::Load(slot) => {
+                    SaveGame::load_slot(&slot).map(|save| self.restore_game(save))
+                }
+            };
+            if let Err(error) = result {
+                self.console.print(&format!("erreur : {}", error));
+            }
+        }
+    }
+
+    /// Met à jour l'état courant.
+    ///
+    /// # Retour
+    ///
+    /// `false` si le joueur a demandé à quitter le jeu (bouton "Exit" ou fermeture de la
+    /// fenêtre), sinon `true`.
+    pub fn update(&mut self) -> bool {
+        if is_quit_requested() {
+            return false;
+        }
+        self.pacer.update();
+        for (player, connected) in self.gamepads.update() {
+            let state = if connected {
+                "branchée"
+            } else {
+                "débranchée"
+            };
+            let notice = format!("Manette du joueur {} {}", player + 1, state);
+            self.gamepad_notice = Some((notice, get_time() + 3.));
+            if !connected {
+                // Le joueur ne contrôle plus son vaisseau : figer la partie
+                if player < self.world.players && self.state == GameState::Playing {
+                    self.transition(GameState::Paused);
+                }
+            }
+        }
+        match self.state {
+            GameState::Title => self.update_title(),
+            GameState::Setup => {
+                if !self.update_setup() {
+                    return false;
+                }
+            }
+            GameState::Playing => self.update_playing(),
+            GameState::Paused => self.update_paused(),
+            GameState::WaveTransition => self.update_wave_transition(),
+            GameState::GameOver => self.update_game_over(),
+            GameState::HighScoreEntry => self.update_high_score_entry(),
+            GameState::Leaderboard => self.update_leaderboard(),
+        }
+
+        // En jeu, les particules suivent la simulation (voir `step_world`)
+        if !matches!(self.state, GameState::Paused | GameState::Playing) {
+            self.particles.update(get_frame_time());
+        }
+        self.audio.set_heartbeat(self.heartbeat_interval());
+        self.audio.set_music_intensity(self.music_intensity());
+        // Avec un seul vaisseau en jeu, ses propres tirs sont joués au centre
+        let listening_player = match self.world.ships.as_slice() {
+            [vaisseau] => Some(vaisseau.get_player()),
+            _ => None,
+        };
+        self.audio
+            .set_listener(self.listener_position(), listening_player, self.world.arena);
+        self.audio.update(get_frame_time());
+
+        // Distribuer les événements de la frame aux abonnés
+        self.events.dispatch(&mut [
+            &mut self.audio,
+            &mut self.particles,
+            &mut self.score,
+            &mut self.scoreboard,
+            &mut self.stats,
+            &mut self.achievements,
+        ]);
+        if let Some(name) = self.achievements.take_recent().pop() {
+            self.achievement_banner = Some((name, get_time() + 3.));
+        }
+        true
+    }
+
+    fn update_title(&mut self) {
+        if is_key_pressed(KeyCode::Enter)
+            || is_key_pressed(KeyCode::Space)
+            || is_mouse_button_pressed(MouseButton::Left)
+        {
+            self.transition(GameState::Setup);
+        }
+    }
+
+    /// Retourne `false` si le bouton "Exit" a été pressé.
+    fn update_setup(&mut self) -> bool {
+        self.config_screen.update();
+        self.particles
+            .set_quality(self.config_screen.get_particle_quality());
+        let (master, music, sfx, muted) = self.config_screen.get_volumes();
+        self.audio.set_volumes(master, music, sfx, muted);
+        self.settings.control_scheme = self.config_screen.get_control_scheme();
+
+        if self.config_screen.is_start_pressed() {
+            self.new_game();
+            self.transition(GameState::Playing);
+        } else if self.config_screen.is_continue_pressed() {
+            if self.continue_game() {
+                self.transition(GameState::Playing);
+            }
+        } else if self.config_screen.is_leaderboard_pressed() {
+            self.highlighted_rank = None;
+            let players = self.config_screen.get_coop_options().0;
+            // Le versus n'a pas de tableau : afficher celui de la coopération
+            self.mode = GameMode::for_match(players, false);
+            self.transition(GameState::Leaderboard);
+        }
+        !self.config_screen.is_exit_pressed()
+    }
+
+    /// Indique si un joueur de la partie a demandé la pause (ou la reprise) : chacun avec sa
+    /// propre touche ou sa manette, et le premier avec le bouton affiché à l'écran.
+    fn is_pause_pressed(&self) -> bool {
+        (0..self.world.players).any(|player| {
+            is_key_pressed(self.settings.player_keys(player).pause)
+                || self
+                    .gamepads
+                    .is_pressed(player, self.settings.pad_buttons.pause)
+        }) || (self.settings.control_scheme == ControlScheme::Touch
+            && controls::is_virtual_pause_pressed())
+    }
+
+    fn update_playing(&mut self) {
+        // La partie est figée tant que la console est ouverte
+        if let Some(line) = self.console.update() {
+            self.run_console_line(&line);
+        }
+        if self.console.is_open() {
+            return;
+        }
+        let pause_pressed = self.is_pause_pressed();
+        if self.world.versus {
+            self.update_versus(pause_pressed);
+        } else if self.world.ships.is_empty() {
+            // Tous les vaisseaux ont été détruits et aucune vie ne reste
+            self.events.push(GameEvent::GameOver { victory: false });
+            self.result_message = format!("Défaite ! Score : {}", self.score.get());
+            self.transition(GameState::GameOver);
+        } else if self.world.asteroids.is_empty() {
+            // La vague est terminée : bonus de rapidité, puis bonus de bouclier
+            let wave_duration = self.world.time - self.wave_start_time;
+            let time_bonus = (TIME_BONUS_DURATION - wave_duration).max(0.).round() as i32;
+            self.score.add(time_bonus);
+            for vaisseau in &self.world.ships {
+                let shield_bonus = (vaisseau.get_shield() as i32) * 5;
+                self.score.add_player(vaisseau.get_player(), shield_bonus);
+            }
+            self.events.push(GameEvent::WaveCleared);
+
+            if self.world.wave >= self.waves {
+                self.events.push(GameEvent::GameOver { victory: true });
+                self.result_message = format!("Victoire ! Score : {}", self.score.get());
+                self.transition(GameState::GameOver);
+            } else {
+                self.transition(GameState::WaveTransition);
+            }
+        } else if pause_pressed || pause_menu::is_long_frame(get_frame_time()) {
+            // Mettre la partie en pause (touche de pause, ou frame anormalement longue)
+            self.transition(GameState::Paused);
+        } else {
+            self.step_world();
+        }
+    }
+
+    /// Met à jour un match en versus : le match se termine à la limite de frags ou de temps,
+    /// et une nouvelle vague d'astéroïdes arrive dès que le champ est vide.
+    ///
+    /// # Arguments
+    ///
+    /// * `pause_pressed` - `true` si un joueur a appuyé sur sa touche de pause.
+    fn update_versus(&mut self, pause_pressed: bool) {
+        if self
+            .versus_rules
+            .is_over(&self.scoreboard, self.match_elapsed)
+        {
+            self.result_message = match self.scoreboard.leader(self.world.players) {
+                Some(player) => format!("Victoire du joueur {} !", player + 1),
+                None => "Égalité !".to_string(),
+            };
+            self.events.push(GameEvent::GameOver { victory: true });
+            self.transition(GameState::GameOver);
+        } else if pause_pressed || pause_menu::is_long_frame(get_frame_time()) {
+            self.transition(GameState::Paused);
+        } else {
+            if self.world.asteroids.is_empty() {
+                self.start_next_wave();
+            }
+            self.step_world();
+        }
+    }
+
+    fn update_paused(&mut self) {
+        match self.pause_menu.update(self.is_pause_pressed()) {
+            Some(PauseAction::Resume) => self.transition(GameState::Playing),
+            Some(PauseAction::Restart) => {
+                self.new_game();
+                self.transition(GameState::Playing);
+            }
+            Some(PauseAction::CycleParticles) => {
+                let quality = self.config_screen.get_particle_quality().next();
+                self.config_screen.set_particle_quality(quality);
+                self.particles.set_quality(quality);
+            }
+            Some(PauseAction::CycleControlScheme) => {
+                self.settings.control_scheme = self.settings.control_scheme.next();
+                self.config_screen
+                    .set_control_scheme(self.settings.control_scheme);
+            }
+            Some(PauseAction::CycleStickMode) => {
+                self.settings.stick_mode = self.settings.stick_mode.next();
+            }
+            Some(PauseAction::SaveAndQuit) => {
+                self.save_game();
+                self.transition(GameState::Setup);
+            }
+            Some(PauseAction::QuitToMenu) => {
+                self.result_message =
+                    format!("Vous avez quitté la partie ! Score : {}", self.score.get());
+                self.transition(GameState::GameOver);
+                // Une partie abandonnée n'entre pas au tableau des meilleurs scores
+                self.pending_entry = None;
+            }
+            None => {}
+        }
+    }
+
+    fn update_wave_transition(&mut self) {
+        if get_time() >= self.transition_until {
+            self.transition(GameState::Playing);
+        }
+    }
+
+    fn update_game_over(&mut self) {
+        if is_key_pressed(KeyCode::Enter) || is_mouse_button_pressed(MouseButton::Left) {
+            let qualifies = self
+                .pending_entry
+                .as_ref()
+                .is_some_and(|entry| self.highscores.qualifies(self.mode, entry.score));
+            if qualifies {
+                self.transition(GameState::HighScoreEntry);
+            } else {
+                self.pending_entry = None;
+                self.transition(GameState::Setup);
+            }
+        }
+    }
+
+    fn update_high_score_entry(&mut self) {
+        while let Some(c) = get_char_pressed() {
+            if !c.is_control() && self.name_input.chars().count() < highscores::MAX_NAME_LENGTH {
+                self.name_input.push(c);
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            self.name_input.pop();
+        }
+
+        if is_key_pressed(KeyCode::Enter) {
+            if let Some(mut entry) = self.pending_entry.take() {
+                entry.name = highscores::sanitize_name(&self.name_input);
+                self.highlighted_rank = self.highscores.insert(self.mode, entry);
+                if let Err(error) = self.highscores.save() {
+                    eprintln!("Impossible d'enregistrer les meilleurs scores : {}", error);
+                }
+            }
+            self.transition(GameState::Leaderboard);
+        } else if is_key_pressed(KeyCode::Escape) {
+            // Le joueur renonce à enregistrer son score
+            self.pending_entry = None;
+            self.transition(GameState::Setup);
+        }
+    }
+
+    fn update_leaderboard(&mut self) {
+        if is_key_pressed(KeyCode::Enter)
+            || is_key_pressed(KeyCode::Escape)
+            || is_mouse_button_pressed(MouseButton::Left)
+        {
+            self.transition(GameState::Setup);
+        }
+    }
+
+    /// Dessine l'état courant.
+    pub fn draw(&mut self) {
+        match self.state {
+            GameState::Title => self.draw_title(),
+            GameState::Setup => {
+                self.config_screen.draw();
+                // Les particules restantes continuent sur l'écran de configuration
+                set_camera(&ArenaCamera::for_screen(self.world.arena).to_camera());
+                self.particles.draw();
+                set_default_camera();
+            }
+            GameState::Playing => self.draw_world(),
+            GameState::Paused => {
+                self.draw_world();
+                self.pause_menu.draw(
+                    self.config_screen.get_particle_quality(),
+                    (self.settings.control_scheme, self.settings.stick_mode),
+                );
+            }
+            GameState::WaveTransition => {
+                self.draw_world();
+                draw_centered_text(
+                    &format!("Vague {} terminée !", self.world.wave),
+                    screen_height() * 0.4,
+                    50.0,
+                    YELLOW,
+                );
+                draw_centered_text(
+                    &format!("Préparez-vous pour la vague {}", self.world.wave + 1),
+                    screen_height() * 0.5,
+                    30.0,
+                    WHITE,
+                );
+            }
+            GameState::GameOver => self.draw_game_over(),
+            GameState::HighScoreEntry => self.draw_high_score_entry(),
+            GameState::Leaderboard => self.draw_leaderboard(),
+        }
+        self.draw_gamepad_notice();
+        self.console.draw();
+        self.pacer.draw();
+    }
+
+    /// Termine la frame affichée : attend si besoin pour tenir le rythme des images choisi.
+    /// À appeler après `next_frame().await`.
+    pub fn end_frame(&mut self) {
+        self.pacer.end_frame();
+    }
+
+    fn draw_title(&self) {
+        clear_background(BLACK);
+        draw_centered_text("ASTEROID", screen_height() * 0.4, 80.0, WHITE);
+        draw_centered_text(
+            "Appuyez sur Entrée ou cliquez pour continuer",
+            screen_height() * 0.6,
+            25.0,
+            GRAY,
+        );
+
+        // Signaler les ressources remplacées par des formes ou des sons générés
+        if !self.missing_assets.is_empty() {
+            draw_centered_text(
+                &format!(
+                    "{} ressource(s) introuvable(s), remplacée(s) par défaut : {}",
+                    self.missing_assets.len(),
+                    self.missing_assets.join(", ")
+                ),
+                screen_height() * 0.9,
+                20.0,
+                ORANGE,
+            );
+        }
+    }
+
+    /// Dessine la partie : arrière-plan, objets et particules vus par la caméra de l'arène,
+    /// puis informations de jeu à l'échelle de la fenêtre.
+    fn draw_world(&mut self) {
+        let world = &mut self.world;
+        let camera = ArenaCamera::for_screen(world.arena);
+        crate::draw_background(&self.textures.sprite_background);
+        set_camera(&camera.to_camera());
+        for vaisseau in &world.ships {
+            crate::draw_vaisseau(
+                vaisseau,
+                &self.textures.sprite_vaisseau,
+                world.hauteur_vaisseau,
+            );
+        }
+        crate::draw_missiles(
+            &world.missiles,
+            world.rayon_missile,
+            &self.textures.sprite_meteor,
+        );
+        crate::draw_asteroids_batched(
+            &mut world.asteroids,
+            world.level_size,
+            [
+                &self.textures.sprite_asteroid_1,
+                &self.textures.sprite_asteroid_2,
+                &self.textures.sprite_asteroid_3,
+            ],
+        );
+        self.particles.draw();
+        self.debug.draw_overlay(world);
+        set_default_camera();
+        camera.draw_letterbox();
+        self.debug.draw(world, self.particles.get_count());
+
+        let score_text = format!("Score: {}", self.score.get());
+        let wave_text = format!("Vague: {}/{}", world.wave, self.waves);
+
+        if world.players == 1 {
+            let shield = world
+                .ships
+                .first()
+                .map_or(0., |vaisseau| vaisseau.get_shield());
+            let shield_text = format!("Bouclier: {:.0}", shield);
+            draw_ui_text(&shield_text, 10.0, 30.0, 30.0, WHITE);
+            draw_ui_text(&score_text, 10.0, 70.0, 30.0, WHITE);
+            draw_ui_text(&wave_text, 10.0, 110.0, 30.0, WHITE);
+        } else if world.versus {
+            // Une ligne par joueur avec ses frags, puis le temps restant du match
+            let frags = self.scoreboard.get_frags();
+            for (player, &color) in PLAYER_COLORS.iter().enumerate().take(world.players) {
+                let shield = world
+                    .ships
+                    .iter()
+                    .find(|vaisseau| vaisseau.get_player() == player)
+                    .map(|vaisseau| format!("{:.0}", vaisseau.get_shield()))
+                    .unwrap_or_else(|| "réapparition...".to_string());
+                let text = format!(
+                    "J{} Bouclier: {}  Frags: {}",
+                    player + 1,
+                    shield,
+                    frags[player]
+                );
+                draw_ui_text(&text, 10.0, 30.0 + 30.0 * player as f32, 25.0, color);
+            }
+            let rules = self.versus_rules;
+            let time_text = match rules.remaining_time(self.match_elapsed) {
+                Some(remaining) => format!(
+                    "Temps: {}:{:02}",
+                    remaining as u32 / 60,
+                    remaining as u32 % 60
+                ),
+                None => "Temps: illimité".to_string(),
+            };
+            draw_ui_text(
+                &format!("{}  Objectif: {} frags", time_text, rules.frag_limit),
+                10.0,
+                30.0 + 30.0 * world.players as f32,
+                25.0,
+                WHITE,
+            );
+            if is_key_down(SCOREBOARD_KEY) {
+                draw_rectangle(
+                    screen_width() * 0.15,
+                    screen_height() * 0.25,
+                    screen_width() * 0.7,
+                    screen_height() * 0.5,
+                    Color::new(0., 0., 0., 0.7),
+                );
+                draw_centered_text("Tableau des frags", screen_height() * 0.33, 35.0, WHITE);
+                self.draw_scoreboard(screen_height() * 0.42);
+            }
+        } else {
+            // Une ligne par joueur, dans sa couleur, puis le score de l'équipe
+            for (player, &color) in PLAYER_COLORS.iter().enumerate().take(world.players) {
+                let shield = world
+                    .ships
+                    .iter()
+                    .find(|vaisseau| vaisseau.get_player() == player)
+                    .map(|vaisseau| format!("{:.0}", vaisseau.get_shield()))
+                    .unwrap_or_else(|| "détruit".to_string());
+                let text = format!(
+                    "J{} Bouclier: {}  Score: {}  Vies: {}",
+                    player + 1,
+                    shield,
+                    self.score.get_player(player),
+                    world.lives.get(player)
+                );
+                let y = 30.0 + 30.0 * player as f32;
+                draw_ui_text(&text, 10.0, y, 25.0, color);
+            }
+            let y = 30.0 + 30.0 * world.players as f32;
+            let lives_text = if world.lives.is_shared() {
+                "  (vies communes)"
+            } else {
+                ""
+            };
+            draw_ui_text(
+                &format!("Équipe: {}{}", score_text, lives_text),
+                10.0,
+                y,
+                25.0,
+                WHITE,
+            );
+            draw_ui_text(&wave_text, 10.0, y + 30.0, 25.0, WHITE);
+        }
+
+        if self.settings.control_scheme == ControlScheme::Touch {
+            controls::draw_virtual_buttons();
+        }
+
+        // Afficher le dernier succès débloqué pendant quelques secondes
+        if let Some((name, until)) = self.achievement_banner {
+            if get_time() < until {
+                let text = format!("Succès : {}", name);
+                let width = measure_ui_text(&text, 30).width;
+                draw_ui_text(&text, screen_width() - width - 10.0, 30.0, 30.0, GOLD);
+            } else {
+                self.achievement_banner = None;
+            }
+        }
+    }
+
+    /// Affiche en bas de l'écran, pendant quelques secondes, le branchement ou le
+    /// débranchement d'une manette.
+    fn draw_gamepad_notice(&mut self) {
+        if let Some((notice, until)) = &self.gamepad_notice {
+            if get_time() < *until {
+                draw_centered_text(notice, screen_height() - 40.0, 25.0, SKYBLUE);
+            } else {
+                self.gamepad_notice = None;
+            }
+        }
+    }
+
+    fn draw_game_over(&self) {
+        crate::draw_background(&self.textures.sprite_background);
+        // Les particules sont en unités du monde, comme sur l'écran de configuration
+        set_camera(&ArenaCamera::for_screen(self.world.arena).to_camera());
+        self.particles.draw();
+        set_default_camera();
+
+        draw_centered_text(&self.result_message, screen_height() * 0.35, 40.0, YELLOW);
+        draw_centered_text(
+            &format!(
+                "Vague atteinte : {}   Précision : {:.0} %   Astéroïdes détruits : {}",
+                self.world.wave,
+                self.stats.accuracy(),
+                self.stats.asteroids_destroyed.iter().sum::<u32>()
+            ),
+            screen_height() * 0.45,
+            25.0,
+            WHITE,
+        );
+        if self.world.versus {
+            self.draw_scoreboard(screen_height() * 0.5);
+        } else if self.world.players > 1 {
+            // Résultat combiné de la coopération : la part de chaque joueur dans le score
+            for (player, &color) in PLAYER_COLORS.iter().enumerate().take(self.world.players) {
+                draw_centered_text(
+                    &format!(
+                        "Joueur {} : {} points",
+                        player + 1,
+                        self.score.get_player(player)
+                    ),
+                    screen_height() * 0.5 + 30.0 * player as f32,
+                    25.0,
+                    color,
+                );
+            }
+        }
+        if self.cheated && self.mode.has_leaderboard() {
+            draw_centered_text(
+                "Triche utilisée : score non enregistré",
+                screen_height() * 0.65,
+                25.0,
+                ORANGE,
+            );
+        }
+        draw_centered_text(
+            "Appuyez sur Entrée ou cliquez pour revenir au menu",
+            screen_height() * 0.7,
+            25.0,
+            GRAY,
+        );
+    }
+
+    /// Dessine le tableau des frags d'un match en versus : une ligne par joueur, dans sa
+    /// couleur, avec ses frags, ses morts et les points gagnés sur les astéroïdes.
+    ///
+    /// # Arguments
+    ///
+    /// * `top` - La position verticale de la première ligne.
+    fn draw_scoreboard(&self, top: f32) {
+        let frags = self.scoreboard.get_frags();
+        let deaths = self.scoreboard.get_deaths();
+        for (player, &color) in PLAYER_COLORS.iter().enumerate().take(self.world.players) {
+            let leader = if self.scoreboard.leader(self.world.players) == Some(player) {
+                "> "
+            } else {
+                ""
+            };
+            draw_centered_text(
+                &format!(
+                    "{}Joueur {}   Frags : {}   Morts : {}   Astéroïdes : {} points",
+                    leader,
+                    player + 1,
+                    frags[player],
+                    deaths[player],
+                    self.score.get_player(player)
+                ),
+                top + 30.0 * player as f32,
+                25.0,
+                color,
+            );
+        }
+    }
+
+    fn draw_high_score_entry(&self) {
+        crate::draw_background(&self.textures.sprite_background);
+        let score = self.pending_entry.as_ref().map_or(0, |entry| entry.score);
+
+        draw_centered_text(
+            "Nouveau meilleur score !",
+            screen_height() * 0.3,
+            50.0,
+            GOLD,
+        );
+        draw_centered_text(
+            &format!("Score : {}", score),
+            screen_height() * 0.4,
+            35.0,
+            WHITE,
+        );
+        // Curseur clignotant à la fin du nom
+        let cursor = if (get_time() * 2.) as i64 % 2 == 0 {
+            "_"
+        } else {
+            " "
+        };
+        draw_centered_text(
+            &format!("Nom : {}{}", self.name_input, cursor),
+            screen_height() * 0.5,
+            35.0,
+            YELLOW,
+        );
+        draw_centered_text(
+            "Entrée pour valider, Échap pour ne pas enregistrer",
+            screen_height() * 0.6,
+            25.0,
+            GRAY,
+        );
+    }
+
+    fn draw_leaderboard(&self) {
+        crate::draw_background(&self.textures.sprite_background);
+        draw_centered_text(
+            &format!("Meilleurs scores - {}", self.mode.label()),
+            screen_height() * 0.15,
+            45.0,
+            WHITE,
+        );
+
+        // Position horizontale de chaque colonne, en fraction de la largeur de l'écran, et
+        // taille du texte de ses cases : la graine, en entier, est écrite plus petit
+        let columns = [
+            ("Rang", 0.05, 25.0),
+            ("Nom", 0.11, 25.0),
+            ("Score", 0.3, 25.0),
+            ("Vague", 0.4, 25.0),
+            ("Durée", 0.49, 25.0),
+            ("Graine", 0.58, 18.0),
+            ("Date", 0.84, 25.0),
+        ];
+        let top = screen_height() * 0.25;
+        for (title, x, _) in columns {
+            draw_ui_text(title, screen_width() * x, top, 25.0, GRAY);
+        }
+
+        let entries = self.highscores.entries(self.mode);
+        if entries.is_empty() {
+            draw_centered_text("Aucun score enregistré", top + 60.0, 30.0, WHITE);
+        }
+        for (rank, entry) in entries.iter().enumerate() {
+            let y = top + 35.0 * (rank as f32 + 1.0);
+            let color = if self.highlighted_rank == Some(rank) {
+                GOLD
+            } else {
+                WHITE
+            };
+            let cells = [
+                format!("{}", rank + 1),
+                entry.name.clone(),
+                entry.score.to_string(),
+                entry.wave.to_string(),
+                format!("{:.0} s", entry.duration),
+                entry.seed.to_string(),
+                highscores::format_date(entry.date),
+            ];
+            for (cell, (_, x, size)) in cells.iter().zip(columns) {
+                draw_ui_text(cell, screen_width() * x, y, size, color);
+            }
+        }
+
+        draw_centered_text(
+            "Appuyez sur Entrée ou cliquez pour revenir au menu",
+            screen_height() * 0.9,
+            25.0,
+            GRAY,
+        );
+    }
+}
+
+/// Dessine un texte centré horizontalement.
+///
+/// # Arguments
+///
+/// * `text` - Le texte à afficher.
+/// * `y` - La position verticale de la ligne de base du texte.
+/// * `font_size` - La taille de la police.
+/// * `color` - La couleur du texte.
+fn draw_centered_text(text: &str, y: f32, font_size: f32, color: Color) {
+    let width = measure_ui_text(text, font_size as u16).width;
+    draw_ui_text(
+        text,
+        screen_width() * 0.5 - width / 2.0,
+        y,
+        font_size,
+        color,
+    );
+}
@@ -0,0 +1,112 @@
+/// État courant de l'application.
+///
+/// Chaque état possède sa propre mise à jour et son propre affichage (voir `Game`),
+/// et les passages d'un état à l'autre se font uniquement via `Game::transition`,
+/// qui appelle les actions de sortie puis d'entrée une seule fois.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameState {
+    /// Écran titre affiché au lancement.
+    Title,
+    /// Écran de configuration de la partie (nombre et vitesse des astéroïdes...).
+    Setup,
+    /// Partie en cours.
+    Playing,
+    /// Partie figée, menu pause affiché par-dessus.
+    Paused,
+    /// Courte pause entre deux vagues d'astéroïdes.
+    WaveTransition,
+    /// Écran de résultat (victoire, défaite ou abandon).
+    GameOver,
+    /// Saisie du nom du joueur lorsque son score entre dans le tableau.
+    HighScoreEntry,
+    /// Tableau des meilleurs scores.
+    Leaderboard,
+}
+
+impl GameState {
+    /// Indique si la musique de fond doit être jouée dans cet état.
+    ///
+    /// # Retour
+    ///
+    /// `true` pendant la partie et entre deux vagues, sinon `false`.
+    pub fn plays_music(self) -> bool {
+        matches!(self, GameState::Playing | GameState::WaveTransition)
+    }
+}
+
+/// Mode de jeu d'une partie. Les meilleurs scores sont classés séparément pour chaque mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameMode {
+    /// Un joueur, vagues d'astéroïdes successives.
+    Classic,
+    /// Deux joueurs dans la même arène, vagues d'astéroïdes successives.
+    Coop,
+    /// Deux à quatre joueurs qui s'affrontent dans le champ d'astéroïdes.
+    Versus,
+}
+
+impl GameMode {
+    /// Tous les modes de jeu, dans l'ordre d'affichage.
+    pub const ALL: [GameMode; 3] = [GameMode::Classic, GameMode::Coop, GameMode::Versus];
+
+    /// Retourne l'identifiant du mode utilisé dans les fichiers.
+    ///
+    /// # Retour
+    ///
+    /// Un identifiant court, sans espace.
+    pub fn key(self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::Coop => "coop",
+            GameMode::Versus => "versus",
+        }
+    }
+
+    /// Retrouve un mode à partir de son identifiant.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - L'identifiant lu dans un fichier.
+    ///
+    /// # Retour
+    ///
+    /// Le mode correspondant, ou `None` si l'identifiant est inconnu.
+    pub fn from_key(key: &str) -> Option<Self> {
+        GameMode::ALL.into_iter().find(|mode| mode.key() == key)
+    }
+
+    /// Retourne le mode correspondant aux joueurs d'une partie.
+    ///
+    /// # Arguments
+    ///
+    /// * `players` - Le nombre de vaisseaux dans l'arène.
+    /// * `versus` - `true` si les joueurs s'affrontent.
+    pub fn for_match(players: usize, versus: bool) -> Self {
+        if players > 1 && versus {
+            GameMode::Versus
+        } else if players > 1 {
+            GameMode::Coop
+        } else {
+            GameMode::Classic
+        }
+    }
+
+    /// Indique si les scores de ce mode sont classés dans le tableau des meilleurs scores
+    /// (un match en versus se gagne aux frags, son score n'est pas enregistré).
+    pub fn has_leaderboard(self) -> bool {
+        self != GameMode::Versus
+    }
+
+    /// Retourne le nom affichable du mode.
+    ///
+    /// # Retour
+    ///
+    /// Le nom du mode en français.
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classique",
+            GameMode::Coop => "Coopération",
+            GameMode::Versus => "Versus",
+        }
+    }
+}
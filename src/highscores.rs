@@ -0,0 +1,261 @@
+use crate::game_state::GameMode;
+use crate::storage;
+use std::io;
+
+/// Nombre maximal de scores conservés pour chaque mode de jeu.
+pub const MAX_ENTRIES: usize = 10;
+
+/// Longueur maximale du nom d'un joueur.
+pub const MAX_NAME_LENGTH: usize = 12;
+
+/// Nom du fichier des meilleurs scores dans le répertoire de données.
+const FILE_NAME: &str = "highscores.txt";
+
+/// Première ligne du fichier, qui identifie son format.
+const HEADER: &str = "# asteroid highscores v1";
+
+/// Une ligne du tableau des meilleurs scores.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HighScoreEntry {
+    /// Nom saisi par le joueur.
+    pub name: String,
+    /// Score final.
+    pub score: i32,
+    /// Dernière vague atteinte.
+    pub wave: u32,
+    /// Durée de la partie, en secondes.
+    pub duration: f64,
+    /// Graine aléatoire de la partie.
+    pub seed: u64,
+    /// Date de la partie (secondes depuis le 1er janvier 1970).
+    pub date: u64,
+}
+
+/// Tableau des meilleurs scores, classés par mode de jeu.
+///
+/// Le fichier est un texte lisible : une ligne par score, champs séparés par des tabulations
+/// (`mode nom score vague durée graine date`). Les lignes illisibles sont ignorées.
+#[derive(Default)]
+pub struct HighScoreTable {
+    entries: Vec<(GameMode, HighScoreEntry)>,
+}
+
+impl HighScoreTable {
+    /// Charge le tableau depuis le répertoire de données.
+    ///
+    /// # Retour
+    ///
+    /// Le tableau lu, ou un tableau vide si le fichier n'existe pas encore.
+    pub fn load() -> Self {
+        storage::read_data_file(FILE_NAME)
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    /// Enregistre le tableau dans le répertoire de données.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur d'entrée/sortie si le fichier n'a pas pu être écrit.
+    pub fn save(&self) -> io::Result<()> {
+        storage::write_data_file(FILE_NAME, &self.serialize())
+    }
+
+    /// Lit un tableau depuis le contenu d'un fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Le contenu du fichier.
+    ///
+    /// # Retour
+    ///
+    /// Le tableau, sans les lignes mal formées.
+    pub fn parse(text: &str) -> Self {
+        let mut table = Self::default();
+        for line in text.lines() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() != 7 {
+                continue;
+            }
+            let (Some(mode), Ok(score), Ok(wave), Ok(duration), Ok(seed), Ok(date)) = (
+                GameMode::from_key(fields[0]),
+                fields[2].parse(),
+                fields[3].parse(),
+                fields[4].parse(),
+                fields[5].parse(),
+                fields[6].parse(),
+            ) else {
+                continue;
+            };
+            let entry = HighScoreEntry {
+                name: sanitize_name(fields[1]),
+                score,
+                wave,
+                duration,
+                seed,
+                date,
+            };
+            table.insert(mode, entry);
+        }
+        table
+    }
+
+    /// Convertit le tableau au format du fichier.
+    ///
+    /// # Retour
+    ///
+    /// Le contenu à écrire dans le fichier.
+    pub fn serialize(&self) -> String {
+        let mut text = format!("{}\n", HEADER);
+        for (mode, entry) in &self.entries {
+            text.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{:.1}\t{}\t{}\n",
+                mode.key(),
+                entry.name,
+                entry.score,
+                entry.wave,
+                entry.duration,
+                entry.seed,
+                entry.date
+            ));
+        }
+        text
+    }
+
+    /// Retourne les scores d'un mode, du meilleur au moins bon.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - Le mode de jeu.
+    pub fn entries(&self, mode: GameMode) -> Vec<&HighScoreEntry> {
+        self.entries
+            .iter()
+            .filter(|(entry_mode, _)| *entry_mode == mode)
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    /// Indique si un score mérite d'entrer dans le tableau.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - Le mode de jeu de la partie.
+    /// * `score` - Le score obtenu.
+    ///
+    /// # Retour
+    ///
+    /// `true` si le score est positif et que le tableau n'est pas plein ou que le score
+    /// dépasse le moins bon score enregistré.
+    pub fn qualifies(&self, mode: GameMode, score: i32) -> bool {
+        let entries = self.entries(mode);
+        score > 0
+            && (entries.len() < MAX_ENTRIES
+                || entries.last().is_some_and(|lowest| score > lowest.score))
+    }
+
+    /// Ajoute un score à sa place et retire les scores en trop.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - Le mode de jeu de la partie.
+    /// * `entry` - Le score à ajouter.
+    ///
+    /// # Retour
+    ///
+    /// Le rang du score (0 = meilleur) s'il est conservé, sinon `None`.
+    pub fn insert(&mut self, mode: GameMode, entry: HighScoreEntry) -> Option<usize> {
+        let rank = self
+            .entries(mode)
+            .iter()
+            .take_while(|other| other.score >= entry.score)
+            .count();
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+
+        // Les scores sont rangés par ordre décroissant à l'intérieur de chaque mode
+        let index = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (entry_mode, _))| *entry_mode == mode)
+            .nth(rank)
+            .map(|(index, _)| index)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, (mode, entry));
+
+        // Retirer le score qui sort du tableau
+        if let Some(index) = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (entry_mode, _))| *entry_mode == mode)
+            .nth(MAX_ENTRIES)
+            .map(|(index, _)| index)
+        {
+            self.entries.remove(index);
+        }
+        Some(rank)
+    }
+}
+
+/// Nettoie un nom saisi : retire les caractères de contrôle et limite sa longueur.
+///
+/// # Arguments
+///
+/// * `name` - Le nom brut.
+///
+/// # Retour
+///
+/// Le nom nettoyé, ou `"Anonyme"` s'il est vide.
+pub fn sanitize_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_NAME_LENGTH)
+        .collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        "Anonyme".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Retourne la date actuelle en secondes depuis le 1er janvier 1970.
+///
+/// L'heure est lue par miniquad, qui la demande au navigateur dans la version web.
+pub fn now_unix() -> u64 {
+    macroquad::miniquad::date::now().max(0.) as u64
+}
+
+/// Formate une date (secondes depuis 1970) au format `AAAA-MM-JJ`.
+///
+/// # Arguments
+///
+/// * `timestamp` - La date en secondes depuis le 1er janvier 1970 (UTC).
+///
+/// # Retour
+///
+/// La date formatée.
+pub fn format_date(timestamp: u64) -> String {
+    // Conversion jours -> date civile (algorithme de Howard Hinnant)
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -1,19 +1,63 @@
-use ::rand::{thread_rng, Rng};
-use macroquad::audio::{load_sound, play_sound, stop_sound, PlaySoundParams, Sound};
+use ::rand::Rng;
+use macroquad::audio::{play_sound, PlaySoundParams, Sound};
 use macroquad::prelude::*;
 use std::f32::consts::PI;
 
+use assets::{AssetLoader, SoundPlaceholder, TexturePlaceholder};
 use asteroid::Asteroid;
-use config_screen::ConfigScreen;
+use audio::{PannedSound, MUSIC_LAYERS};
+use events::{EventQueue, GameEvent};
+use game::Game;
+use manifest::AssetPaths;
 use missile::Missile;
+use players::PLAYER_COLORS;
+use settings::{Settings, WindowMode};
+#[cfg(not(target_arch = "wasm32"))]
+use spectator::{SnapshotReader, SnapshotStream};
+use stats::Score;
 use stellarobject::StellarObject;
 use vaisseau::Vaisseau;
 
+mod assets;
 mod asteroid;
+mod audio;
+mod camera;
+// Le réseau, le serveur et les spectateurs utilisent les sockets, absentes dans le navigateur
+#[cfg(not(target_arch = "wasm32"))]
+mod client;
 mod config_screen;
+mod console;
+mod controls;
+mod debug;
+#[cfg(feature = "embedded-assets")]
+mod embedded;
+mod events;
+mod game;
+mod game_state;
+mod highscores;
+mod manifest;
 mod missile;
+#[cfg(not(target_arch = "wasm32"))]
+mod net;
+mod pacing;
+mod particles;
+mod pause_menu;
+mod players;
+mod rng;
+mod save;
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
+mod settings;
+#[cfg(not(target_arch = "wasm32"))]
+mod spectator;
+mod stats;
 mod stellarobject;
+mod storage;
+mod synth;
+mod text;
 mod vaisseau;
+mod versus;
+mod world;
 
 /// Dessine une texture centrée sur une position donnée avec une taille spécifique et une rotation.
 ///
@@ -39,6 +83,9 @@ mod vaisseau;
 ///   Un `f32` représentant l'angle de rotation (en radians) appliqué à la texture.  
 ///   La rotation se fait autour du centre de la texture.
 ///
+/// - `tint` :  
+///   La couleur multipliée à la texture (`WHITE` pour garder ses couleurs d'origine).
+///
 /// # Détails
 ///
 /// - La fonction ajuste automatiquement la position pour compenser l'origine de la texture,
@@ -46,12 +93,18 @@ mod vaisseau;
 /// - La taille finale de la texture à l'écran est proportionnelle à `size * 1.9`, ce qui permet
 ///   de la redimensionner dynamiquement tout en respectant ses proportions initiales.
 /// - La rotation est appliquée autour du centre visuel de la texture.
-fn draw_centered_texture(texture: &Texture2D, position: Vec2, size: f32, rotation: f32) {
+fn draw_centered_texture(
+    texture: &Texture2D,
+    position: Vec2,
+    size: f32,
+    rotation: f32,
+    tint: Color,
+) {
     draw_texture_ex(
         texture,
         position.x - (size),
         position.y - (size),
-        WHITE,
+        tint,
         DrawTextureParams {
             dest_size: Some(Vec2::new(size * 1.9, size * 1.9)),
             rotation,
@@ -182,12 +235,19 @@ fn draw_background(sprite: &Texture2D) {
 ///
 /// - Cette fonction utilise la rotation de le vaisseau, donc il est important que l'objet `Vaisseau`
 ///   ait une valeur de rotation mise à jour pour que l'affichage soit correct.
+/// - Le sprite est teinté de la couleur du joueur, et à moitié transparent tant que le vaisseau
+///   est invincible après sa réapparition.
 fn draw_vaisseau(vaisseau: &Vaisseau, sprite: &Texture2D, hauteur_vaisseau: f32) {
+    let mut tint = PLAYER_COLORS[vaisseau.get_player() % PLAYER_COLORS.len()];
+    if vaisseau.is_invulnerable() {
+        tint.a = 0.4;
+    }
     draw_centered_texture(
         sprite,
         vaisseau.get_position(),
         hauteur_vaisseau,
         vaisseau.get_rotation(),
+        tint,
     );
 }
 
@@ -214,9 +274,16 @@ fn draw_vaisseau(vaisseau: &Vaisseau, sprite: &Texture2D, hauteur_vaisseau: f32)
 ///   que leur position est mise à jour à chaque itération pour obtenir un mouvement fluide à l'écran.
 /// - Le rayon du missile est utilisé pour calculer sa taille à l'écran, donc il peut être ajusté
 ///   dynamiquement selon les besoins du jeu.
+/// - Chaque missile est teinté de la couleur du joueur qui l'a tiré.
 fn draw_missiles(missiles: &Vec<Missile>, rayon_missile: f32, sprite: &Texture2D) {
     for missile in missiles {
-        draw_centered_texture(sprite, missile.get_position(), rayon_missile, 0.);
+        draw_centered_texture(
+            sprite,
+            missile.get_position(),
+            rayon_missile,
+            0.,
+            PLAYER_COLORS[missile.get_owner() % PLAYER_COLORS.len()],
+        );
     }
 }
 
@@ -229,14 +296,35 @@ fn draw_missiles(missiles: &Vec<Missile>, rayon_missile: f32, sprite: &Texture2D
 /// - `vaisseau` :  
 ///   Une référence mutable à un objet `Vaisseau`. La position de cet vaisseau est mise à jour
 ///   directement en fonction de sa vitesse et de son orientation.
+/// - `arena` :  
+///   La taille de l'arène, dont le vaisseau ressort de l'autre côté.
+/// - `hauteur_vaisseau` :  
+///   La hauteur de le vaisseau, utilisée pour placer la tuyère à l'arrière du sprite.
+/// - `events` :  
+///   La file dans laquelle publier `GameEvent::ShipThrust` si le vaisseau accélère.
 ///
 /// # Fonctionnement
 ///
 /// - La méthode `update_position` de l'vaisseau est appelée, ce qui met à jour sa position.
+/// - Si le réacteur est allumé, un événement `ShipThrust` est publié à l'arrière de le vaisseau.
 /// - Les limites de l'écran ou d'autres contraintes ne sont pas gérées ici ; il est supposé
 ///   que cela est pris en charge par d'autres parties du code.
-fn update_model_vaisseau(vaisseau: &mut Vaisseau) {
-    vaisseau.update_position();
+fn update_model_vaisseau(
+    vaisseau: &mut Vaisseau,
+    arena: Vec2,
+    hauteur_vaisseau: f32,
+    events: &mut EventQueue,
+) {
+    vaisseau.update_position(arena);
+
+    if vaisseau.is_thrusting() {
+        let rotation = vaisseau.get_rotation();
+        let forward = Vec2::new(rotation.sin(), -rotation.cos());
+        events.push(GameEvent::ShipThrust {
+            position: vaisseau.get_position() - forward * hauteur_vaisseau * 0.8,
+            direction: -forward,
+        });
+    }
 }
 
 /// Met à jour la position de chaque astéroïde de la liste.
@@ -248,15 +336,17 @@ fn update_model_vaisseau(vaisseau: &mut Vaisseau) {
 /// - `asteroids` :  
 ///   Une référence mutable à un vecteur d'objets `Asteroid`. La position de chaque astéroïde
 ///   est mise à jour individuellement.
+/// - `arena` :  
+///   La taille de l'arène, dont les astéroïdes ressortent de l'autre côté.
 ///
 /// # Fonctionnement
 ///
 /// - La méthode `update_position` de chaque astéroïde est appelée.
 /// - Cette fonction ne vérifie pas les collisions ni les limites de l'écran ; ces aspects doivent
 ///   être gérés ailleurs.
-fn update_asteroids(asteroids: &mut Vec<Asteroid>) {
+fn update_asteroids(asteroids: &mut Vec<Asteroid>, arena: Vec2) {
     for asteroid in asteroids {
-        asteroid.update_position();
+        asteroid.update_position(arena);
     }
 }
 
@@ -269,6 +359,8 @@ fn update_asteroids(asteroids: &mut Vec<Asteroid>) {
 /// - `missiles` :  
 ///   Une référence mutable à un vecteur d'objets `Missile`. Chaque missile voit sa position
 ///   mise à jour, et ceux qui sont hors de l'écran sont supprimés.
+/// - `arena` :  
+///   La taille de l'arène, en dehors de laquelle un missile est supprimé.
 ///
 /// # Fonctionnement
 ///
@@ -276,23 +368,24 @@ fn update_asteroids(asteroids: &mut Vec<Asteroid>) {
 /// 2. Les missiles qui dépassent les limites de l'écran sont identifiés à l'aide de la méthode
 ///    `is_off_screen`.  
 /// 3. Ces missiles sont ensuite retirés de la liste à l'aide de `retain`.
-fn update_missiles(missiles: &mut Vec<Missile>) {
+fn update_missiles(missiles: &mut Vec<Missile>, arena: Vec2) {
     for missile in missiles.iter_mut() {
-        missile.update_position();
+        missile.update_position(arena);
     }
-    missiles.retain(|missile| !missile.is_off_screen(screen_width(), screen_height()));
+    missiles.retain(|missile| !missile.is_off_screen(arena.x, arena.y));
 }
 
-/// Vérifie et gère les collisions entre le vaisseau et les astéroïdes.
-/// Cette fonction détecte les collisions entre le vaisseau et les astéroïdes, applique une force
-/// gravitationnelle si le vaisseau est à proximité, et gère les impacts directs en mettant à jour
-/// les états de le vaisseau et des astéroïdes.
+/// Vérifie et gère les collisions entre les vaisseaux et les astéroïdes.
+/// Cette fonction détecte les collisions entre chaque vaisseau et les astéroïdes, applique une force
+/// gravitationnelle si un vaisseau est à proximité, et gère les impacts directs en mettant à jour
+/// les états des vaisseaux et des astéroïdes.
 ///
 /// # Paramètres
 ///
-/// - `vaisseau` :  
-///   Référence mutable à un objet `Vaisseau`. La fonction vérifie si cet vaisseau entre en collision
+/// - `ships` :  
+///   Slice mutable des vaisseaux en jeu. La fonction vérifie si chaque vaisseau entre en collision
 ///   avec des astéroïdes et met à jour sa position, sa vitesse, et son état en conséquence.
+///   Un vaisseau invincible (qui vient de réapparaître) est ignoré.
 /// - `asteroids` :  
 ///   Slice mutable d'objets `Asteroid` représentant les astéroïdes présents dans le jeu.
 ///   Chaque astéroïde peut influencer le vaisseau via la gravité ou entrer en collision avec lui.
@@ -303,14 +396,14 @@ fn update_missiles(missiles: &mut Vec<Missile>) {
 /// - `gravite_dist` :  
 ///   Un `f32` définissant la portée maximale de la gravité exercée par les astéroïdes.
 ///   Si le vaisseau est dans cette portée, une force gravitationnelle est appliquée.
-/// - `ship_hit` :  
-///   Une `Option<&Sound>` représentant le son à jouer lorsqu'une collision directe se produit
-///   entre le vaisseau et un astéroïde. Si `None`, aucun son ne sera joué.
+/// - `events` :  
+///   La file dans laquelle publier un `GameEvent::ShipHit` pour chaque collision directe.
+///   Le son et les étincelles sont gérés par les abonnés de cette file.
 ///
 /// # Fonctionnement
 ///
 /// 1. **Calcul de la distance :**  
-///    Pour chaque astéroïde, la distance entre le vaisseau et l'astéroïde est calculée.
+///    Pour chaque vaisseau et chaque astéroïde, la distance entre les deux est calculée.
 /// 2. **Force gravitationnelle :**  
 ///    Si le vaisseau est à une distance inférieure à `gravite_dist + taille_astéroïde`, une force
 ///    gravitationnelle est appliquée à le vaisseau. Cette force est calculée avec la fonction
@@ -318,16 +411,40 @@ fn update_missiles(missiles: &mut Vec<Missile>) {
 /// 3. **Collision directe :**  
 ///    Si la distance entre le vaisseau et un astéroïde est inférieure à la somme de leurs rayons
 ///    (collision), alors :
-///    - Le son spécifié dans `ship_hit` est joué (si fourni).
 ///    - L'état de le vaisseau est mis à jour avec la méthode `handle_collision`.
 ///    - L'astéroïde est marqué comme "collidé" et son état est mis à jour.
+///    - Un événement `ShipHit` est publié avec les points de bouclier perdus et le point de contact.
+fn check_ships_asteroids(
+    ships: &mut [Vaisseau],
+    asteroids: &mut [Asteroid],
+    hauteur_vaisseau: f32,
+    level_size: (f32, f32, f32),
+    gravite_dist: f32,
+    events: &mut EventQueue,
+) {
+    for vaisseau in ships.iter_mut() {
+        if !vaisseau.is_invulnerable() {
+            check_vaisseau_asteroids(
+                vaisseau,
+                asteroids,
+                hauteur_vaisseau,
+                level_size,
+                gravite_dist,
+                events,
+            );
+        }
+    }
+}
+
+/// Vérifie et gère les collisions entre un seul vaisseau et les astéroïdes (voir
+/// `check_ships_asteroids`).
 fn check_vaisseau_asteroids(
     vaisseau: &mut Vaisseau,
     asteroids: &mut [Asteroid],
     hauteur_vaisseau: f32,
     level_size: (f32, f32, f32),
     gravite_dist: f32,
-    ship_hit: Option<&Sound>,
+    events: &mut EventQueue,
 ) {
     let vaisseau_position = vaisseau.get_position();
     let vaisseau_radius = hauteur_vaisseau;
@@ -346,12 +463,17 @@ fn check_vaisseau_asteroids(
         }
 
         if distance_squared <= collision_distance_squared && !asteroid.get_collided() {
-            if let Some(sound) = ship_hit {
-                play_game_sound(sound, false, 0.1);
-            }
-
+            let shield_before = vaisseau.get_shield();
             vaisseau.handle_collision(asteroid.get_level(), true, Vec2::ZERO);
             asteroid.handle_collision(0, true, Vec2::ZERO);
+
+            let direction = (asteroid.get_position() - vaisseau_position).normalize_or_zero();
+            events.push(GameEvent::ShipHit {
+                player: vaisseau.get_player(),
+                damage: shield_before - vaisseau.get_shield(),
+                position: vaisseau_position,
+                impact: vaisseau_position + direction * vaisseau_radius,
+            });
         }
     }
 }
@@ -437,8 +559,8 @@ fn calculate_gravity(
 
 /// Vérifie et gère les collisions entre les missiles et les astéroïdes dans le jeu.
 /// Cette fonction détecte les collisions, met à jour les états des objets (missiles et astéroïdes),
-/// joue un son lorsqu'un astéroïde est touché, met à jour le score et gère la division
-/// des astéroïdes en morceaux si applicable.
+/// publie les événements d'impact et de destruction, et gère la division des astéroïdes
+/// en morceaux si applicable.
 ///
 /// # Paramètres
 ///
@@ -456,13 +578,10 @@ fn calculate_gravity(
 /// - `level_size` :
 ///   Tuple `(f32, f32, f32)` représentant les tailles des niveaux, utilisé pour déterminer la
 ///   taille des astéroïdes en fonction de leur niveau.
-/// - `asteroid_hit` :
-///   Une `Option<&Sound>` représentant le son à jouer lorsqu'un astéroïde est touché.
-///   Si `None`, aucun son ne sera joué.
-/// - `score` :
-///   Référence mutable à un `i32` représentant le score du joueur. Le score sera augmenté
-///   en fonction du niveau de l'astéroïde détruit (par exemple, un astéroïde de niveau 1 donne
-///   10 points, un niveau 2 donne 20 points, etc.).
+/// - `events` :
+///   La file dans laquelle publier un `GameEvent::MissileImpact` et un
+///   `GameEvent::AsteroidDestroyed` pour chaque collision. Le son, les particules et le score
+///   sont gérés par les abonnés de cette file.
 ///
 /// # Fonctionnement
 ///
@@ -470,8 +589,7 @@ fn calculate_gravity(
 /// 2. Une collision est détectée si la distance au carré entre le missile et l'astéroïde est
 ///    inférieure ou égale au carré de la somme de leurs rayons (`rayon_missile + taille_asteroid`).
 /// 3. En cas de collision :
-///    - Le son spécifié (`asteroid_hit`) est joué si fourni.
-///    - Le score est mis à jour en fonction du niveau de l'astéroïde touché.
+///    - Les événements `MissileImpact` et `AsteroidDestroyed` sont publiés.
 ///    - Le missile est marqué comme "collidé".
 ///    - L'astéroïde est supprimé. S'il peut se diviser, deux nouveaux astéroïdes sont générés
 ///      avec des propriétés issues de la collision.
@@ -482,8 +600,7 @@ fn check_missiles_asteroids(
     asteroids: &mut Vec<Asteroid>,
     rayon_missile: f32,
     level_size: (f32, f32, f32),
-    asteroid_hit: Option<&Sound>,
-    score: &mut i32,
+    events: &mut EventQueue,
 ) {
     let mut asteroids_to_remove = Vec::new();
     let mut new_asteroids = Vec::new();
@@ -497,11 +614,16 @@ fn check_missiles_asteroids(
             if distance_squared >= collision_distance_squared {
                 continue;
             }
-            if let Some(sound) = asteroid_hit {
-                play_game_sound(sound, false, 0.1);
-            }
+            events.push(GameEvent::MissileImpact {
+                position: missile.get_position(),
+                direction: missile.get_speed(),
+            });
+            events.push(GameEvent::AsteroidDestroyed {
+                level: asteroid.get_level(),
+                position: asteroid.get_position(),
+                player: missile.get_owner(),
+            });
 
-            *score += asteroid.get_level() as i32 * 10;
             missile.handle_collision(0, true, Vec2::ZERO);
 
             if let Some((asteroid_1, asteroid_2)) =
@@ -526,7 +648,55 @@ fn check_missiles_asteroids(
     asteroids.extend(new_asteroids);
 }
 
-/// Réinitialise l'état du jeu, en créant de nouveaux astéroïdes et réinitialisant le vaisseau, les missiles et le score.
+/// Vérifie et gère les collisions entre les missiles et les vaisseaux (tir ami ou versus).
+/// Un missile ne touche jamais le vaisseau qui l'a tiré, ni un vaisseau invincible. Le vaisseau
+/// touché retient le joueur qui a tiré, à qui sera attribuée sa destruction.
+///
+/// # Paramètres
+///
+/// - `missiles` :
+///   Référence mutable à la liste des missiles actifs. Les missiles qui touchent un vaisseau
+///   sont supprimés de cette liste.
+/// - `ships` :
+///   Slice mutable des vaisseaux en jeu. Chaque missile reçu retire un point de bouclier.
+/// - `rayon_missile` :
+///   Le rayon des missiles.
+/// - `hauteur_vaisseau` :
+///   La hauteur des vaisseaux, utilisée comme rayon de collision.
+/// - `events` :
+///   La file dans laquelle publier un `GameEvent::ShipHit` pour chaque vaisseau touché.
+fn check_missiles_ships(
+    missiles: &mut Vec<Missile>,
+    ships: &mut [Vaisseau],
+    rayon_missile: f32,
+    hauteur_vaisseau: f32,
+    events: &mut EventQueue,
+) {
+    let collision_distance_squared = (rayon_missile + hauteur_vaisseau).powi(2);
+    for missile in missiles.iter_mut() {
+        let target = ships.iter_mut().find(|vaisseau| {
+            vaisseau.get_player() != missile.get_owner()
+                && !vaisseau.is_invulnerable()
+                && (vaisseau.get_position() - missile.get_position()).length_squared()
+                    < collision_distance_squared
+        });
+        let Some(vaisseau) = target else {
+            continue;
+        };
+        vaisseau.dmg_shield(1.);
+        vaisseau.set_last_attacker(Some(missile.get_owner()));
+        missile.handle_collision(0, true, Vec2::ZERO);
+        events.push(GameEvent::ShipHit {
+            player: vaisseau.get_player(),
+            damage: 1.,
+            position: vaisseau.get_position(),
+            impact: missile.get_position(),
+        });
+    }
+    missiles.retain(|missile| !missile.get_collided());
+}
+
+/// Réinitialise l'état du jeu, en créant de nouveaux astéroïdes et réinitialisant les vaisseaux, les missiles et le score.
 /// Cette fonction prépare le jeu pour un nouveau départ en effaçant les objets existants et en initialisant de nouvelles instances.
 ///
 /// # Paramètres
@@ -534,43 +704,53 @@ fn check_missiles_asteroids(
 /// - `liste_asteroid` :
 ///   Référence mutable à un `Vec<Asteroid>` contenant la liste des astéroïdes du jeu. Cette liste
 ///   sera vidée et remplie avec de nouveaux astéroïdes générés.
-/// - `vaisseau` :
-///   Référence mutable à l'objet `Vaisseau`, qui sera réinitialisé avec des paramètres par défaut
-///   ou des valeurs spéciales si le mode de test est activé.
+/// - `ships` :
+///   Référence mutable à la liste des vaisseaux, qui sera remplacée par un vaisseau neuf par
+///   joueur, réparti sur la ligne de départ (ou placé selon le mode de test).
+/// - `players` :
+///   Le nombre de joueurs, donc de vaisseaux, de la partie.
 /// - `missiles` :
 ///   Référence mutable à un `Vec<Missile>` contenant les missiles actifs. Cette liste sera vidée
 ///   pour un redémarrage propre.
 /// - `level_size` :
 ///   Tuple `(f32, f32, f32)` représentant la taille des niveaux, utilisé pour déterminer les
 ///   positions et les limites des astéroïdes.
+/// - `arena` :
+///   La taille de l'arène, sur laquelle les vaisseaux et les astéroïdes sont répartis.
 /// - `number_asteroid` :
 ///   Un `i32` représentant le nombre d'astéroïdes à générer.
 /// - `asteroid_speed` :
 ///   Un `f32` représentant la vitesse des astéroïdes. Ce paramètre affecte les plages minimale
 ///   et maximale des vitesses des astéroïdes générés.
 /// - `score` :
-///   Référence mutable à un `i32` représentant le score du joueur, qui sera réinitialisé à 0.
+///   Référence mutable au `Score` du joueur, qui sera réinitialisé à 0.
 /// - `test` :
 ///   Booléen indiquant si la fonction est appelée dans un contexte de test. Si `true`, des valeurs
-///   par défaut simplifiées seront utilisées pour la position initiale de le vaisseau et l'état du dernier
+///   par défaut simplifiées seront utilisées pour la position initiale des vaisseaux et l'état du dernier
 ///   tir :
-///   - La position de le vaisseau sera fixée à `(0., 0.)`.
+///   - La position des vaisseaux sera fixée à `(0., 0.)`.
 ///   - L'état du dernier tir sera fixé à `0.`
+/// - `rng` :
+///   Le générateur aléatoire de la partie, initialisé avec sa graine.
 ///
 ///  # Fonctionnement
 ///
 /// 1. - Rétinialise toutes les variables utile au bon fonctionnement du jeu.
 /// 2. - Créer une quantité "number_asteroid" d'asteroids ayant une vitesse "asteroid_speed"
-///         on cacule un mimum et un maximum avec cette speed.
+///      on cacule un mimum et un maximum avec cette speed.
+#[allow(clippy::too_many_arguments)]
 fn reset_game(
     liste_asteroid: &mut Vec<Asteroid>,
-    vaisseau: &mut Vaisseau,
+    ships: &mut Vec<Vaisseau>,
+    players: usize,
     missiles: &mut Vec<Missile>,
     level_size: (f32, f32, f32),
+    arena: Vec2,
     number_asteroid: i32,
     asteroid_speed: f32,
-    score: &mut i32,
+    score: &mut Score,
     test: bool,
+    rng: &mut impl Rng,
 ) {
     let mut position = None;
     let mut last_shot = None;
@@ -579,24 +759,79 @@ fn reset_game(
         last_shot = Some(0.);
     }
     liste_asteroid.clear();
-    *vaisseau = Vaisseau::new(position, last_shot);
+    ships.clear();
+    for player in 0..players {
+        let spawn = position.unwrap_or_else(|| players::spawn_position(player, players, arena));
+        let mut vaisseau = Vaisseau::new(Some(spawn), last_shot);
+        vaisseau.set_player(player);
+        ships.push(vaisseau);
+    }
     missiles.clear();
-    *score = 0;
+    score.reset();
+
+    spawn_asteroids(
+        liste_asteroid,
+        level_size,
+        arena,
+        number_asteroid,
+        asteroid_speed,
+        position,
+        rng,
+    );
+}
 
+/// Ajoute des astéroïdes de niveau 3 avec des directions et des vitesses aléatoires.
+/// Cette fonction est utilisée au début de la partie (via `reset_game`) et au début de chaque vague.
+///
+/// # Paramètres
+///
+/// - `liste_asteroid` :
+///   Référence mutable à un `Vec<Asteroid>` auquel les nouveaux astéroïdes sont ajoutés.
+/// - `level_size` :
+///   Tuple `(f32, f32, f32)` représentant la taille des niveaux, utilisé pour placer les
+///   astéroïdes hors de l'écran.
+/// - `arena` :
+///   La taille de l'arène, autour de laquelle les astéroïdes apparaissent.
+/// - `number_asteroid` :
+///   Un `i32` représentant le nombre d'astéroïdes à générer.
+/// - `asteroid_speed` :
+///   Un `f32` représentant la vitesse des astéroïdes, qui fixe les bornes minimale et maximale
+///   des vitesses tirées.
+/// - `position` :
+///   Position imposée de tous les astéroïdes (utilisée par les tests). Si `None`, chaque
+///   astéroïde apparaît à une position aléatoire sur les bords de l'écran.
+/// - `rng` :
+///   Le générateur aléatoire de la partie, utilisé pour les directions, vitesses et positions.
+fn spawn_asteroids(
+    liste_asteroid: &mut Vec<Asteroid>,
+    level_size: (f32, f32, f32),
+    arena: Vec2,
+    number_asteroid: i32,
+    asteroid_speed: f32,
+    position: Option<Vec2>,
+    rng: &mut impl Rng,
+) {
     // Définir une plage dynamique pour la vitesse des astéroïdes
     let min_speed = 0.2 + (1.0 - asteroid_speed) * 0.4; // La borne inférieure se réduit avec la vitesse
     let max_speed = asteroid_speed * 2.0; // La borne supérieure est multipliée par la vitesse
 
     // Générer les astéroïdes
     for _ in 0..number_asteroid {
-        let angle = thread_rng().gen_range(0.0..(2.0 * PI));
-        let speed_magnitude = thread_rng().gen_range(min_speed..max_speed);
+        let angle = rng.gen_range(0.0..(2.0 * PI));
+        let speed_magnitude = rng.gen_range(min_speed..max_speed);
 
         let speed = Vec2::new(
             speed_magnitude * angle.cos(), // Composante x
             speed_magnitude * angle.sin(), // Composante y
         );
-        liste_asteroid.push(asteroid::Asteroid::new(3, speed, level_size, position));
+        let position =
+            position.unwrap_or_else(|| Asteroid::random_position(3, level_size, arena, rng));
+        liste_asteroid.push(asteroid::Asteroid::new(
+            3,
+            speed,
+            level_size,
+            Some(position),
+        ));
     }
 }
 
@@ -630,67 +865,6 @@ fn asteroid_level(asteroid: &mut Asteroid, level_size: (f32, f32, f32)) -> f32 {
     }
 }
 
-/// Met à jour les échelles de taille des objets du jeu en fonction des dimensions actuelles de l'écran.
-/// Cette fonction ajuste proportionnellement les tailles des objets à l'écran en fonction du changement de taille d'écran
-/// depuis le dernier calcul.
-///
-/// # Paramètres
-///
-/// - `hauteur_vaisseau` :
-///   Référence mutable à un `f32` représentant la hauteur de le vaisseau. Sa valeur sera mise à jour
-///   en fonction de l'échelle calculée.
-/// - `rayon_missile` :
-///   Référence mutable à un `f32` représentant le rayon des missiles. Cette valeur sera ajustée
-///   proportionnellement à l'échelle calculée.
-/// - `level_size` :
-///   Référence mutable à un tuple `(f32, f32, f32)` représentant les dimensions des niveaux.
-///   Les trois composantes seront ajustées proportionnellement à l'échelle calculée.
-/// - `last_screen_size` :
-///   Référence mutable à un tuple `(f32, f32)` représentant la taille de l'écran lors du dernier calcul.
-///   Ce paramètre sera mis à jour pour refléter les nouvelles dimensions de l'écran.
-/// - `gravite_dist` :
-///   Référence mutable à un `f32` représentant la distance de gravité utilisée dans le jeu. Sa valeur
-///   sera ajustée selon l'échelle calculée.
-/// - `test` :
-///   Booléen indiquant si la fonction est appelée dans un contexte de test. Si `true`, les dimensions
-///   de l'écran (`current_width` et `current_height`) seront fixées à des valeurs par défaut
-///   (500 pour la largeur et 400 pour la hauteur).
-///
-/// #Fonctionnement
-///
-/// 1. - calcule de du ratio nouvelle taille / ancienne taille de l'écran
-/// 2. - ajusté les tailles de toutes les variables
-fn update_scale(
-    hauteur_vaisseau: &mut f32,
-    rayon_missile: &mut f32,
-    level_size: &mut (f32, f32, f32),
-    last_screen_size: &mut (f32, f32),
-    gravite_dist: &mut f32,
-    test: bool,
-) {
-    let mut current_width = 500.;
-    let mut current_height = 400.;
-    if !test {
-        current_width = screen_width();
-        current_height = screen_height();
-    }
-
-    let width_scale = current_width / last_screen_size.0;
-    let height_scale = current_height / last_screen_size.1;
-
-    let scale_factor = (width_scale + height_scale) / 2.0;
-
-    // Mise à jour des tailles des objets
-    *hauteur_vaisseau *= scale_factor;
-    *rayon_missile *= scale_factor;
-    level_size.0 *= scale_factor;
-    level_size.1 *= scale_factor;
-    level_size.2 *= scale_factor;
-    *gravite_dist *= scale_factor;
-
-    *last_screen_size = (current_width, current_height);
-}
-
 /// Joue un son dans le jeu.
 /// Cette fonction utilise un objet `Sound` pour jouer un effet sonore ou une musique,
 /// avec des options pour la mise en boucle et le contrôle du volume.
@@ -731,190 +905,309 @@ struct Textures {
 /// Contient les sons du jeu pour les différents événements.
 ///
 /// - `shoot`: Son lorsque le vaisseau tire. (Sound)
-/// - `asteroid_hit`: Son lorsqu'un astéroïde est touché. (Sound)
-/// - `background_music`: Musique de fond. (Sound)
+/// - `asteroid_hit`: Son lorsqu'un astéroïde est touché, spatialisé. (PannedSound)
+/// - `music`: Couches de la musique de fond : base, rythme et tension. ([Sound; MUSIC_LAYERS])
 /// - `win`: Son lorsque le joueur gagne. (Sound)
 /// - `lose`: Son lorsque le joueur perd. (Sound)
-/// - `ship_hit`: Son lorsque le vaisseau est touché. (Sound)
+/// - `ship_hit`: Son lorsque le vaisseau est touché, spatialisé. (PannedSound)
+/// - `laser`: Tir synthétisé. (Sound)
+/// - `explosions`: Explosions synthétisées et spatialisées, par niveau d'astéroïde
+///   (indice `niveau - 1`). ([PannedSound; 3])
+/// - `thrust`: Grondement synthétisé du réacteur, joué en boucle. (Sound)
+/// - `heartbeat`: Notes basse et haute du rythme de fond synthétisé. ([Sound; 2])
+///
+/// Les sons sont joués par `audio::AudioMixer`, qui applique les volumes.
 struct Sounds {
     shoot: Sound,
-    asteroid_hit: Sound,
-    background_music: Sound,
+    asteroid_hit: PannedSound,
+    music: [Sound; MUSIC_LAYERS],
     win: Sound,
     lose: Sound,
-    ship_hit: Sound,
+    ship_hit: PannedSound,
+    laser: Sound,
+    explosions: [PannedSound; 3],
+    thrust: Sound,
+    heartbeat: [Sound; 2],
 }
 
-/// Fonction principale du jeu, exécutée dans la boucle principale.
-/// Gère la configuration, la mise à jour des objets, les entrées utilisateur et l'affichage.
+/// Fréquence de départ (en Hz) du tir laser synthétisé de le vaisseau.
+const LASER_PITCH: f32 = 1400.;
+
+/// Configure la fenêtre à partir des préférences du joueur (mode plein écran et taille).
 ///
 /// # Retour
 ///
-/// Cette fonction ne retourne rien, elle tourne indéfiniment tant que le jeu est en cours.
-#[macroquad::main("BasicShapes")]
-async fn main() {
-    let target_fps: f32 = 120.0; // Limite de FPS
-    let frame_duration: f32 = 1.0 / target_fps; // Durée cible par frame en secondes
+/// La configuration de la fenêtre utilisée par macroquad au démarrage.
+fn window_conf() -> Conf {
+    let settings = Settings::load();
+    Conf {
+        window_title: "Asteroid".to_owned(),
+        window_width: settings.window_width,
+        window_height: settings.window_height,
+        fullscreen: settings.window_mode == WindowMode::Fullscreen,
+        platform: macroquad::miniquad::conf::Platform {
+            swap_interval: Some(settings.frame_limit.swap_interval()),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
 
-    // Chargement des textures
-    let textures = Textures {
-        sprite_vaisseau: load_texture("./sprite/vaisseau.png").await.unwrap(),
-        sprite_asteroid_3: load_texture("./sprite/asteroid_3.png").await.unwrap(),
-        sprite_asteroid_2: load_texture("./sprite/asteroid_2.png").await.unwrap(),
-        sprite_asteroid_1: load_texture("./sprite/asteroid_1.png").await.unwrap(),
-        sprite_background: load_texture("./sprite/background.png").await.unwrap(),
-        sprite_meteor: load_texture("./sprite/missile.png").await.unwrap(),
-    };
+/// Ce que la fenêtre affiche, choisi sur la ligne de commande.
+#[cfg(not(target_arch = "wasm32"))]
+enum Launch {
+    /// Le jeu, en publiant éventuellement ses parties pour des spectateurs.
+    Game {
+        /// Adresse TCP du flux des spectateurs.
+        stream: Option<String>,
+        /// Fichier dans lequel enregistrer le flux.
+        record: Option<String>,
+    },
+    /// Le client d'une partie en réseau, connecté à cette adresse.
+    Connect(String),
+    /// Un spectateur connecté au flux d'une partie à cette adresse.
+    Watch(String),
+    /// Un spectateur qui rejoue un flux enregistré dans ce fichier.
+    Replay(String),
+}
 
-    // Chargement des sons
-    let sounds = Sounds {
-        shoot: load_sound("./audio/shoot.wav").await.unwrap(),
-        asteroid_hit: load_sound("./audio/asteroid_hit.wav").await.unwrap(),
-        background_music: load_sound("./audio/background_music.wav").await.unwrap(),
-        win: load_sound("./audio/win.wav").await.unwrap(),
-        lose: load_sound("./audio/lose.wav").await.unwrap(),
-        ship_hit: load_sound("./audio/ship_hit.wav").await.unwrap(),
+/// Point d'entrée : lit la ligne de commande et lance le jeu, le client d'une partie en réseau,
+/// un spectateur ou le serveur.
+///
+/// - `asteroid [--stream [adresse]] [--record fichier]` : le jeu, dans sa fenêtre ; ses parties
+///   sont publiées pour les spectateurs en TCP (par défaut sur le port
+///   `spectator::DEFAULT_STREAM_PORT` de cette machine) ou enregistrées dans un fichier.
+/// - `asteroid --server [adresse] [--versus]` : un serveur sans fenêtre, par défaut sur le port
+///   `net::DEFAULT_PORT` de toutes les interfaces.
+/// - `asteroid --connect [adresse]` : une fenêtre qui rejoint la partie d'un serveur (par défaut
+///   sur cette machine).
+/// - `asteroid --watch [adresse]` : un spectateur de la partie publiée à cette adresse.
+/// - `asteroid --replay fichier` : un spectateur qui rejoue un enregistrement.
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let value_after = |flag: &str| {
+        let index = args.iter().position(|arg| arg == flag)?;
+        Some(
+            args.get(index + 1)
+                .filter(|value| !value.starts_with("--"))
+                .cloned(),
+        )
     };
 
-    // Initialisation des variables du jeu
-    let mut score: i32 = 0;
-    let begin_time = get_time();
+    if let Some(address) = value_after("--server") {
+        let address = address.unwrap_or_else(|| format!("0.0.0.0:{}", net::DEFAULT_PORT));
+        run_server(&address, args.iter().any(|arg| arg == "--versus"));
+    } else {
+        let local = |port: u16| format!("127.0.0.1:{}", port);
+        let launch = if let Some(address) = value_after("--connect") {
+            Launch::Connect(address.unwrap_or_else(|| local(net::DEFAULT_PORT)))
+        } else if let Some(address) = value_after("--watch") {
+            Launch::Watch(address.unwrap_or_else(|| local(spectator::DEFAULT_STREAM_PORT)))
+        } else if let Some(Some(path)) = value_after("--replay") {
+            Launch::Replay(path)
+        } else {
+            Launch::Game {
+                stream: value_after("--stream").map(|address| {
+                    address.unwrap_or_else(|| local(spectator::DEFAULT_STREAM_PORT))
+                }),
+                record: value_after("--record").flatten(),
+            }
+        };
+        macroquad::Window::from_config(window_conf(), run(launch));
+    }
+}
 
-    let mut last_screen_size: (f32, f32) = (screen_width(), screen_height());
-    let mut hauteur_vaisseau: f32 = 30.;
-    let mut rayon_missile: f32 = 7.;
-    let mut level_size: (f32, f32, f32) = (40., 20., 10.);
-    let mut gravite_dist = 30.;
+/// Point d'entrée de la version web : le navigateur n'a pas de ligne de commande, seul le
+/// jeu est disponible.
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    macroquad::Window::from_config(window_conf(), run());
+}
 
-    let mut liste_asteroid = Vec::new();
-    let mut vaisseau: Vaisseau = Vaisseau::new(None, None);
-    let mut missiles = Vec::new();
+/// Lance un serveur de partie en réseau, sans fenêtre, jusqu'à une erreur réseau.
+///
+/// # Arguments
+///
+/// * `address` - L'adresse d'écoute.
+/// * `versus` - `true` pour un match en versus, `false` pour une partie en coopération.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_server(address: &str, versus: bool) {
+    let settings = Settings::load();
+    let config = server::ServerConfig {
+        versus,
+        friendly_fire: settings.friendly_fire,
+        asteroid_count: settings.asteroid_count,
+        asteroid_speed: settings.asteroid_speed,
+        seed: rng::random_seed(),
+        ..Default::default()
+    };
+    let result = server::Server::bind(address, config).and_then(|mut server| {
+        println!("Serveur ouvert sur {}", server.local_addr()?);
+        server.run()
+    });
+    if let Err(error) = result {
+        eprintln!("Erreur du serveur : {}", error);
+    }
+}
 
-    let mut config_screen = ConfigScreen::new();
-    let mut in_configuration = true;
+/// Boucle du jeu dans sa fenêtre.
+/// Charge les ressources puis délègue la mise à jour et l'affichage de chaque frame à `Game`,
+/// qui gère les différents écrans (titre, configuration, partie, pause, fin de partie).
+///
+/// # Arguments
+///
+/// * `launch` - Ce que la fenêtre affiche : le jeu, ou à la place de l'écran titre le client
+///   d'une partie en réseau ou un spectateur (sauf dans la version web).
+async fn run(#[cfg(not(target_arch = "wasm32"))] launch: Launch) {
+    let settings = Settings::load();
 
-    loop {
-        let start_time = get_time(); // Début de la frame actuelle
-
-        // Si l'écran de configuration est actif
-        if in_configuration {
-            stop_sound(&sounds.background_music);
-            config_screen.draw();
-            config_screen.update();
-
-            if config_screen.is_start_pressed() {
-                in_configuration = false;
-                reset_game(
-                    &mut liste_asteroid,
-                    &mut vaisseau,
-                    &mut missiles,
-                    level_size,
-                    config_screen.get_asteroid_count(),
-                    config_screen.get_asteroid_speed(),
-                    &mut score,
-                    false,
-                );
-                stop_sound(&sounds.lose);
-                stop_sound(&sounds.win);
-                play_game_sound(&sounds.background_music, true, 0.1);
+    // Chargement des textures (une forme colorée remplace chaque image manquante)
+    let mut loader = AssetLoader::new(AssetPaths::resolve(&settings).await);
+    let textures = Textures {
+        sprite_vaisseau: loader
+            .texture("vaisseau", TexturePlaceholder::Triangle(SKYBLUE))
+            .await,
+        sprite_asteroid_3: loader
+            .texture("asteroid_3", TexturePlaceholder::Circle(GRAY))
+            .await,
+        sprite_asteroid_2: loader
+            .texture("asteroid_2", TexturePlaceholder::Circle(GRAY))
+            .await,
+        sprite_asteroid_1: loader
+            .texture("asteroid_1", TexturePlaceholder::Circle(GRAY))
+            .await,
+        sprite_background: loader
+            .texture("background", TexturePlaceholder::Solid(BLACK))
+            .await,
+        sprite_meteor: loader
+            .texture("missile", TexturePlaceholder::Circle(ORANGE))
+            .await,
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let (stream, record) = match launch {
+        Launch::Game { stream, record } => (stream, record),
+        Launch::Connect(address) => return client::play(&address, textures, settings).await,
+        Launch::Watch(address) => {
+            match SnapshotReader::connect(&address) {
+                Ok(reader) => spectator::watch(reader, textures, &settings).await,
+                Err(error) => eprintln!("Impossible de joindre le flux {} : {}", address, error),
             }
-            if config_screen.is_exit_pressed() {
-                break;
+            return;
+        }
+        Launch::Replay(path) => {
+            match SnapshotReader::open(&path) {
+                Ok(reader) => spectator::watch(reader, textures, &settings).await,
+                Err(error) => eprintln!("Impossible d'ouvrir {} : {}", path, error),
             }
-            next_frame().await;
-        } else {
-            // Si le bouclier de le vaisseau est épuisé
-            if vaisseau.get_shield() < 0. {
-                config_screen.set_end_message(&format!("Défaite ! Score : {}", score));
-                play_game_sound(&sounds.lose, false, 0.1);
-                in_configuration = true;
-            } else if liste_asteroid.is_empty() {
-                // Le joueur a gagné
-                let time_bonus = ((begin_time - get_time()) as f32).round() as i32;
-                let shield_bonus = (vaisseau.get_shield() as i32) * 5;
-
-                score += time_bonus + shield_bonus;
-                config_screen.set_end_message(&format!("Victoire ! Score : {}", score));
-                play_game_sound(&sounds.win, false, 0.1);
-                in_configuration = true;
-            } else {
-                // Gérer les entrées et mettre à jour l'état du jeu
-                if is_key_down(KeyCode::Escape) {
-                    config_screen.set_end_message(&format!(
-                        "Vous avez quitté la partie ! Score : {}",
-                        score
-                    ));
-                    in_configuration = true;
-                }
+            return;
+        }
+    };
 
-                update_model_vaisseau(&mut vaisseau);
-                update_asteroids(&mut liste_asteroid);
-                update_missiles(&mut missiles);
+    // Chargement des sons (un son synthétisé ou un silence remplace chaque son manquant)
+    let tone = |start, end, duration| SoundPlaceholder::Tone {
+        start,
+        end,
+        duration,
+    };
+    // Les sons dont la source a une position sont déclinés en plusieurs positions stéréo
+    let panned = |(samples, sample_rate): (Vec<f32>, u32)| async move {
+        PannedSound::new(&samples, sample_rate).await
+    };
+    let sounds = Sounds {
+        shoot: loader.sound("shoot", tone(880., 440., 0.15)).await,
+        asteroid_hit: panned(
+            loader
+                .sound_samples("asteroid_hit", tone(200., 60., 0.3))
+                .await,
+        )
+        .await,
+        music: [
+            loader
+                .sound(
+                    "background_music",
+                    SoundPlaceholder::Synth(synth::music_base),
+                )
+                .await,
+            loader
+                .optional_sound("music_pulse", SoundPlaceholder::Synth(synth::music_pulse))
+                .await,
+            loader
+                .optional_sound(
+                    "music_tension",
+                    SoundPlaceholder::Synth(synth::music_tension),
+                )
+                .await,
+        ],
+        win: loader.sound("win", tone(440., 880., 0.8)).await,
+        lose: loader.sound("lose", tone(440., 110., 1.2)).await,
+        ship_hit: panned(
+            loader
+                .sound_samples("ship_hit", tone(150., 80., 0.25))
+                .await,
+        )
+        .await,
+        laser: assets::sound_from_samples(&synth::laser(LASER_PITCH)).await,
+        explosions: [
+            panned((synth::explosion(1, 1), assets::SAMPLE_RATE)).await,
+            panned((synth::explosion(2, 2), assets::SAMPLE_RATE)).await,
+            panned((synth::explosion(3, 3), assets::SAMPLE_RATE)).await,
+        ],
+        thrust: assets::sound_from_samples(&synth::thrust_rumble(4)).await,
+        heartbeat: [
+            assets::sound_from_samples(&synth::heartbeat(false)).await,
+            assets::sound_from_samples(&synth::heartbeat(true)).await,
+        ],
+    };
 
-                // Tirer un missile si nécessaire
-                if let Some(missile) = vaisseau.fire_missile(get_time()) {
-                    play_game_sound(&sounds.shoot, false, 0.1);
-                    missiles.push(missile);
-                }
+    text::set_ui_font(loader.font("ui").await);
 
-                check_vaisseau_asteroids(
-                    &mut vaisseau,
-                    &mut liste_asteroid,
-                    hauteur_vaisseau,
-                    level_size,
-                    gravite_dist,
-                    Some(&sounds.ship_hit),
-                );
-                check_missiles_asteroids(
-                    &mut missiles,
-                    &mut liste_asteroid,
-                    rayon_missile,
-                    level_size,
-                    Some(&sounds.asteroid_hit),
-                    &mut score,
-                );
-
-                // Si la taille de l'écran a changé, ajuster l'échelle des objets
-                if last_screen_size != (screen_width(), screen_height()) {
-                    update_scale(
-                        &mut hauteur_vaisseau,
-                        &mut rayon_missile,
-                        &mut level_size,
-                        &mut last_screen_size,
-                        &mut gravite_dist,
-                        false,
-                    );
-                }
+    for path in loader.missing() {
+        eprintln!("Ressource introuvable, remplacée par défaut : {}", path);
+    }
 
-                // Affichage des objets et du score
-                draw_background(&textures.sprite_background);
-                draw_vaisseau(&vaisseau, &textures.sprite_vaisseau, hauteur_vaisseau);
-                draw_missiles(&missiles, rayon_missile, &textures.sprite_meteor);
-                draw_asteroids_batched(
-                    &mut liste_asteroid,
-                    level_size,
-                    [
-                        &textures.sprite_asteroid_1,
-                        &textures.sprite_asteroid_2,
-                        &textures.sprite_asteroid_3,
-                    ],
-                );
-
-                let shield_text = format!("Bouclier: {:.0}", vaisseau.get_shield());
-                let score_text = format!("Score: {}", score);
-
-                draw_text(&shield_text, 10.0, 30.0, 30.0, WHITE);
-                draw_text(&score_text, 10.0, 70.0, 30.0, WHITE);
-            }
+    let mut game = Game::new(textures, sounds, settings, loader.missing().to_vec());
+    #[cfg(not(target_arch = "wasm32"))]
+    open_stream(&mut game, stream, record);
+
+    // Intercepter la fermeture de la fenêtre pour enregistrer les préférences avant de quitter
+    prevent_quit();
 
-            next_frame().await
+    loop {
+        if !game.update() {
+            game.save_settings();
+            break;
         }
+        game.draw();
+        next_frame().await;
+        game.end_frame();
+    }
+}
 
-        // Gestion du temps d'exécution de chaque frame
-        let elapsed_frame_time = (get_time() - start_time) as f32;
-        if elapsed_frame_time < frame_duration {
-            let sleep_time = frame_duration - elapsed_frame_time;
-            std::thread::sleep(std::time::Duration::from_secs_f32(sleep_time));
+/// Publie les parties du jeu pour les spectateurs, si la ligne de commande le demande.
+///
+/// # Arguments
+///
+/// * `game` - Le jeu.
+/// * `stream` - L'adresse TCP du flux des spectateurs.
+/// * `record` - Le fichier dans lequel enregistrer le flux, si le flux TCP n'est pas demandé.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_stream(game: &mut Game, stream: Option<String>, record: Option<String>) {
+    if let Some(address) = stream {
+        match SnapshotStream::listen(&address) {
+            Ok(stream) => {
+                if let Some(address) = stream.local_addr() {
+                    println!("Flux des spectateurs ouvert sur {}", address);
+                }
+                game.set_stream(stream);
+            }
+            Err(error) => eprintln!("Impossible d'ouvrir le flux {} : {}", address, error),
+        }
+    } else if let Some(path) = record {
+        match SnapshotStream::record(&path) {
+            Ok(stream) => game.set_stream(stream),
+            Err(error) => eprintln!("Impossible d'enregistrer dans {} : {}", path, error),
         }
     }
 }
@@ -922,6 +1215,7 @@ async fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ::rand::thread_rng;
 
     /// Teste le comportement de le vaisseau avec plusieurs niveaux d'astéroïdes.
     ///
@@ -971,7 +1265,7 @@ mod tests {
             let asteroid2 = Asteroid::new(2, Vec2::ZERO, level_size, Some(positions[1]));
             let asteroid3 = Asteroid::new(3, Vec2::ZERO, level_size, Some(positions[2]));
 
-            let mut asteroids = vec![asteroid1, asteroid2, asteroid3];
+            let mut asteroids = [asteroid1, asteroid2, asteroid3];
 
             // Appel de la fonction
             check_vaisseau_asteroids(
@@ -980,7 +1274,7 @@ mod tests {
                 30.,
                 level_size,
                 30.,
-                None,
+                &mut EventQueue::new(),
             );
             // Déterminer les indices des astéroïdes à vérifier
             let check_indices = match iteration {
@@ -1026,7 +1320,8 @@ mod tests {
 
         let rayon_missile = 7.0;
 
-        let mut score = 0;
+        let mut score = Score::new();
+        let mut events = EventQueue::new();
 
         let mut asteroids = vec![asteroid];
 
@@ -1042,9 +1337,9 @@ mod tests {
                 &mut asteroids,
                 rayon_missile,
                 level_size,
-                None,
-                &mut score,
+                &mut events,
             );
+            events.dispatch(&mut [&mut score]);
             if let Some(last_asteroid) = asteroids.last_mut() {
                 let k = (i + 1) as f32;
                 last_asteroid.set_position(Vec2::new(k * 100., k * 100.));
@@ -1062,7 +1357,7 @@ mod tests {
             "Il es sensé n'y avoir aucun missile restant"
         );
         assert!(
-            score == 60,
+            score.get() == 60,
             "Le score devrait être de 60 (level 3 : 30 + level 2 : 20 + level 1 : 10)"
         )
     }
@@ -1075,28 +1370,33 @@ mod tests {
     #[test]
     fn test_reset_game() {
         let position = Some(Vec2::new(0., 0.));
-        let mut vaisseau = Vaisseau::new(position, Some(0.));
+        let mut ships = vec![Vaisseau::new(position, Some(0.))];
         let mut liste_asteroid = vec![Asteroid::new(3, Vec2::ZERO, (40.0, 20.0, 10.0), position)];
         let mut missiles = Vec::new();
-        let mut score = 100; // Un score initial non nul
+        let mut score = Score::new();
+        score.add(100); // Un score initial non nul
 
         reset_game(
             &mut liste_asteroid,
-            &mut vaisseau,
+            &mut ships,
+            1,
             &mut missiles,
             (40.0, 20.0, 10.0),
+            vec2(800., 600.),
             5,
             1.0,
             &mut score,
             true,
+            &mut thread_rng(),
         );
 
         // Vérifiez si le score a été réinitialisé
-        assert!(score == 0, "Initalement le score doit être a 0");
+        assert!(score.get() == 0, "Initalement le score doit être a 0");
 
         // Vérifiez si le vaisseau est réinitialisé (par exemple, sa position)
+        assert_eq!(ships.len(), 1, "Une partie solo n'a qu'un vaisseau");
         assert!(
-            vaisseau.get_position() == Vec2::ZERO,
+            ships[0].get_position() == Vec2::ZERO,
             "On simule que le vaisseau spawn en 0 0 donc il doit y être"
         );
 
@@ -1105,7 +1405,123 @@ mod tests {
             liste_asteroid.len() == 5,
             "Le nombre d'asteroid doit être 5"
         ); // Nombre d'astéroïdes après réinitialisation
-        assert!(missiles.len() == 0, "Il doit y avoir 0 missile"); // Les missiles doivent être vides
+        assert!(missiles.is_empty(), "Il doit y avoir 0 missile"); // Les missiles doivent être vides
+    }
+
+    /// Teste la coopération : un missile touche le vaisseau d'un autre joueur mais jamais
+    /// celui qui l'a tiré, les points vont au joueur qui a détruit l'astéroïde, et les vies
+    /// sont propres à chaque joueur ou communes.
+    #[test]
+    fn test_coop() {
+        use players::Lives;
+
+        let mut ships = Vec::new();
+        for player in 0..2 {
+            let mut vaisseau = Vaisseau::new(Some(Vec2::new(100. * player as f32, 0.)), Some(0.));
+            vaisseau.set_player(player);
+            ships.push(vaisseau);
+        }
+        let mut own_missile = Missile::new(Vec2::new(0., 0.), 0.);
+        own_missile.set_owner(0);
+        let mut friendly_missile = Missile::new(Vec2::new(100., 0.), 0.);
+        friendly_missile.set_owner(0);
+        let mut missiles = vec![own_missile, friendly_missile];
+        let mut events = EventQueue::new();
+
+        check_missiles_ships(&mut missiles, &mut ships, 7., 30., &mut events);
+        assert_eq!(ships[0].get_shield(), vaisseau::MAX_SHIELD);
+        assert_eq!(ships[1].get_shield(), vaisseau::MAX_SHIELD - 1.);
+        assert_eq!(missiles.len(), 1, "Seul le missile qui a touché disparaît");
+
+        // Un vaisseau qui vient de réapparaître ne peut pas être touché
+        ships[1].set_invulnerable(players::RESPAWN_INVULNERABILITY);
+        missiles[0].set_position(Vec2::new(100., 0.));
+        missiles[0].set_owner(0);
+        check_missiles_ships(&mut missiles, &mut ships, 7., 30., &mut events);
+        assert_eq!(ships[1].get_shield(), vaisseau::MAX_SHIELD - 1.);
+
+        let mut score = Score::new();
+        events.push(GameEvent::AsteroidDestroyed {
+            level: 3,
+            position: Vec2::ZERO,
+            player: 1,
+        });
+        events.dispatch(&mut [&mut score]);
+        score.add(5);
+        assert_eq!(score.get_player(0), 0);
+        assert_eq!(score.get_player(1), 30);
+        assert_eq!(score.get(), 35);
+
+        // Vies séparées : un joueur sans vie ne peut pas utiliser celles de l'autre
+        let mut separate = Lives::new(2, 1, false);
+        assert!(separate.try_respawn(0));
+        assert!(!separate.try_respawn(0));
+        assert_eq!(separate.get(1), 1);
+
+        // Vies communes : un joueur peut utiliser toute la réserve de l'équipe
+        let mut shared = Lives::new(2, 1, true);
+        assert!(shared.try_respawn(0));
+        assert!(shared.try_respawn(0));
+        assert!(!shared.try_respawn(1));
+    }
+
+    /// Teste le versus : le dernier joueur à avoir touché un vaisseau marque le frag, un
+    /// vaisseau détruit par un astéroïde ne rapporte rien, le match se termine à la limite de
+    /// frags, et un vaisseau détruit réapparaît après le délai.
+    #[test]
+    fn test_versus() {
+        use versus::{PendingRespawn, Scoreboard, VersusRules, RESPAWN_DELAY};
+
+        let mut target = Vaisseau::new(Some(Vec2::ZERO), Some(0.));
+        target.set_player(1);
+        let mut missile = Missile::new(Vec2::ZERO, 0.);
+        missile.set_owner(2);
+        let mut ships = [target];
+        check_missiles_ships(
+            &mut vec![missile],
+            &mut ships,
+            7.,
+            30.,
+            &mut EventQueue::new(),
+        );
+        assert_eq!(ships[0].get_last_attacker(), Some(2));
+        // Un astéroïde porte le dernier coup : personne ne marque
+        ships[0].handle_collision(1, true, Vec2::ZERO);
+        assert_eq!(ships[0].get_last_attacker(), None);
+
+        let mut scoreboard = Scoreboard::new();
+        let mut events = EventQueue::new();
+        for (player, killer) in [(1, Some(0)), (0, Some(1)), (1, None), (1, Some(0))] {
+            events.push(GameEvent::ShipDestroyed {
+                player,
+                killer,
+                position: Vec2::ZERO,
+            });
+        }
+        events.dispatch(&mut [&mut scoreboard]);
+        assert_eq!(scoreboard.get_frags(), [2, 1, 0, 0]);
+        assert_eq!(scoreboard.get_deaths(), [1, 3, 0, 0]);
+        assert_eq!(scoreboard.leader(2), Some(0));
+        assert_eq!(Scoreboard::new().leader(2), None, "Égalité à zéro");
+
+        let rules = VersusRules {
+            frag_limit: 2,
+            time_limit: 60,
+        };
+        assert!(rules.is_over(&scoreboard, 10.));
+        assert!(!rules.is_over(&Scoreboard::new(), 59.));
+        assert!(rules.is_over(&Scoreboard::new(), 60.));
+
+        let mut pending = vec![PendingRespawn {
+            player: 1,
+            keys: Default::default(),
+            steps: RESPAWN_DELAY,
+        }];
+        for _ in 1..RESPAWN_DELAY {
+            assert!(versus::tick_respawns(&mut pending).is_empty());
+        }
+        assert_eq!(versus::tick_respawns(&mut pending).len(), 1);
+        assert!(pending.is_empty());
     }
 
     /// Teste la trajectoire du missile.
@@ -1126,7 +1542,7 @@ mod tests {
         let screen_width = 101.;
         let screen_height = 102.;
 
-        missile.update_position();
+        missile.update_position(vec2(screen_width, screen_height));
 
         assert!(
             missile.get_position() == expected_position_after_1s,
@@ -1139,7 +1555,7 @@ mod tests {
         );
 
         let expected_position_after_2s = expected_position_after_1s + missile_velocity;
-        missile.update_position();
+        missile.update_position(vec2(screen_width, screen_height));
 
         assert!(
             missile.get_position() == expected_position_after_2s,
@@ -1151,39 +1567,1091 @@ mod tests {
             "Le missile devrait être hors de l'écran après plusieurs déplacements"
         );
     }
-    /// Teste l'application du facteur d'échelle pour ajuster la taille des éléments.
-    ///
-    /// Ce test vérifie que les différentes tailles, telles que la hauteur de le vaisseau,
-    /// le rayon du missile et la taille du niveau, sont mises à l'échelle correctement
-    /// en fonction du nouveau facteur d'échelle de l'écran.
+    /// Teste la caméra de l'arène : l'arène garde sa taille et les tailles des objets ne
+    /// changent pas avec la fenêtre, qui est remplie sans déformation avec des bandes noires
+    /// sur les côtés ou en haut et en bas.
     #[test]
     fn test_scale() {
-        let mut hauteur_vaisseau: f32 = 30.;
-        let mut rayon_missile: f32 = 7.;
-        let mut level_size: (f32, f32, f32) = (40., 20., 10.);
-        let mut gravite_dist: f32 = 30.;
-        let mut last_screen_size: (f32, f32) = (400., 300.);
-
-        let width_scale = 500. / last_screen_size.0;
-        let height_scale = 400. / last_screen_size.1;
-
-        let scale_factor = (width_scale + height_scale) / 2.;
-
-        update_scale(
-            &mut hauteur_vaisseau,
-            &mut rayon_missile,
-            &mut level_size,
-            &mut last_screen_size,
-            &mut gravite_dist,
-            true,
+        use camera::{ArenaAspect, ArenaCamera, ARENA_HEIGHT};
+        use world::World;
+
+        assert_eq!(ArenaAspect::Standard.get_size(), vec2(800., ARENA_HEIGHT));
+        assert_eq!(ArenaAspect::Wide.get_size(), vec2(1067., ARENA_HEIGHT));
+        assert_eq!(ArenaAspect::Square.get_size(), vec2(600., ARENA_HEIGHT));
+        for aspect in ArenaAspect::ALL {
+            assert_eq!(ArenaAspect::from_key(aspect.key()), Some(aspect));
+        }
+        assert_eq!(ArenaAspect::from_key("3:2"), None);
+
+        let arena = ArenaAspect::Standard.get_size();
+        let world = World::new(ArenaAspect::Standard);
+        assert_eq!(world.arena, arena);
+        assert_eq!(world.hauteur_vaisseau, 30.);
+        assert_eq!(world.rayon_missile, 7.);
+        assert_eq!(world.level_size, (40., 20., 10.));
+        assert_eq!(world.gravite_dist, 30.);
+
+        // Même format : l'arène remplit toute la fenêtre
+        let camera = ArenaCamera::new(arena, vec2(1600., 1200.));
+        assert_eq!(camera.get_viewport(), Rect::new(0., 0., 1600., 1200.));
+        assert_eq!(camera.world_to_screen(vec2(400., 300.)), vec2(800., 600.));
+
+        // Fenêtre plus large : bandes à gauche et à droite
+        let camera = ArenaCamera::new(arena, vec2(1920., 1080.));
+        assert_eq!(camera.get_viewport(), Rect::new(240., 0., 1440., 1080.));
+        assert_eq!(camera.world_to_screen(Vec2::ZERO), vec2(240., 0.));
+        assert_eq!(camera.world_to_screen(arena), vec2(1680., 1080.));
+
+        // Fenêtre plus haute : bandes en haut et en bas
+        let camera = ArenaCamera::new(arena, vec2(400., 600.));
+        assert_eq!(camera.get_viewport(), Rect::new(0., 150., 400., 300.));
+        assert_eq!(camera.world_to_screen(vec2(800., 0.)), vec2(400., 150.));
+    }
+
+    /// Teste le classement, la limite de taille et la relecture du tableau des meilleurs scores.
+    #[test]
+    fn test_highscores() {
+        use game_state::GameMode;
+        use highscores::{HighScoreEntry, HighScoreTable, MAX_ENTRIES};
+
+        let entry = |name: &str, score: i32| HighScoreEntry {
+            name: name.to_string(),
+            score,
+            wave: 2,
+            duration: 61.5,
+            seed: 1234,
+            date: 1_700_000_000,
+        };
+
+        let mut table = HighScoreTable::default();
+        assert!(!table.qualifies(GameMode::Classic, 0));
+        assert_eq!(table.insert(GameMode::Classic, entry("B", 50)), Some(0));
+        assert_eq!(table.insert(GameMode::Classic, entry("A", 80)), Some(0));
+        assert_eq!(table.insert(GameMode::Classic, entry("C", 10)), Some(2));
+
+        // Remplir le tableau : un score trop faible n'y entre plus
+        for i in 0..MAX_ENTRIES {
+            table.insert(GameMode::Classic, entry("X", 20 + i as i32));
+        }
+        let entries = table.entries(GameMode::Classic);
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].name, "A");
+        assert!(!table.qualifies(GameMode::Classic, 10));
+        assert!(table.qualifies(GameMode::Classic, 1000));
+
+        // Relire le fichier produit
+        let reloaded = HighScoreTable::parse(&table.serialize());
+        let reloaded_entries = reloaded.entries(GameMode::Classic);
+        assert_eq!(reloaded_entries.len(), MAX_ENTRIES);
+        assert_eq!(*reloaded_entries[0], *entries[0]);
+        assert_eq!(highscores::format_date(1_700_000_000), "2023-11-14");
+    }
+
+    /// Teste la lecture du fichier de préférences : valeurs valides, valeurs hors limites,
+    /// valeurs invalides, et relecture du fichier produit.
+    #[test]
+    fn test_settings() {
+        let text = "# commentaire\n\
+                    asteroid_count = 40\n\
+                    asteroid_speed = 2.5\n\
+                    waves = 5\n\
+                    music_volume = abc\n\
+                    window_mode = fullscreen\n\
+                    key_fire = z\n\
+                    inconnue = 1\n\
+                    ligne sans égal\n";
+        let (settings, warnings) = Settings::parse(text);
+
+        assert_eq!(settings.asteroid_count, 25); // Ramené au maximum
+        assert_eq!(settings.asteroid_speed, 2.5);
+        assert_eq!(settings.waves, 5);
+        assert_eq!(Settings::default().waves, 1); // Vider le champ suffit pour gagner
+        assert_eq!(settings.music_volume, Settings::default().music_volume);
+        assert_eq!(settings.window_mode, WindowMode::Fullscreen);
+        assert_eq!(settings.keys.fire, KeyCode::Z);
+        assert_eq!(warnings.len(), 4);
+
+        // Une touche attribuée deux fois remet les touches par défaut
+        let (duplicated, warnings) = Settings::parse("key_fire = Up\n");
+        assert_eq!(duplicated.keys, settings::KeyBindings::default());
+        assert_eq!(warnings.len(), 1);
+
+        let (reloaded, warnings) = Settings::parse(&settings.serialize());
+        assert_eq!(reloaded, settings);
+        assert!(warnings.is_empty());
+    }
+
+    /// Teste la sauvegarde d'une partie : relecture exacte du fichier, refus d'une autre
+    /// version, reprise du générateur aléatoire au même point, et missiles qui gardent leur
+    /// tireur et vaisseaux leur recharge d'une capture du monde à sa restauration, sur
+    /// l'horloge de la simulation.
+    #[test]
+    fn test_save_game() {
+        use rng::GameRng;
+        use save::{SaveGame, SavedShip};
+
+        let mut rng = GameRng::new(1234);
+        let _ = rng.gen::<u64>();
+        let save = SaveGame {
+            seed: 1234,
+            rng_state: rng.get_state(),
+            wave: 3,
+            score: 420,
+            player_scores: [250, 140, 0, 0],
+            players: 2,
+            lives: players::Lives::from_counts(true, [3, 0, 0, 0]),
+            friendly_fire: true,
+            versus: Some(versus::VersusRules {
+                frag_limit: 15,
+                time_limit: 300,
+            }),
+            match_elapsed: 42.5,
+            scoreboard: versus::Scoreboard::from_counts([3, 1, 0, 0], [1, 2, 0, 0]),
+            elapsed: 83.123456789,
+            wave_elapsed: 0.1 + 0.2,
+            asteroid_count: 12,
+            asteroid_speed: 1.7,
+            waves: 5,
+            cheated: true,
+            stats: stats::GameStats {
+                shots_fired: 30,
+                shots_hit: 12,
+                asteroids_destroyed: [4, 5, 3],
+                hits_taken: 2,
+                damage_taken: 1.5,
+            },
+            ships: vec![
+                SavedShip {
+                    player: 0,
+                    position: Vec2::new(123.456, 78.9),
+                    rotation: -2.345_678,
+                    speed: Vec2::new(0.1, -0.7),
+                    shield: 3.5,
+                    last_shot_age: 0.25,
+                },
+                SavedShip {
+                    player: 1,
+                    position: Vec2::new(400., 300.),
+                    rotation: 0.5,
+                    speed: Vec2::ZERO,
+                    shield: 5.,
+                    last_shot_age: 1.5,
+                },
+            ],
+            asteroids: vec![(3, Vec2::new(-40., 10.5), Vec2::new(1.25, -0.333_333_34))],
+            missiles: vec![(2, Vec2::new(200., 300.), Vec2::new(0., -5.))],
+        };
+
+        let text = save.serialize();
+        assert_eq!(SaveGame::parse(&text), Ok(save.clone()));
+        assert!(SaveGame::parse(&text.replacen("asteroid-save 6", "asteroid-save 99", 1)).is_err());
+        assert!(SaveGame::parse("n'importe quoi").is_err());
+
+        // Le générateur restauré produit les mêmes tirages que l'original
+        let mut restored = GameRng::new(save.seed);
+        restored.set_state(save.rng_state);
+        assert_eq!(restored.gen::<u64>(), rng.gen::<u64>());
+
+        // La recharge des tirs suit l'horloge de la simulation : un tir par demi-seconde
+        let fire = vaisseau::ShipInput {
+            fire: true,
+            ..Default::default()
+        };
+        let mut events = events::EventQueue::new();
+        let mut world = world::World::with_arena(vec2(800., 600.));
+        world.ships[0].set_input(fire);
+        for _ in 0..100 {
+            world.step(&mut events);
+        }
+        assert_eq!(world.missiles.len(), 1);
+        world.missiles[0].set_owner(2);
+
+        // Le tireur des missiles et la recharge en cours survivent à la sauvegarde
+        let text = SaveGame::capture(&world).serialize();
+        let mut restored = world::World::with_arena(vec2(800., 600.));
+        SaveGame::parse(&text).unwrap().restore(&mut restored);
+        assert_eq!(restored.missiles.len(), 1);
+        assert_eq!(restored.missiles[0].get_owner(), 2);
+        restored.ships[0].set_input(fire);
+        for _ in 0..10 {
+            restored.step(&mut events);
+        }
+        assert_eq!(restored.missiles.len(), 1);
+        for _ in 0..20 {
+            restored.step(&mut events);
+        }
+        assert_eq!(restored.missiles.len(), 2);
+    }
+
+    /// Teste l'encodage WAV des sons générés : en-tête RIFF, taille et écrêtage des échantillons.
+    #[test]
+    fn test_encode_wav() {
+        let wav = assets::encode_wav(&[0., 1., -2.], 44_100);
+
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(wav.len(), 44 + 3 * 2);
+        assert_eq!(u32::from_le_bytes([wav[4], wav[5], wav[6], wav[7]]), 36 + 6);
+        assert_eq!(i16::from_le_bytes([wav[46], wav[47]]), i16::MAX);
+        assert_eq!(i16::from_le_bytes([wav[48], wav[49]]), -i16::MAX);
+    }
+
+    /// Teste la limitation du nombre d'exemplaires simultanés d'un effet sonore et la
+    /// relecture de la sourdine dans les préférences.
+    #[test]
+    fn test_audio_voices() {
+        use audio::{SoundEffect, VoiceLimiter};
+
+        let mut voices = VoiceLimiter::default();
+        let max = SoundEffect::Shoot.max_voices();
+        for _ in 0..max {
+            assert!(voices.try_start(SoundEffect::Shoot, 0.));
+        }
+        assert!(!voices.try_start(SoundEffect::Shoot, 0.)); // Limite atteinte
+        assert!(voices.try_start(SoundEffect::AsteroidHit, 0.)); // Autre effet : indépendant
+
+        // Les exemplaires terminés libèrent leur place
+        let later = SoundEffect::Shoot.voice_duration() + 0.01;
+        assert!(voices.try_start(SoundEffect::Shoot, later));
+
+        voices.release(SoundEffect::Win);
+        assert!(voices.try_start(SoundEffect::Win, later));
+        assert!(!voices.try_start(SoundEffect::Win, later));
+        voices.release(SoundEffect::Win);
+        assert!(voices.try_start(SoundEffect::Win, later));
+
+        let (settings, warnings) = Settings::parse("muted = true\nmaster_volume = 0.5\n");
+        assert!(settings.muted);
+        assert!(warnings.is_empty());
+        assert_eq!(Settings::parse(&settings.serialize()).0, settings);
+    }
+
+    /// Teste la synthèse des sons : échantillons entre -1 et 1, explosions plus longues pour
+    /// les gros astéroïdes, sons reproductibles et rythme de fond qui accélère.
+    #[test]
+    fn test_synth() {
+        let sounds = [
+            synth::laser(LASER_PITCH),
+            synth::explosion(1, 7),
+            synth::explosion(3, 7),
+            synth::thrust_rumble(7),
+            synth::heartbeat(true),
+        ];
+        for samples in &sounds {
+            assert!(!samples.is_empty());
+            assert!(samples.iter().all(|s| (-1. ..=1.).contains(s)));
+        }
+        assert!(sounds[2].len() > sounds[1].len());
+        assert_eq!(synth::explosion(2, 7), synth::explosion(2, 7));
+
+        // La boucle du réacteur ne claque pas : le saut entre la fin et le début ne dépasse
+        // pas le plus grand saut entre deux échantillons voisins
+        let rumble = &sounds[3];
+        let max_step = rumble
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .fold(0., f32::max);
+        assert!((rumble[rumble.len() - 1] - rumble[0]).abs() <= max_step);
+
+        assert_eq!(synth::hits_to_clear(3), 7);
+        assert_eq!(synth::heartbeat_interval(70, 70), 1.0);
+        assert_eq!(synth::heartbeat_interval(0, 70), 0.25);
+        assert!(synth::heartbeat_interval(10, 70) < synth::heartbeat_interval(40, 70));
+    }
+
+    /// Teste la spatialisation des sons : position stéréo selon le côté de l'événement,
+    /// atténuation avec la distance, puissance constante et relecture d'un WAV stéréo.
+    #[test]
+    fn test_stereo_panning() {
+        use audio::{pan_gains, pan_step, spatialize, PAN_STEPS};
+
+        let screen = vec2(800., 600.);
+        let ship = vec2(400., 300.);
+        assert_eq!(spatialize(ship, ship, screen), (0., 1.));
+        let (pan, gain) = spatialize(vec2(0., 300.), ship, screen);
+        assert_eq!(pan, -1.);
+        assert!((0.5..1.).contains(&gain));
+        let (pan, _) = spatialize(vec2(500., 300.), ship, screen);
+        assert_eq!(pan, 0.25);
+
+        assert_eq!(pan_step(-1.), 0);
+        assert_eq!(pan_step(0.), PAN_STEPS / 2);
+        assert_eq!(pan_step(3.), PAN_STEPS - 1);
+        for pan in [-1., -0.3, 0., 0.7, 1.] {
+            let (left, right) = pan_gains(pan);
+            assert!((left * left + right * right - 1.).abs() < 1e-5);
+        }
+        let (left, right) = pan_gains(-1.);
+        assert!(left > 0.99 && right < 0.01);
+
+        // Un WAV stéréo relu est mélangé en mono
+        let wav = assets::encode_wav_stereo(&[[0.5, 0.], [-0.5, -0.5]], 22_050);
+        let (samples, sample_rate) = assets::decode_wav(&wav).unwrap();
+        assert_eq!(sample_rate, 22_050);
+        assert_eq!(samples.len(), 2);
+        assert!((samples[0] - 0.25).abs() < 1e-3);
+        assert!((samples[1] + 0.5).abs() < 1e-3);
+        assert!(assets::decode_wav(b"pas un wav").is_none());
+    }
+
+    /// Teste les couches de la musique : l'intensité monte avec l'avancement et le danger,
+    /// et les couches la suivent par des fondus progressifs.
+    #[test]
+    fn test_music_layers() {
+        use audio::{music_intensity, MusicLayers};
+
+        assert_eq!(music_intensity(1., 1., false), 0.);
+        assert_eq!(music_intensity(0., 0., true), 1.);
+        assert!(music_intensity(0.2, 1., false) > music_intensity(0.8, 1., false));
+        assert!(music_intensity(0.5, 0.2, false) > music_intensity(0.5, 1., false));
+
+        // Au calme, seule la couche de base est jouée
+        let mut layers = MusicLayers::default();
+        assert_eq!(layers.get_levels(), [1., 0., 0.]);
+
+        // À pleine intensité, les couches montent progressivement
+        layers.set_intensity(1.);
+        layers.update(0.5);
+        let levels = layers.get_levels();
+        assert!(levels[1] > 0. && levels[1] < 1.);
+        for _ in 0..10 {
+            layers.update(0.5);
+        }
+        assert_eq!(layers.get_levels(), [1., 1., 1.]);
+
+        // Puis redescendent quand l'intensité baisse
+        layers.set_intensity(0.);
+        layers.update(0.5);
+        assert!(layers.get_levels()[2] < 1.);
+    }
+
+    /// Teste le jeu en réseau sur la boucle locale : encodage des messages, interpolation, puis
+    /// un serveur et plusieurs clients sur la même machine (connexion, partie pleine, prédiction
+    /// du vaisseau du joueur, tir vu par les autres clients et départ d'un client).
+    #[test]
+    fn test_network_loopback() {
+        use client::Client;
+        use net::{Message, Snapshot};
+        use server::{Server, ServerConfig};
+        use std::time::Duration;
+        use vaisseau::ShipInput;
+        use world::World;
+
+        // Les messages sont relus à l'identique
+        let arena = vec2(800., 600.);
+        let mut world = World::with_arena(arena);
+        world.asteroids.push(Asteroid::new(
+            2,
+            vec2(1., 0.5),
+            world.level_size,
+            Some(vec2(100., 100.)),
+        ));
+        let snapshot = Snapshot::capture(&world, 42);
+        let message = Message::Snapshot(snapshot.clone());
+        assert_eq!(Message::decode(&message.encode()), Ok(message));
+        let input = ShipInput {
+            thrust: true,
+            fire: true,
+            ..Default::default()
+        };
+        assert_eq!(ShipInput::from_bits(input.to_bits()), input);
+        assert!(Message::decode("snapshot 1 2 3\nasteroid 7 0 0 0 0").is_err());
+
+        // Interpolation à mi-chemin, sauf pour un objet qui a traversé un bord de l'arène
+        let mut next = snapshot.clone();
+        next.asteroids[0].1 = vec2(110., 100.);
+        let halfway = snapshot.interpolate(&next, 0.5, arena);
+        assert_eq!(halfway.asteroids[0].1, vec2(105., 100.));
+        next.asteroids[0].1 = vec2(795., 100.);
+        let wrapped = snapshot.interpolate(&next, 0.5, arena);
+        assert_eq!(wrapped.asteroids[0].1, vec2(795., 100.));
+
+        // Un serveur pour deux joueurs et trois clients
+        let config = ServerConfig {
+            max_players: 2,
+            asteroid_count: 0,
+            seed: 1,
+            ..Default::default()
+        };
+        let mut server = Server::bind("127.0.0.1:0", config).unwrap();
+        let address = server.local_addr().unwrap();
+        let mut clients = vec![
+            Client::connect(address).unwrap(),
+            Client::connect(address).unwrap(),
+        ];
+        let mut now = 0.;
+        let round =
+            |server: &mut Server, clients: &mut [Client], inputs: &[ShipInput], now: &mut f64| {
+                for (client, &input) in clients.iter_mut().zip(inputs) {
+                    client.send_input(input).unwrap();
+                }
+                std::thread::sleep(Duration::from_millis(1));
+                server.poll().unwrap();
+                server.tick().unwrap();
+                std::thread::sleep(Duration::from_millis(1));
+                *now += 1. / net::TICK_RATE;
+                for client in clients.iter_mut() {
+                    client.poll(*now).unwrap();
+                }
+            };
+
+        for _ in 0..5 {
+            round(&mut server, &mut clients, &[], &mut now);
+        }
+        assert_eq!(clients[0].get_player(), Some(0));
+        assert_eq!(clients[1].get_player(), Some(1));
+        assert_eq!(clients[0].get_arena(), arena);
+        assert_eq!(server.get_client_count(), 2);
+        assert_eq!(server.get_world().ships.len(), 2);
+
+        let mut third = vec![Client::connect(address).unwrap()];
+        round(&mut server, &mut third, &[], &mut now);
+        assert_eq!(third[0].get_refusal(), Some("partie pleine"));
+        assert_eq!(server.get_client_count(), 2);
+
+        // Le joueur 1 accélère : son vaisseau prédit avance avec celui du serveur
+        let start = clients[0].get_predicted().unwrap().get_position();
+        let thrust = ShipInput {
+            thrust: true,
+            ..Default::default()
+        };
+        for _ in 0..30 {
+            round(
+                &mut server,
+                &mut clients,
+                &[thrust, ShipInput::default()],
+                &mut now,
+            );
+        }
+        for _ in 0..5 {
+            round(&mut server, &mut clients, &[], &mut now);
+        }
+        let predicted = clients[0].get_predicted().unwrap().get_position();
+        let authoritative = server.get_world().ships[0].get_position();
+        assert!(
+            predicted.distance(start) > 10.,
+            "Le vaisseau prédit n'a pas bougé"
+        );
+        assert!(
+            predicted.distance(authoritative) < 2.,
+            "La prédiction s'écarte du serveur : {} / {}",
+            predicted,
+            authoritative
+        );
+
+        // Le deuxième client voit le vaisseau du joueur 1 s'être déplacé
+        let mut view = World::with_arena(arena);
+        assert!(clients[1].view(now + 1., &mut view));
+        let seen = view
+            .ships
+            .iter()
+            .find(|vaisseau| vaisseau.get_player() == 0);
+        assert!(seen.unwrap().get_position().distance(start) > 10.);
+
+        // Le tir du joueur 2 arrive chez le joueur 1
+        let fire = ShipInput {
+            fire: true,
+            ..Default::default()
+        };
+        // Le premier tir attend la fin du temps de recharge (0,5 s depuis le début), puis un
+        // instantané est envoyé tous les 4 pas
+        for _ in 0..24 {
+            round(
+                &mut server,
+                &mut clients,
+                &[ShipInput::default(), fire],
+                &mut now,
+            );
+        }
+        let latest = clients[0].get_latest().unwrap();
+        assert!(latest.missiles.iter().any(|missile| missile.0 == 1));
+
+        // Le départ d'un client retire son vaisseau
+        clients[1].disconnect();
+        round(&mut server, &mut clients[..1], &[], &mut now);
+        assert_eq!(server.get_client_count(), 1);
+        assert_eq!(server.get_world().ships.len(), 1);
+    }
+
+    /// Teste le flux des spectateurs : les instantanés publiés arrivent dans l'ordre en TCP
+    /// et depuis un enregistrement, et la caméra suit les joueurs puis redevient libre.
+    #[test]
+    fn test_spectator_stream() {
+        use net::{Message, Snapshot};
+        use spectator::SpectatorCamera;
+        use std::time::Duration;
+        use world::World;
+
+        let arena = vec2(800., 600.);
+        let mut world = World::with_arena(arena);
+        world.ships[0].set_player(2);
+        let snapshots: Vec<Snapshot> = (0..3).map(|tick| Snapshot::capture(&world, tick)).collect();
+        let read_all = |reader: &mut SnapshotReader| {
+            let mut messages = Vec::new();
+            for _ in 0..100 {
+                reader.fill().unwrap();
+                while let Some(message) = reader.next_message() {
+                    messages.push(message);
+                }
+                if messages.len() == 4 {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            messages
+        };
+        let expected: Vec<Message> = std::iter::once(Message::Arena { size: arena })
+            .chain(snapshots.iter().cloned().map(Message::Snapshot))
+            .collect();
+
+        // En direct, sur la boucle locale
+        let mut stream = SnapshotStream::listen("127.0.0.1:0").unwrap();
+        let mut reader = SnapshotReader::connect(stream.local_addr().unwrap()).unwrap();
+        assert!(reader.is_live());
+        std::thread::sleep(Duration::from_millis(5));
+        for snapshot in &snapshots {
+            stream.publish(arena, snapshot).unwrap();
+        }
+        assert_eq!(read_all(&mut reader), expected);
+
+        // Enregistré dans un fichier puis rejoué
+        let path = std::env::temp_dir().join(format!("asteroid-stream-{}.txt", std::process::id()));
+        let mut recording = SnapshotStream::record(&path).unwrap();
+        for snapshot in &snapshots {
+            recording.publish(arena, snapshot).unwrap();
+        }
+        drop(recording);
+        let mut replay = SnapshotReader::open(&path).unwrap();
+        assert!(!replay.is_live());
+        assert_eq!(read_all(&mut replay), expected);
+        replay.fill().unwrap();
+        assert!(replay.is_ended());
+        std::fs::remove_file(&path).unwrap();
+
+        // La caméra suit le joueur 3, puis redevient libre
+        let mut with_two = snapshots[0].clone();
+        with_two.ships.push(with_two.ships[0].clone());
+        with_two.ships[1].player = 0;
+        with_two.ships[1].position = vec2(100., 50.);
+        let mut camera = SpectatorCamera::new(arena);
+        camera.cycle_follow(&with_two);
+        assert_eq!(camera.follow, Some(0));
+        camera.update(&with_two);
+        assert_eq!(camera.center, vec2(100., 50.));
+        camera.cycle_follow(&with_two);
+        assert_eq!(camera.follow, Some(2));
+        camera.cycle_follow(&with_two);
+        assert_eq!(camera.follow, None);
+        camera.follow = Some(0);
+        camera.pan(vec2(-500., 0.));
+        assert_eq!(camera.follow, None);
+        assert_eq!(camera.center, vec2(0., 50.));
+    }
+
+    /// Teste les commandes analogiques : zone morte, stick en mode absolu ou tank, gâchette
+    /// proportionnelle, rotation vers la direction visée, réseau et boutons des préférences.
+    #[test]
+    fn test_gamepad_controls() {
+        use controls::{apply_deadzone, heading_towards, PadState, StickMode};
+        use net::Message;
+        use std::f32::consts::{FRAC_PI_2, TAU};
+        use vaisseau::ShipInput;
+
+        // Zone morte et mise à l'échelle du stick
+        assert_eq!(apply_deadzone(vec2(0.2, 0.)), Vec2::ZERO);
+        assert!((apply_deadzone(vec2(0.625, 0.)).x - 0.5).abs() < 1e-6);
+        assert!((apply_deadzone(vec2(1., 1.)).length() - 1.).abs() < 1e-6);
+
+        // Le stick à droite vise la droite en mode absolu, et fait tourner en mode tank
+        let pad = PadState {
+            stick: vec2(0.625, 0.),
+            thrust: 0.5,
+            fire: true,
+            ..Default::default()
+        };
+        let absolute = pad.to_input(StickMode::Absolute);
+        assert_eq!(absolute.heading, Some(FRAC_PI_2));
+        assert_eq!(absolute.throttle, 0.5);
+        assert!(absolute.fire);
+        let tank = pad.to_input(StickMode::Tank);
+        assert_eq!(tank.heading, None);
+        assert!((tank.turn - 0.5).abs() < 1e-6);
+        let up = PadState {
+            stick: vec2(0., 1.),
+            ..Default::default()
+        };
+        assert_eq!(up.to_input(StickMode::Absolute).heading, Some(0.));
+
+        // La direction visée correspond à celle des missiles
+        let missile = Missile::new(Vec2::ZERO, heading_towards(vec2(-3., 4.)));
+        assert!(missile.get_speed().normalize().distance(vec2(-0.6, 0.8)) < 1e-5);
+
+        // L'accélération est proportionnelle à la gâchette
+        let arena = vec2(800., 600.);
+        let mut vaisseau = Vaisseau::new(Some(vec2(400., 300.)), Some(0.));
+        vaisseau.set_input(ShipInput {
+            throttle: 0.5,
+            ..Default::default()
+        });
+        vaisseau.update_position(arena);
+        assert!(vaisseau.is_thrusting());
+        assert!((vaisseau.get_speed().length() - 0.5).abs() < 1e-6);
+
+        // Le vaisseau tourne vers la direction visée par le plus court chemin
+        vaisseau.set_input(ShipInput {
+            heading: Some(FRAC_PI_2),
+            ..Default::default()
+        });
+        vaisseau.update_position(arena);
+        assert!((vaisseau.get_rotation() - 0.1).abs() < 1e-6);
+        for _ in 0..20 {
+            vaisseau.update_position(arena);
+        }
+        assert!((vaisseau.get_rotation() - FRAC_PI_2).abs() < 1e-6);
+        vaisseau.set_rotation(0.05);
+        vaisseau.set_input(ShipInput {
+            heading: Some(TAU - 0.05),
+            ..Default::default()
+        });
+        vaisseau.update_position(arena);
+        assert!((vaisseau.get_rotation() + 0.05).abs() < 1e-6);
+
+        // Le clavier et la manette se combinent, et les commandes analogiques passent le réseau
+        let keyboard = ShipInput {
+            thrust: true,
+            ..Default::default()
+        };
+        let merged = keyboard.merge(absolute);
+        assert!(merged.thrust && merged.fire);
+        assert_eq!(merged.heading, Some(FRAC_PI_2));
+        let message = Message::Input {
+            sequence: 3,
+            inputs: vec![merged, tank, keyboard],
+        };
+        assert_eq!(Message::decode(&message.encode()), Ok(message));
+        assert!(Message::decode("input 1 1/0.5/nan/-").is_err());
+
+        // Les boutons se choisissent dans les préférences, sans doublon
+        let (settings, warnings) =
+            Settings::parse("pad_fire = west\nstick_mode = tank\ncontrol_scheme = mouse_aim\n");
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(settings.pad_buttons.fire, controls::PadButton::West);
+        assert_eq!(settings.stick_mode, StickMode::Tank);
+        assert_eq!(Settings::parse(&settings.serialize()).0, settings);
+        let (duplicated, warnings) = Settings::parse("pad_fire = Start\n");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(duplicated.pad_buttons, controls::GamepadBindings::default());
+        for button in [
+            controls::PadButton::South,
+            controls::PadButton::RightTrigger2,
+            controls::PadButton::DPadLeft,
+        ] {
+            let name = settings::button_name(button);
+            assert_eq!(settings::button_from_name(name), Some(button));
+        }
+        assert_eq!(
+            settings::button_from_name("righttrigger2"),
+            Some(controls::PadButton::RightTrigger2)
+        );
+        assert_eq!(settings::button_from_name("Unknown"), None);
+    }
+
+    /// Teste les commandes tactiles : boutons à l'écran, visée vers le doigt ou la souris, et
+    /// tir au clic de la souris seulement.
+    #[test]
+    fn test_touch_controls() {
+        use controls::{ControlScheme, Pointers, VirtualButton};
+        use std::f32::consts::FRAC_PI_2;
+
+        let screen = vec2(800., 600.);
+        let ship = vec2(400., 300.);
+        let (thrust, _) = VirtualButton::Thrust.area(screen);
+        let (fire, _) = VirtualButton::Fire.area(screen);
+        let (pause, _) = VirtualButton::Pause.area(screen);
+        assert_eq!(
+            VirtualButton::at(thrust, screen),
+            Some(VirtualButton::Thrust)
+        );
+        assert_eq!(VirtualButton::at(fire, screen), Some(VirtualButton::Fire));
+        assert_eq!(VirtualButton::at(pause, screen), Some(VirtualButton::Pause));
+        assert_eq!(VirtualButton::at(ship, screen), None);
+
+        // Un doigt sur la poussée, un autre qui vise à droite du vaisseau
+        let fingers = Pointers {
+            held: vec![thrust, vec2(500., 300.)],
+            ..Default::default()
+        };
+        let input = fingers.to_input(ship, screen);
+        assert!(input.thrust && !input.fire);
+        assert_eq!(input.heading, Some(FRAC_PI_2));
+
+        // Le bouton de tir tire sans changer la visée
+        let fingers = Pointers {
+            held: vec![fire],
+            ..Default::default()
+        };
+        let input = fingers.to_input(ship, screen);
+        assert!(input.fire && !input.thrust);
+        assert_eq!(input.heading, None);
+
+        // La souris vise sans clic, sauf au-dessus d'un bouton, et tire au clic
+        let mouse = |hover: Vec2, held: Vec<Vec2>| Pointers {
+            hover: Some(hover),
+            held,
+            click_fires: true,
+            thrust: false,
+        };
+        let input = mouse(vec2(400., 200.), Vec::new()).to_input(ship, screen);
+        assert_eq!(input.heading, Some(0.));
+        assert!(!input.fire);
+        assert_eq!(
+            mouse(thrust, vec![thrust]).to_input(ship, screen).heading,
+            None
+        );
+        assert!(mouse(thrust, vec![thrust]).to_input(ship, screen).thrust);
+        assert!(
+            mouse(vec2(1., 1.), vec![vec2(1., 1.)])
+                .to_input(ship, screen)
+                .fire
+        );
+
+        // Le schéma tactile se choisit dans les préférences
+        let (settings, warnings) = Settings::parse("control_scheme = touch\n");
+        assert!(warnings.is_empty());
+        assert_eq!(settings.control_scheme, ControlScheme::Touch);
+        assert_eq!(ControlScheme::Touch.next(), ControlScheme::Keyboard);
+    }
+
+    /// Teste la couche de stockage qui remplace les fichiers par le stockage local dans la
+    /// version web : écriture, relecture et suppression dans un répertoire de données
+    /// temporaire, et graines tirées sans source d'entropie du système.
+    #[test]
+    fn test_web_portability() {
+        let dir = std::env::temp_dir().join(format!("asteroid-test-{}", std::process::id()));
+        let variable = if cfg!(target_os = "windows") {
+            "APPDATA"
+        } else if cfg!(target_os = "macos") {
+            "HOME"
+        } else {
+            "XDG_DATA_HOME"
+        };
+        std::env::set_var(variable, &dir);
+        assert!(storage::data_dir().starts_with(&dir));
+
+        assert_eq!(storage::read_data_file("test.txt"), None);
+        storage::write_data_file("test.txt", "ligne 1\nligne 2\n").unwrap();
+        assert_eq!(
+            storage::read_data_file("test.txt").as_deref(),
+            Some("ligne 1\nligne 2\n")
+        );
+        storage::write_data_file("test.txt", "remplacé").unwrap();
+        assert_eq!(
+            storage::read_data_file("test.txt").as_deref(),
+            Some("remplacé")
+        );
+        storage::remove_data_file("test.txt");
+        assert_eq!(storage::read_data_file("test.txt"), None);
+        // Supprimer un fichier absent n'est pas une erreur
+        storage::remove_data_file("test.txt");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_ne!(rng::random_seed(), rng::random_seed());
+    }
+
+    /// Teste le rythme des images : préférences, échéances régulières avec une limite,
+    /// reprise après un retard et mesure de la durée des frames.
+    #[test]
+    fn test_frame_pacing() {
+        use pacing::{FixedStep, FrameLimit, FramePacer};
+
+        for limit in [
+            FrameLimit::VSync,
+            FrameLimit::Uncapped,
+            FrameLimit::Capped(144),
+        ] {
+            assert_eq!(FrameLimit::from_key(&limit.key()), Some(limit));
+        }
+        assert_eq!(FrameLimit::from_key("5"), None);
+        assert_eq!(FrameLimit::VSync.swap_interval(), 1);
+        assert_eq!(FrameLimit::Capped(60).swap_interval(), 0);
+        let (settings, warnings) = Settings::parse("frame_limit = 75\nshow_frame_graph = true");
+        assert!(warnings.is_empty());
+        assert_eq!(settings.frame_limit, FrameLimit::Capped(75));
+        assert!(Settings::parse(&settings.serialize()).0.show_frame_graph);
+
+        // Sans limite, jamais d'attente
+        let mut pacer = FramePacer::new(FrameLimit::VSync, false);
+        assert_eq!(pacer.next_deadline(1.), None);
+
+        // Les échéances se suivent toutes les 1/64 s, même si une frame finit en avance
+        let mut pacer = FramePacer::new(FrameLimit::Capped(64), false);
+        assert_eq!(pacer.next_deadline(8.), None);
+        assert_eq!(pacer.next_deadline(8.005), Some(8.015625));
+        assert_eq!(pacer.next_deadline(8.015625), Some(8.03125));
+        // Une frame un peu lente n'attend pas, la suivante rattrape
+        assert_eq!(pacer.next_deadline(8.05), None);
+        assert_eq!(pacer.next_deadline(8.055), Some(8.0625));
+        // Un long retard fait repartir les échéances de maintenant
+        assert_eq!(pacer.next_deadline(9.), None);
+        assert_eq!(pacer.next_deadline(9.01), Some(9.015625));
+
+        assert_eq!(pacer.get_average_frame_time(), None);
+        for frame in 0..5 {
+            pacer.record(frame as f64 * 0.02);
+        }
+        let average = pacer.get_average_frame_time().unwrap();
+        assert!((average - 0.02).abs() < 1e-6);
+
+        // La simulation fait 120 pas par seconde, que les images soient à 30 ou à 240 par seconde
+        let mut fixed_step = FixedStep::new(world::STEP_RATE);
+        let steps: u32 = (0..30).map(|_| fixed_step.advance(1. / 30.)).sum();
+        assert_eq!(steps, 120);
+        let steps: Vec<u32> = (0..4).map(|_| fixed_step.advance(1. / 240.)).collect();
+        assert_eq!(steps, [0, 1, 0, 1]);
+        // Une très longue frame ne rattrape qu'un quart de seconde
+        assert_eq!(fixed_step.advance(5.), 30);
+    }
+
+    /// Teste les outils de débogage : bornes de l'échelle de temps, grille et invincibilité
+    /// d'un vaisseau percuté par un astéroïde.
+    #[test]
+    fn test_debug_tools() {
+        use debug::{DebugTools, MAX_TIME_SCALE, MIN_TIME_SCALE};
+        use events::EventQueue;
+        use world::World;
+
+        let mut tools = DebugTools::new();
+        assert_eq!(tools.get_time_scale(), 1.);
+        tools.set_time_scale(100.);
+        assert_eq!(tools.get_time_scale(), MAX_TIME_SCALE);
+        tools.set_time_scale(0.);
+        assert_eq!(tools.get_time_scale(), MIN_TIME_SCALE);
+
+        let mut world = World::with_arena(vec2(800., 600.));
+        assert_eq!(debug::grid_cell_size(&world), 140.);
+
+        // Un vaisseau au bouclier presque vide survit aux chocs en mode invincible
+        world.god_mode = true;
+        let position = world.ships[0].get_position();
+        world.ships[0].set_shield(0.1);
+        let mut events = EventQueue::new();
+        for _ in 0..3 {
+            world.asteroids.push(Asteroid::new(
+                3,
+                Vec2::ZERO,
+                world.level_size,
+                Some(position),
+            ));
+            world.step(&mut events);
+        }
+        assert_eq!(world.ships.len(), 1);
+        assert_eq!(world.ships[0].get_shield(), vaisseau::MAX_SHIELD);
+        world.god_mode = false;
+        world.ships[0].set_shield(0.1);
+        world.asteroids.push(Asteroid::new(
+            3,
+            Vec2::ZERO,
+            world.level_size,
+            Some(position),
+        ));
+        world.step(&mut events);
+        assert!(world.ships.is_empty());
+    }
+
+    /// Teste les actions du menu pause : liste principale, panneau des réglages et retour, et
+    /// la pause sur une frame anormalement longue.
+    #[test]
+    fn test_pause_menu_actions() {
+        use pause_menu::{PauseAction, PauseMenu};
+
+        let mut menu = PauseMenu::new();
+        assert_eq!(menu.handle(false, false, None), None);
+        assert_eq!(menu.handle(true, false, None), Some(PauseAction::Resume));
+        assert_eq!(
+            menu.handle(false, false, Some(0)),
+            Some(PauseAction::Resume)
+        );
+        assert_eq!(
+            menu.handle(false, false, Some(1)),
+            Some(PauseAction::Restart)
+        );
+        assert_eq!(
+            menu.handle(false, false, Some(3)),
+            Some(PauseAction::SaveAndQuit)
+        );
+        assert_eq!(
+            menu.handle(false, false, Some(4)),
+            Some(PauseAction::QuitToMenu)
+        );
+
+        // Panneau des réglages : la touche de pause ne reprend pas la partie
+        assert_eq!(menu.handle(false, false, Some(2)), None);
+        assert_eq!(menu.handle(true, false, None), None);
+        assert_eq!(
+            menu.handle(false, false, Some(0)),
+            Some(PauseAction::CycleParticles)
+        );
+        assert_eq!(
+            menu.handle(false, false, Some(1)),
+            Some(PauseAction::CycleControlScheme)
+        );
+        assert_eq!(
+            menu.handle(false, false, Some(2)),
+            Some(PauseAction::CycleStickMode)
+        );
+        // Retour par le bouton, puis par Escape
+        assert_eq!(menu.handle(false, false, Some(3)), None);
+        assert_eq!(menu.handle(true, false, None), Some(PauseAction::Resume));
+        menu.handle(false, false, Some(2));
+        assert_eq!(menu.handle(false, true, Some(0)), None);
+        assert_eq!(
+            menu.handle(false, false, Some(1)),
+            Some(PauseAction::Restart)
+        );
+        // Une nouvelle pause revient toujours à la liste principale
+        menu.handle(false, false, Some(2));
+        menu.reset();
+        assert_eq!(menu.handle(true, false, None), Some(PauseAction::Resume));
+
+        assert!(!pause_menu::is_long_frame(1. / 60.));
+        assert!(pause_menu::is_long_frame(0.6));
+    }
+
+    /// Teste la limite du pool de particules et la réutilisation des emplacements des
+    /// particules mortes.
+    #[test]
+    fn test_particle_pool() {
+        use particles::{Emitter, ParticleQuality, ParticleSystem};
+
+        let max = ParticleQuality::Low.max_particles();
+        let death = Emitter::ship_death();
+        let mut particles = ParticleSystem::new(ParticleQuality::Low);
+        particles.emit(&death, Vec2::ZERO, Vec2::X);
+        assert_eq!(particles.get_count(), death.count);
+        particles.emit(&death, Vec2::ZERO, Vec2::X);
+        assert_eq!(particles.get_count(), max);
+        particles.update(0.1);
+        assert_eq!(particles.get_count(), max);
+
+        // Une fois mortes, les particules laissent leur place aux suivantes
+        particles.update(10.);
+        assert_eq!(particles.get_count(), 0);
+        particles.emit(&death, Vec2::ZERO, Vec2::X);
+        assert_eq!(particles.get_count(), death.count);
+        particles.emit(&death, Vec2::ZERO, Vec2::X);
+        assert_eq!(particles.get_count(), max);
+
+        let mut disabled = ParticleSystem::new(ParticleQuality::Off);
+        disabled.emit(&death, Vec2::ZERO, Vec2::X);
+        assert_eq!(disabled.get_count(), 0);
+    }
+
+    /// Teste les commandes de la console exécutées sans fenêtre, par un script : apparition
+    /// d'un astéroïde, bouclier, vague, échelle de temps, graine et sauvegarde, la partie
+    /// marquée comme trichée, ainsi que les erreurs et la complétion.
+    #[test]
+    fn test_console_commands() {
+        use console::{CommandRegistry, ConsoleContext, ConsoleRequest};
+        use debug::DebugTools;
+        use save::SaveGame;
+        use world::World;
+
+        let registry = CommandRegistry::new();
+        let mut world = World::with_arena(vec2(800., 600.));
+        let mut debug = DebugTools::new();
+        let mut context = ConsoleContext {
+            world: &mut world,
+            debug: &mut debug,
+            asteroid_count: 4,
+            asteroid_speed: 1.,
+            requests: Vec::new(),
+            cheated: false,
+        };
+
+        // Seules les commandes qui changent la partie la marquent comme trichée
+        assert!(registry.execute("help", &mut context).is_ok());
+        assert!(registry.execute("save slot0", &mut context).is_ok());
+        assert!(!context.cheated);
+        context.requests.clear();
+
+        let count = context.world.asteroids.len();
+        let results = registry.run_script("spawn asteroid 3 at 100 200 speed 1 0", &mut context);
+        assert!(results[0].is_ok());
+        assert_eq!(context.world.asteroids.len(), count + 1);
+        let asteroid = context.world.asteroids.last().unwrap();
+        assert_eq!(asteroid.get_level(), 3);
+        assert_eq!(asteroid.get_position(), vec2(100., 200.));
+        assert_eq!(asteroid.get_speed(), vec2(1., 0.));
+        assert!(context.cheated);
+
+        let script = "set shield 5; # commentaire\nwave 7\ntimescale 0.25\nseed 1234\nsave slot1";
+        let results = registry.run_script(script, &mut context);
+        assert_eq!(results.len(), 5);
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(context.world.ships[0].get_shield(), 5.);
+        assert_eq!(context.world.wave, 7);
+        assert_eq!(context.world.asteroids.len(), 4 + 12);
+        assert_eq!(context.debug.get_time_scale(), 0.25);
+        assert_eq!(context.world.seed, 1234);
+        assert_eq!(
+            context.requests,
+            vec![ConsoleRequest::Save("slot1".to_string())]
+        );
+        // Recharger un emplacement est une triche : il permet d'annuler une mort
+        context.cheated = false;
+        assert!(registry.execute("load slot1", &mut context).is_ok());
+        assert!(context.cheated);
+
+        // Erreurs : commande inconnue, arguments invalides, emplacement hors du dossier
+        assert!(registry.execute("fly", &mut context).is_err());
+        assert!(registry.execute("spawn asteroid 4", &mut context).is_err());
+        assert!(registry
+            .execute("spawn asteroid 1 at 3", &mut context)
+            .is_err());
+        assert!(registry.execute("wave 0", &mut context).is_err());
+        assert!(SaveGame::load_slot("../x").is_err());
+
+        assert_eq!(registry.complete("sp").0, "spawn ");
+        assert_eq!(registry.complete("spawn ast").0, "spawn asteroid ");
+        let (line, candidates) = registry.complete("s");
+        assert_eq!(line, "s");
+        assert!(candidates.contains(&"spawn") && candidates.contains(&"seed"));
+    }
+
+    /// Teste la lecture du manifeste des ressources : chemins par défaut, remplacement,
+    /// ressource facultative et lignes invalides.
+    #[test]
+    fn test_asset_manifest() {
+        use manifest::{AssetKind, AssetManifest};
+
+        let builtin = AssetManifest::default();
+        assert_eq!(
+            builtin.path(AssetKind::Texture, "vaisseau"),
+            Some("sprite/vaisseau.png")
+        );
+        assert_eq!(builtin.path(AssetKind::Font, "ui"), None);
+
+        let (manifest, warnings) = AssetManifest::parse(
+            "# commentaire\n\
+             texture vaisseau = pack/ship.png\n\
+             font ui = fonts/ui.ttf\n\
+             image fond = fond.png\n\
+             sound sans_chemin\n",
+        );
+        assert_eq!(
+            manifest.path(AssetKind::Texture, "vaisseau"),
+            Some("pack/ship.png")
+        );
+        assert_eq!(manifest.path(AssetKind::Font, "ui"), Some("fonts/ui.ttf"));
+        assert_eq!(
+            manifest.path(AssetKind::Sound, "shoot"),
+            Some("audio/shoot.wav")
         );
+        assert_eq!(warnings.len(), 2);
 
-        assert_eq!(hauteur_vaisseau, (30. * scale_factor));
-        assert_eq!(rayon_missile, 7. * scale_factor);
-        assert_eq!(level_size.0, 40. * scale_factor);
-        assert_eq!(level_size.1, 20. * scale_factor);
-        assert_eq!(level_size.2, 10. * scale_factor);
-        assert_eq!(gravite_dist, 30. * scale_factor);
-        assert_eq!(last_screen_size, (500., 400.))
+        // Le manifeste livré avec le jeu décrit les mêmes ressources que le manifeste par défaut
+        let (shipped, warnings) = AssetManifest::parse(include_str!("../assets.manifest"));
+        assert_eq!(shipped, builtin);
+        assert!(warnings.is_empty());
     }
 }
@@ -0,0 +1,273 @@
+use crate::settings::Settings;
+use macroquad::file::load_string;
+use std::path::{Path, PathBuf};
+
+/// Nom du fichier manifeste, cherché à la racine des ressources.
+pub const MANIFEST_FILE: &str = "assets.manifest";
+
+/// Catégorie d'une ressource du manifeste.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssetKind {
+    /// Image chargée comme texture.
+    Texture,
+    /// Fichier audio.
+    Sound,
+    /// Police TrueType.
+    Font,
+}
+
+impl AssetKind {
+    /// Retourne le mot-clé de la catégorie dans le manifeste.
+    pub fn key(self) -> &'static str {
+        match self {
+            AssetKind::Texture => "texture",
+            AssetKind::Sound => "sound",
+            AssetKind::Font => "font",
+        }
+    }
+
+    /// Retrouve une catégorie à partir de son mot-clé.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Le mot-clé lu dans le manifeste.
+    ///
+    /// # Retour
+    ///
+    /// La catégorie correspondante, ou `None` si le mot-clé est inconnu.
+    pub fn from_key(key: &str) -> Option<Self> {
+        [AssetKind::Texture, AssetKind::Sound, AssetKind::Font]
+            .into_iter()
+            .find(|kind| kind.key() == key)
+    }
+}
+
+/// Ressources du jeu et leurs chemins par défaut, relatifs à la racine des ressources.
+/// Un chemin vide signifie que la ressource est facultative (police par défaut de macroquad,
+/// couches de musique synthétisées).
+const BUILTIN_ENTRIES: [(AssetKind, &str, &str); 15] = [
+    (AssetKind::Texture, "vaisseau", "sprite/vaisseau.png"),
+    (AssetKind::Texture, "asteroid_3", "sprite/asteroid_3.png"),
+    (AssetKind::Texture, "asteroid_2", "sprite/asteroid_2.png"),
+    (AssetKind::Texture, "asteroid_1", "sprite/asteroid_1.png"),
+    (AssetKind::Texture, "background", "sprite/background.png"),
+    (AssetKind::Texture, "missile", "sprite/missile.png"),
+    (AssetKind::Sound, "shoot", "audio/shoot.wav"),
+    (AssetKind::Sound, "asteroid_hit", "audio/asteroid_hit.wav"),
+    (
+        AssetKind::Sound,
+        "background_music",
+        "audio/background_music.wav",
+    ),
+    (AssetKind::Sound, "win", "audio/win.wav"),
+    (AssetKind::Sound, "lose", "audio/lose.wav"),
+    (AssetKind::Sound, "ship_hit", "audio/ship_hit.wav"),
+    (AssetKind::Sound, "music_pulse", ""),
+    (AssetKind::Sound, "music_tension", ""),
+    (AssetKind::Font, "ui", ""),
+];
+
+/// Liste des ressources du jeu : pour chaque ressource, sa catégorie, son identifiant et
+/// son chemin relatif à la racine des ressources.
+///
+/// Le fichier `assets.manifest` contient une ressource par ligne, sous la forme
+/// `catégorie identifiant = chemin` ; il remplace les chemins par défaut ligne par ligne.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetManifest {
+    entries: Vec<(AssetKind, String, String)>,
+}
+
+impl Default for AssetManifest {
+    fn default() -> Self {
+        Self {
+            entries: BUILTIN_ENTRIES
+                .iter()
+                .map(|(kind, id, path)| (*kind, id.to_string(), path.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl AssetManifest {
+    /// Lit un manifeste, en partant des chemins par défaut.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Le contenu du fichier manifeste.
+    ///
+    /// # Retour
+    ///
+    /// Le manifeste, et la liste des lignes ignorées.
+    pub fn parse(text: &str) -> (Self, Vec<String>) {
+        let mut manifest = Self::default();
+        let mut warnings = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parsed = line.split_once('=').and_then(|(name, path)| {
+                let mut words = name.split_whitespace();
+                let kind = AssetKind::from_key(words.next()?)?;
+                let id = words.next()?;
+                words
+                    .next()
+                    .is_none()
+                    .then(|| (kind, id.to_string(), path.trim().to_string()))
+            });
+            match parsed {
+                Some((kind, id, path)) => manifest.set(kind, &id, path),
+                None => warnings.push(format!("ligne {} ignorée : \"{}\"", number + 1, line)),
+            }
+        }
+        (manifest, warnings)
+    }
+
+    /// Remplace le chemin d'une ressource, ou ajoute la ressource si elle est inconnue.
+    fn set(&mut self, kind: AssetKind, id: &str, path: String) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|(entry_kind, entry_id, _)| *entry_kind == kind && entry_id == id)
+        {
+            Some(entry) => entry.2 = path,
+            None => self.entries.push((kind, id.to_string(), path)),
+        }
+    }
+
+    /// Retourne le chemin relatif d'une ressource.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - La catégorie de la ressource.
+    /// * `id` - L'identifiant de la ressource.
+    ///
+    /// # Retour
+    ///
+    /// Le chemin relatif, ou `None` si la ressource est absente du manifeste ou facultative.
+    pub fn path(&self, kind: AssetKind, id: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(entry_kind, entry_id, _)| *entry_kind == kind && entry_id == id)
+            .map(|(_, _, path)| path.as_str())
+            .filter(|path| !path.is_empty())
+    }
+}
+
+/// Emplacement des ressources : racine, pack de ressources facultatif et manifeste.
+///
+/// Un pack de ressources est un dossier qui reprend l'arborescence de la racine ; chaque
+/// fichier présent dans le pack remplace la ressource du même chemin, les autres ressources
+/// sont prises à la racine.
+pub struct AssetPaths {
+    root: PathBuf,
+    pack: Option<PathBuf>,
+    manifest: AssetManifest,
+}
+
+impl AssetPaths {
+    /// Détermine l'emplacement des ressources à partir des préférences.
+    ///
+    /// La racine est `asset_root` s'il est renseigné ; sinon, le premier dossier contenant un
+    /// manifeste ou un dossier `sprite` parmi le dossier de l'exécutable, ses parents (pour
+    /// `cargo run`) et le dossier courant. Le pack `resource_pack` est relatif à la racine
+    /// s'il n'est pas absolu.
+    ///
+    /// Dans la version web, la racine est le dossier de la page : le manifeste et les
+    /// ressources sont téléchargés à côté de `index.html`.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - Les préférences du joueur.
+    ///
+    /// # Retour
+    ///
+    /// Une instance d'`AssetPaths`. Les problèmes du manifeste sont affichés sur la sortie d'erreur.
+    pub async fn resolve(settings: &Settings) -> Self {
+        let root = if settings.asset_root.is_empty() {
+            find_asset_root()
+        } else {
+            PathBuf::from(&settings.asset_root)
+        };
+        let pack = (!settings.resource_pack.is_empty()).then(|| root.join(&settings.resource_pack));
+
+        let manifest_path = root.join(MANIFEST_FILE);
+        let manifest = match load_string(&manifest_path.to_string_lossy()).await {
+            Ok(text) => {
+                let (manifest, warnings) = AssetManifest::parse(&text);
+                for warning in warnings {
+                    eprintln!("{} : {}", MANIFEST_FILE, warning);
+                }
+                manifest
+            }
+            Err(_) => AssetManifest::default(),
+        };
+        Self {
+            root,
+            pack,
+            manifest,
+        }
+    }
+
+    /// Retourne le chemin complet d'une ressource, en privilégiant le pack de ressources.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - La catégorie de la ressource.
+    /// * `id` - L'identifiant de la ressource.
+    ///
+    /// # Retour
+    ///
+    /// Le chemin à charger, ou `None` si la ressource n'a pas de chemin dans le manifeste.
+    pub fn resolve_path(&self, kind: AssetKind, id: &str) -> Option<String> {
+        let relative = self.relative_path(kind, id)?;
+        let path = self
+            .pack_override(kind, id)
+            .unwrap_or_else(|| self.root.join(relative));
+        Some(path.to_string_lossy().into_owned())
+    }
+
+    /// Retourne le chemin d'une ressource relatif à la racine, tel qu'écrit dans le manifeste.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - La catégorie de la ressource.
+    /// * `id` - L'identifiant de la ressource.
+    pub fn relative_path(&self, kind: AssetKind, id: &str) -> Option<&str> {
+        self.manifest.path(kind, id)
+    }
+
+    /// Retourne le fichier du pack de ressources qui remplace une ressource, s'il existe.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - La catégorie de la ressource.
+    /// * `id` - L'identifiant de la ressource.
+    pub fn pack_override(&self, kind: AssetKind, id: &str) -> Option<PathBuf> {
+        let relative = self.relative_path(kind, id)?;
+        self.pack
+            .as_ref()
+            .map(|pack| pack.join(relative))
+            .filter(|path| path.is_file())
+    }
+}
+
+/// Cherche la racine des ressources près de l'exécutable, puis dans le dossier courant.
+fn find_asset_root() -> PathBuf {
+    let is_root = |dir: &Path| dir.join(MANIFEST_FILE).is_file() || dir.join("sprite").is_dir();
+    let exe_dirs = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .map(|dir| {
+            dir.ancestors()
+                .take(4)
+                .map(Path::to_path_buf)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    exe_dirs
+        .into_iter()
+        .find(|dir| is_root(dir))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
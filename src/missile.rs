@@ -5,6 +5,7 @@ use macroquad::prelude::*;
 /// Représente un missile dans le jeu.
 /// Les missiles sont des objets stellaires qui se déplacent dans une direction fixe après leur lancement.
 /// Ils peuvent détecter s'ils sont hors de l'écran ou s'ils ont été impliqués dans une collision.
+#[derive(Clone)]
 pub struct Missile {
     /// Position actuelle du missile dans l'espace de jeu.
     position: Vec2,
@@ -12,6 +13,8 @@ pub struct Missile {
     speed: Vec2,
     /// Indique si le missile a été impliqué dans une collision.
     has_collided: bool,
+    /// Numéro du joueur qui a tiré le missile.
+    owner: usize,
 }
 
 impl Missile {
@@ -30,6 +33,7 @@ impl Missile {
             position,
             speed: Vec2::new(angle.sin() * 1.5, -angle.cos() * 1.5),
             has_collided: false,
+            owner: 0,
         }
     }
 
@@ -58,6 +62,24 @@ impl Missile {
     pub fn get_collided(&self) -> bool {
         self.has_collided
     }
+
+    /// Retourne le numéro du joueur qui a tiré le missile.
+    ///
+    /// # Retour
+    ///
+    /// Le numéro du joueur (à partir de 0).
+    pub fn get_owner(&self) -> usize {
+        self.owner
+    }
+
+    /// Définit le joueur qui a tiré le missile.
+    ///
+    /// # Arguments
+    ///
+    /// * `owner` - Le numéro du joueur (à partir de 0).
+    pub fn set_owner(&mut self, owner: usize) {
+        self.owner = owner;
+    }
 }
 
 impl StellarObject for Missile {
@@ -98,7 +120,7 @@ impl StellarObject for Missile {
     }
 
     /// Met à jour la position du missile en fonction de sa vitesse.
-    fn update_position(&mut self) {
+    fn update_position(&mut self, _: Vec2) {
         self.position += self.speed;
     }
 
@@ -0,0 +1,556 @@
+use crate::asteroid::Asteroid;
+use crate::missile::Missile;
+use crate::players::MAX_PLAYERS;
+use crate::stellarobject::StellarObject;
+use crate::vaisseau::{ShipInput, Vaisseau};
+use crate::world::{World, STEP_RATE};
+use macroquad::prelude::*;
+
+/// Port UDP du serveur par défaut.
+pub const DEFAULT_PORT: u16 = 7777;
+
+/// Nombre de pas de simulation par seconde sur le serveur, le même que pour une partie
+/// locale : les vitesses sont exprimées par pas. Le client envoie une trame d'entrée à
+/// chaque pas.
+pub const TICK_RATE: f64 = STEP_RATE;
+
+/// Version du protocole, vérifiée à la connexion : un client d'une autre version est refusé.
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// Taille maximale d'un datagramme UDP.
+pub const MAX_DATAGRAM: usize = 65_507;
+
+/// État d'un vaisseau dans un instantané.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShipState {
+    /// Numéro du joueur qui pilote le vaisseau.
+    pub player: usize,
+    /// Position du vaisseau.
+    pub position: Vec2,
+    /// Angle de rotation, en radians.
+    pub rotation: f32,
+    /// Vitesse du vaisseau.
+    pub speed: Vec2,
+    /// Points de bouclier restants.
+    pub shield: f32,
+    /// Nombre de pas de simulation d'invincibilité restants.
+    pub invulnerable_steps: u32,
+}
+
+impl ShipState {
+    /// Capture l'état d'un vaisseau.
+    ///
+    /// # Arguments
+    ///
+    /// * `vaisseau` - Le vaisseau à capturer.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `ShipState`.
+    pub fn capture(vaisseau: &Vaisseau) -> Self {
+        Self {
+            player: vaisseau.get_player(),
+            position: vaisseau.get_position(),
+            rotation: vaisseau.get_rotation(),
+            speed: vaisseau.get_speed(),
+            shield: vaisseau.get_shield(),
+            invulnerable_steps: vaisseau.get_invulnerable_steps(),
+        }
+    }
+
+    /// Recrée le vaisseau décrit par cet état.
+    ///
+    /// # Retour
+    ///
+    /// Un `Vaisseau` à la position, la rotation et la vitesse capturées.
+    pub fn to_vaisseau(&self) -> Vaisseau {
+        let mut vaisseau = Vaisseau::new(Some(self.position), Some(0.));
+        vaisseau.set_player(self.player);
+        vaisseau.set_rotation(self.rotation);
+        vaisseau.set_speed(self.speed);
+        vaisseau.set_shield(self.shield);
+        vaisseau.set_invulnerable(self.invulnerable_steps);
+        vaisseau
+    }
+}
+
+/// État de la partie envoyé par le serveur à un client après un pas de simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    /// Numéro du pas de simulation du serveur.
+    pub tick: u64,
+    /// Numéro de la dernière trame d'entrée du client appliquée par le serveur.
+    pub ack: u32,
+    /// Numéro de la vague en cours.
+    pub wave: u32,
+    /// Points marqués par chaque joueur.
+    pub scores: [i32; MAX_PLAYERS],
+    /// Frags de chaque joueur (versus).
+    pub frags: [i32; MAX_PLAYERS],
+    /// Les vaisseaux en jeu.
+    pub ships: Vec<ShipState>,
+    /// Les astéroïdes : niveau, position et vitesse.
+    pub asteroids: Vec<(u8, Vec2, Vec2)>,
+    /// Les missiles : joueur qui a tiré, position et vitesse.
+    pub missiles: Vec<(usize, Vec2, Vec2)>,
+}
+
+impl Snapshot {
+    /// Capture l'état du monde. Les scores, les frags et l'acquittement sont laissés à zéro
+    /// et remplis par le serveur.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - Le monde à capturer.
+    /// * `tick` - Le numéro du pas de simulation.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `Snapshot`.
+    pub fn capture(world: &World, tick: u64) -> Self {
+        Self {
+            tick,
+            ack: 0,
+            wave: world.wave,
+            scores: [0; MAX_PLAYERS],
+            frags: [0; MAX_PLAYERS],
+            ships: world.ships.iter().map(ShipState::capture).collect(),
+            asteroids: world
+                .asteroids
+                .iter()
+                .map(|asteroid| {
+                    (
+                        asteroid.get_level(),
+                        asteroid.get_position(),
+                        asteroid.get_speed(),
+                    )
+                })
+                .collect(),
+            missiles: world
+                .missiles
+                .iter()
+                .map(|missile| {
+                    (
+                        missile.get_owner(),
+                        missile.get_position(),
+                        missile.get_speed(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Remplace les vaisseaux, les astéroïdes et les missiles du monde par ceux de l'instantané.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - Le monde à mettre à jour (celui que le client dessine).
+    pub fn restore(&self, world: &mut World) {
+        world.wave = self.wave;
+        world.ships = self.ships.iter().map(ShipState::to_vaisseau).collect();
+        world.asteroids = self
+            .asteroids
+            .iter()
+            .map(|&(level, position, speed)| {
+                Asteroid::new(level, speed, world.level_size, Some(position))
+            })
+            .collect();
+        world.missiles = self
+            .missiles
+            .iter()
+            .map(|&(owner, position, speed)| {
+                let mut missile = Missile::new(position, 0.);
+                missile.set_speed(speed);
+                missile.set_owner(owner);
+                missile
+            })
+            .collect();
+    }
+
+    /// Calcule un état intermédiaire entre cet instantané et le suivant.
+    ///
+    /// Les vaisseaux sont associés par joueur ; les astéroïdes et les missiles, qui n'ont pas
+    /// d'identifiant, sont associés à l'objet le plus proche de même niveau (ou du même joueur)
+    /// dans l'instantané précédent. Un objet sans correspondant, ou qui vient de traverser un
+    /// bord de l'arène, est placé directement à sa position dans l'instantané suivant.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - L'instantané suivant.
+    /// * `t` - L'avancement entre les deux instantanés (0 : celui-ci, 1 : le suivant).
+    /// * `arena` - La taille de l'arène.
+    ///
+    /// # Retour
+    ///
+    /// L'instantané interpolé, qui reprend les objets de `next`.
+    pub fn interpolate(&self, next: &Snapshot, t: f32, arena: Vec2) -> Snapshot {
+        let t = t.clamp(0., 1.);
+        let mut result = next.clone();
+        for ship in result.ships.iter_mut() {
+            if let Some(previous) = self.ships.iter().find(|old| old.player == ship.player) {
+                ship.position = lerp_wrapped(previous.position, ship.position, t, arena);
+                ship.rotation = previous.rotation + (ship.rotation - previous.rotation) * t;
+            }
+        }
+        for (level, position, _) in result.asteroids.iter_mut() {
+            let candidates = self.asteroids.iter().filter(|old| old.0 == *level);
+            if let Some(previous) = nearest(candidates.map(|old| old.1), *position) {
+                *position = lerp_wrapped(previous, *position, t, arena);
+            }
+        }
+        for (owner, position, _) in result.missiles.iter_mut() {
+            let candidates = self.missiles.iter().filter(|old| old.0 == *owner);
+            if let Some(previous) = nearest(candidates.map(|old| old.1), *position) {
+                *position = lerp_wrapped(previous, *position, t, arena);
+            }
+        }
+        result
+    }
+}
+
+/// Retourne la position la plus proche d'une cible.
+///
+/// # Arguments
+///
+/// * `positions` - Les positions candidates.
+/// * `target` - La position cible.
+///
+/// # Retour
+///
+/// La position la plus proche, ou `None` s'il n'y a aucun candidat.
+fn nearest(positions: impl Iterator<Item = Vec2>, target: Vec2) -> Option<Vec2> {
+    positions.min_by(|a, b| {
+        a.distance_squared(target)
+            .total_cmp(&b.distance_squared(target))
+    })
+}
+
+/// Interpole entre deux positions, sauf si l'objet a traversé un bord de l'arène (il aurait
+/// sinon traversé tout l'écran) : la position d'arrivée est alors retournée telle quelle.
+///
+/// # Arguments
+///
+/// * `from` - La position de départ.
+/// * `to` - La position d'arrivée.
+/// * `t` - L'avancement entre les deux positions.
+/// * `arena` - La taille de l'arène.
+///
+/// # Retour
+///
+/// La position interpolée.
+fn lerp_wrapped(from: Vec2, to: Vec2, t: f32, arena: Vec2) -> Vec2 {
+    let delta = to - from;
+    if delta.x.abs() > arena.x / 2. || delta.y.abs() > arena.y / 2. {
+        to
+    } else {
+        from + delta * t
+    }
+}
+
+/// Message échangé entre un client et le serveur, dans un datagramme UDP.
+///
+/// Les messages sont du texte, au même format que les sauvegardes : une ligne par élément
+/// (`clé valeurs...`), la première ligne donnant le type du message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    /// Client → serveur : demande à rejoindre la partie.
+    Hello {
+        /// Version du protocole du client.
+        version: u32,
+    },
+    /// Serveur → client : la demande est acceptée.
+    Welcome {
+        /// Le joueur attribué au client.
+        player: usize,
+        /// La taille de l'arène du serveur.
+        arena: Vec2,
+    },
+    /// Serveur → client : la demande est refusée (partie pleine, autre version...).
+    Refused {
+        /// La raison du refus.
+        reason: String,
+    },
+    /// Client → serveur : les dernières commandes du joueur, de la plus récente à la plus
+    /// ancienne. Chaque trame répète les précédentes pour résister à la perte de paquets.
+    ///
+    /// Une commande est l'octet de `ShipInput::to_bits`, suivi pour une commande analogique
+    /// de l'accélération, de la rotation et de la direction visée (`-` sans direction),
+    /// séparées par des `/`.
+    Input {
+        /// Numéro de la commande la plus récente.
+        sequence: u32,
+        /// Les commandes, la plus récente en premier.
+        inputs: Vec<ShipInput>,
+    },
+    /// Serveur → client : l'état de la partie.
+    Snapshot(Snapshot),
+    /// Flux des spectateurs : la taille de l'arène, envoyée au début du flux et à chaque
+    /// changement.
+    Arena {
+        /// La taille de l'arène.
+        size: Vec2,
+    },
+    /// Client → serveur : le joueur quitte la partie.
+    Bye,
+}
+
+impl Message {
+    /// Convertit le message en texte.
+    ///
+    /// # Retour
+    ///
+    /// Le contenu du datagramme.
+    pub fn encode(&self) -> String {
+        match self {
+            Message::Hello { version } => format!("hello {}", version),
+            Message::Welcome { player, arena } => {
+                format!("welcome {} {} {}", player, arena.x, arena.y)
+            }
+            Message::Refused { reason } => format!("refused {}", reason),
+            Message::Input { sequence, inputs } => {
+                let inputs: Vec<String> = inputs.iter().map(encode_input).collect();
+                format!("input {} {}", sequence, inputs.join(" "))
+            }
+            Message::Snapshot(snapshot) => {
+                let scores = &snapshot.scores;
+                let frags = &snapshot.frags;
+                let mut lines = vec![
+                    format!(
+                        "snapshot {} {} {}",
+                        snapshot.tick, snapshot.ack, snapshot.wave
+                    ),
+                    format!(
+                        "scores {} {} {} {}",
+                        scores[0], scores[1], scores[2], scores[3]
+                    ),
+                    format!("frags {} {} {} {}", frags[0], frags[1], frags[2], frags[3]),
+                ];
+                for ship in &snapshot.ships {
+                    lines.push(format!(
+                        "ship {} {} {} {} {} {} {} {}",
+                        ship.player,
+                        ship.position.x,
+                        ship.position.y,
+                        ship.rotation,
+                        ship.speed.x,
+                        ship.speed.y,
+                        ship.shield,
+                        ship.invulnerable_steps
+                    ));
+                }
+                for (level, position, speed) in &snapshot.asteroids {
+                    lines.push(format!(
+                        "asteroid {} {} {} {} {}",
+                        level, position.x, position.y, speed.x, speed.y
+                    ));
+                }
+                for (owner, position, speed) in &snapshot.missiles {
+                    lines.push(format!(
+                        "missile {} {} {} {} {}",
+                        owner, position.x, position.y, speed.x, speed.y
+                    ));
+                }
+                lines.join("\n")
+            }
+            Message::Arena { size } => format!("arena {} {}", size.x, size.y),
+            Message::Bye => "bye".to_string(),
+        }
+    }
+
+    /// Lit un message reçu.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Le contenu du datagramme.
+    ///
+    /// # Retour
+    ///
+    /// Le message, ou un message d'erreur indiquant la ligne fautive.
+    pub fn decode(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        let first = lines.next().unwrap_or_default();
+        let mut fields = first.split_whitespace();
+        let kind = fields.next().unwrap_or_default();
+        let values: Vec<&str> = fields.collect();
+        let error = || format!("message invalide : \"{}\"", first);
+
+        match kind {
+            "hello" if values.len() == 1 => Ok(Message::Hello {
+                version: values[0].parse().map_err(|_| error())?,
+            }),
+            "welcome" if values.len() == 3 => {
+                let float = |index: usize| values[index].parse::<f32>().map_err(|_| error());
+                Ok(Message::Welcome {
+                    player: values[0].parse().map_err(|_| error())?,
+                    arena: vec2(float(1)?, float(2)?),
+                })
+            }
+            "refused" => Ok(Message::Refused {
+                reason: values.join(" "),
+            }),
+            "input" if values.len() >= 2 => Ok(Message::Input {
+                sequence: values[0].parse().map_err(|_| error())?,
+                inputs: values[1..]
+                    .iter()
+                    .map(|input| decode_input(input).ok_or_else(error))
+                    .collect::<Result<_, _>>()?,
+            }),
+            "snapshot" if values.len() == 3 => {
+                let mut snapshot = Snapshot {
+                    tick: values[0].parse().map_err(|_| error())?,
+                    ack: values[1].parse().map_err(|_| error())?,
+                    wave: values[2].parse().map_err(|_| error())?,
+                    scores: [0; MAX_PLAYERS],
+                    frags: [0; MAX_PLAYERS],
+                    ships: Vec::new(),
+                    asteroids: Vec::new(),
+                    missiles: Vec::new(),
+                };
+                for line in lines {
+                    parse_snapshot_line(&mut snapshot, line)?;
+                }
+                Ok(Message::Snapshot(snapshot))
+            }
+            "arena" if values.len() == 2 => {
+                let float = |index: usize| values[index].parse::<f32>().map_err(|_| error());
+                Ok(Message::Arena {
+                    size: vec2(float(0)?, float(1)?),
+                })
+            }
+            "bye" => Ok(Message::Bye),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// Lit une ligne d'un instantané (score, vaisseau, astéroïde ou missile).
+///
+/// # Arguments
+///
+/// * `snapshot` - L'instantané à compléter.
+/// * `line` - La ligne à lire.
+///
+/// # Retour
+///
+/// Un message d'erreur si la ligne est invalide.
+fn parse_snapshot_line(snapshot: &mut Snapshot, line: &str) -> Result<(), String> {
+    let mut fields = line.split_whitespace();
+    let Some(key) = fields.next() else {
+        return Ok(());
+    };
+    let values: Vec<&str> = fields.collect();
+    let error = || format!("ligne d'instantané invalide : \"{}\"", line);
+    let expect = |count: usize| {
+        if values.len() == count {
+            Ok(())
+        } else {
+            Err(error())
+        }
+    };
+    let float = |index: usize| values[index].parse::<f32>().map_err(|_| error());
+    let integer = |index: usize| values[index].parse::<i32>().map_err(|_| error());
+    let player = |index: usize| {
+        values[index]
+            .parse::<usize>()
+            .ok()
+            .filter(|&player| player < MAX_PLAYERS)
+            .ok_or_else(error)
+    };
+
+    match key {
+        "scores" | "frags" => {
+            expect(MAX_PLAYERS)?;
+            let mut counts = [0; MAX_PLAYERS];
+            for (index, count) in counts.iter_mut().enumerate() {
+                *count = integer(index)?;
+            }
+            if key == "scores" {
+                snapshot.scores = counts;
+            } else {
+                snapshot.frags = counts;
+            }
+        }
+        "ship" => {
+            expect(8)?;
+            snapshot.ships.push(ShipState {
+                player: player(0)?,
+                position: vec2(float(1)?, float(2)?),
+                rotation: float(3)?,
+                speed: vec2(float(4)?, float(5)?),
+                shield: float(6)?,
+                invulnerable_steps: values[7].parse().map_err(|_| error())?,
+            });
+        }
+        "asteroid" => {
+            expect(5)?;
+            let level = values[0].parse().map_err(|_| error())?;
+            if !(1..=3).contains(&level) {
+                return Err(error());
+            }
+            snapshot.asteroids.push((
+                level,
+                vec2(float(1)?, float(2)?),
+                vec2(float(3)?, float(4)?),
+            ));
+        }
+        "missile" => {
+            expect(5)?;
+            snapshot.missiles.push((
+                player(0)?,
+                vec2(float(1)?, float(2)?),
+                vec2(float(3)?, float(4)?),
+            ));
+        }
+        _ => return Err(error()),
+    }
+    Ok(())
+}
+
+/// Encode une commande dans une trame d'entrée.
+///
+/// # Arguments
+///
+/// * `input` - La commande du joueur.
+///
+/// # Retour
+///
+/// L'octet des commandes tout-ou-rien, suivi des commandes analogiques s'il y en a.
+fn encode_input(input: &ShipInput) -> String {
+    let bits = input.to_bits();
+    if !input.is_analog() {
+        return bits.to_string();
+    }
+    let heading = input
+        .heading
+        .map_or("-".to_string(), |heading| heading.to_string());
+    format!("{}/{}/{}/{}", bits, input.throttle, input.turn, heading)
+}
+
+/// Décode une commande encodée par `encode_input`.
+///
+/// # Arguments
+///
+/// * `text` - La commande lue dans la trame.
+///
+/// # Retour
+///
+/// La commande, ou `None` si elle est illisible.
+fn decode_input(text: &str) -> Option<ShipInput> {
+    let number = |value: &str| {
+        value
+            .parse::<f32>()
+            .ok()
+            .filter(|number| number.is_finite())
+    };
+    let mut values = text.split('/');
+    let mut input = ShipInput::from_bits(values.next()?.parse().ok()?);
+    if let Some(throttle) = values.next() {
+        input.throttle = number(throttle)?.clamp(0., 1.);
+        input.turn = number(values.next()?)?.clamp(-1., 1.);
+        input.heading = match values.next()? {
+            "-" => None,
+            heading => Some(number(heading)?),
+        };
+    }
+    values.next().is_none().then_some(input)
+}
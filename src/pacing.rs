@@ -0,0 +1,317 @@
+use crate::text::draw_ui_text;
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+/// Touche qui affiche ou masque le graphe des images par seconde.
+pub const GRAPH_KEY: KeyCode = KeyCode::F9;
+
+/// Limite d'images par seconde la plus basse acceptée.
+pub const MIN_FPS_CAP: u32 = 20;
+
+/// Limite d'images par seconde la plus haute acceptée.
+pub const MAX_FPS_CAP: u32 = 1000;
+
+/// Nombre de frames gardées pour le graphe.
+const HISTORY_LEN: usize = 240;
+
+/// Temps de retard que la simulation rattrape au plus en une frame, en secondes : au-delà,
+/// après une longue frame, elle ralentit au lieu d'enchaîner des centaines de pas.
+const MAX_CATCH_UP: f64 = 0.25;
+
+/// Marge avant l'échéance pendant laquelle le pacer attend activement plutôt que de dormir,
+/// en secondes : le réveil du système n'est pas assez précis pour une attente plus courte.
+#[cfg(not(target_arch = "wasm32"))]
+const SPIN_MARGIN: f64 = 0.002;
+
+/// Rythme des images du jeu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameLimit {
+    /// Une image par rafraîchissement de l'écran (synchronisation verticale).
+    VSync,
+    /// Autant d'images que possible.
+    Uncapped,
+    /// Au plus ce nombre d'images par seconde.
+    Capped(u32),
+}
+
+impl FrameLimit {
+    /// Retourne l'identifiant du rythme utilisé dans le fichier de préférences :
+    /// `vsync`, `uncapped` ou le nombre d'images par seconde.
+    pub fn key(self) -> String {
+        match self {
+            FrameLimit::VSync => "vsync".to_string(),
+            FrameLimit::Uncapped => "uncapped".to_string(),
+            FrameLimit::Capped(fps) => fps.to_string(),
+        }
+    }
+
+    /// Retrouve un rythme à partir de son identifiant.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - L'identifiant lu dans le fichier de préférences.
+    ///
+    /// # Retour
+    ///
+    /// Le rythme correspondant, ou `None` si l'identifiant est inconnu ou si la limite n'est
+    /// pas comprise entre `MIN_FPS_CAP` et `MAX_FPS_CAP`.
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "vsync" => Some(FrameLimit::VSync),
+            "uncapped" => Some(FrameLimit::Uncapped),
+            _ => key
+                .parse()
+                .ok()
+                .filter(|fps| (MIN_FPS_CAP..=MAX_FPS_CAP).contains(fps))
+                .map(FrameLimit::Capped),
+        }
+    }
+
+    /// Retourne l'intervalle de rafraîchissement demandé au pilote graphique à l'ouverture
+    /// de la fenêtre : 1 pour la synchronisation verticale, 0 sinon.
+    pub fn swap_interval(self) -> i32 {
+        match self {
+            FrameLimit::VSync => 1,
+            FrameLimit::Uncapped | FrameLimit::Capped(_) => 0,
+        }
+    }
+}
+
+/// Cadence des frames et mesure de leur durée.
+///
+/// Avec une limite d'images par seconde, chaque frame a une échéance fixe : le pacer dort
+/// jusqu'à peu avant l'échéance, puis attend activement pour la tenir précisément. Les
+/// échéances se suivent à intervalle régulier, si bien qu'une frame un peu lente est
+/// compensée par la suivante au lieu de décaler toutes les autres.
+///
+/// Dans la version web, le navigateur donne le rythme des frames : le pacer ne fait que
+/// les mesurer.
+pub struct FramePacer {
+    limit: FrameLimit,
+    /// Échéance de la dernière frame, en secondes.
+    deadline: f64,
+    /// Fin de la dernière frame, en secondes.
+    last_frame: Option<f64>,
+    /// Durée des dernières frames, en secondes, de la plus ancienne à la plus récente.
+    history: VecDeque<f32>,
+    graph_visible: bool,
+}
+
+impl FramePacer {
+    /// Crée un pacer.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Le rythme des images.
+    /// * `graph_visible` - Affiche le graphe des images par seconde dès le démarrage.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `FramePacer`.
+    pub fn new(limit: FrameLimit, graph_visible: bool) -> Self {
+        Self {
+            limit,
+            deadline: f64::NEG_INFINITY,
+            last_frame: None,
+            history: VecDeque::with_capacity(HISTORY_LEN),
+            graph_visible,
+        }
+    }
+
+    /// Indique si le graphe des images par seconde est affiché.
+    pub fn is_graph_visible(&self) -> bool {
+        self.graph_visible
+    }
+
+    /// Affiche ou masque le graphe avec la touche `GRAPH_KEY`. À appeler une fois par frame.
+    pub fn update(&mut self) {
+        if is_key_pressed(GRAPH_KEY) {
+            self.graph_visible = !self.graph_visible;
+        }
+    }
+
+    /// Calcule l'échéance de la frame qui se termine.
+    ///
+    /// Une frame en retard de plus d'un intervalle ne rattrape pas son retard : les
+    /// échéances repartent de maintenant.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - L'instant présent, en secondes.
+    ///
+    /// # Retour
+    ///
+    /// L'instant jusqu'auquel attendre, ou `None` s'il ne faut pas attendre (pas de limite,
+    /// ou échéance déjà passée).
+    pub fn next_deadline(&mut self, now: f64) -> Option<f64> {
+        let FrameLimit::Capped(fps) = self.limit else {
+            return None;
+        };
+        let period = 1. / fps as f64;
+        let deadline = self.deadline + period;
+        if now > deadline + period {
+            self.deadline = now;
+            return None;
+        }
+        self.deadline = deadline;
+        (deadline > now).then_some(deadline)
+    }
+
+    /// Enregistre la fin d'une frame pour le graphe.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - La fin de la frame, en secondes.
+    pub fn record(&mut self, now: f64) {
+        if let Some(last) = self.last_frame {
+            if self.history.len() == HISTORY_LEN {
+                self.history.pop_front();
+            }
+            self.history.push_back((now - last) as f32);
+        }
+        self.last_frame = Some(now);
+    }
+
+    /// Retourne la durée moyenne des frames récentes.
+    ///
+    /// # Retour
+    ///
+    /// La durée en secondes, ou `None` si aucune frame n'a encore été mesurée.
+    pub fn get_average_frame_time(&self) -> Option<f32> {
+        if self.history.is_empty() {
+            return None;
+        }
+        Some(self.history.iter().sum::<f32>() / self.history.len() as f32)
+    }
+
+    /// Termine la frame : attend son échéance s'il y a une limite d'images par seconde, puis
+    /// mesure sa durée. À appeler après `next_frame().await`.
+    pub fn end_frame(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(deadline) = self.next_deadline(get_time()) {
+            wait_until(deadline);
+        }
+        self.record(get_time());
+    }
+
+    /// Affiche en haut à droite de l'écran le nombre d'images par seconde et le graphe de la
+    /// durée des dernières frames, s'il est visible.
+    ///
+    /// Les barres sont vertes sous la durée visée (ou sous 60 images par seconde sans limite),
+    /// jaunes jusqu'au double et rouges au-delà ; la ligne grise marque la durée visée.
+    pub fn draw(&self) {
+        if !self.graph_visible {
+            return;
+        }
+        let (width, height) = (HISTORY_LEN as f32, 60.);
+        let x = screen_width() - width - 10.;
+        let y = 40.;
+        draw_rectangle(x, y - 30., width, height + 30., Color::new(0., 0., 0., 0.6));
+
+        let target = match self.limit {
+            FrameLimit::Capped(fps) => 1. / fps as f32,
+            FrameLimit::VSync | FrameLimit::Uncapped => 1. / 60.,
+        };
+        // Toute la hauteur du graphe représente trois fois la durée visée
+        let scale = height / (target * 3.);
+        for (i, &frame_time) in self.history.iter().enumerate() {
+            let bar = (frame_time * scale).min(height);
+            let color = if frame_time <= target * 1.05 {
+                GREEN
+            } else if frame_time <= target * 2. {
+                YELLOW
+            } else {
+                RED
+            };
+            draw_line(
+                x + i as f32,
+                y + height,
+                x + i as f32,
+                y + height - bar,
+                1.,
+                color,
+            );
+        }
+        let target_y = y + height - target * scale;
+        draw_line(x, target_y, x + width, target_y, 1., GRAY);
+
+        let text = match self.get_average_frame_time() {
+            Some(average) => format!(
+                "{:.0} FPS  {:.2} ms  ({})",
+                1. / average,
+                average * 1000.,
+                self.limit.key()
+            ),
+            None => format!("- FPS  ({})", self.limit.key()),
+        };
+        draw_ui_text(&text, x + 5., y - 10., 20., WHITE);
+    }
+}
+
+/// Découpe le temps écoulé en pas de simulation de durée fixe.
+///
+/// La simulation avance ainsi au même rythme quel que soit le nombre d'images par seconde :
+/// une frame longue fait plusieurs pas, une frame courte n'en fait parfois aucun. Le reste
+/// d'une frame est gardé pour la suivante.
+pub struct FixedStep {
+    /// Durée d'un pas, en secondes.
+    step: f64,
+    /// Temps écoulé qui n'a pas encore donné de pas, en secondes.
+    accumulator: f64,
+}
+
+impl FixedStep {
+    /// Crée un découpage en pas.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - Le nombre de pas par seconde.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `FixedStep`.
+    pub fn new(rate: f64) -> Self {
+        Self {
+            step: 1. / rate,
+            accumulator: 0.,
+        }
+    }
+
+    /// Ajoute le temps écoulé pendant une frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed` - La durée de la frame, en secondes ; au plus `MAX_CATCH_UP` de retard
+    ///   est rattrapé.
+    ///
+    /// # Retour
+    ///
+    /// Le nombre de pas de simulation à faire pendant cette frame.
+    pub fn advance(&mut self, elapsed: f64) -> u32 {
+        self.accumulator = (self.accumulator + elapsed).min(MAX_CATCH_UP);
+        // La marge évite qu'une frame d'exactement un pas n'en donne aucun à cause des arrondis
+        let steps = ((self.accumulator + 1e-9) / self.step).floor();
+        self.accumulator = (self.accumulator - steps * self.step).max(0.);
+        steps as u32
+    }
+}
+
+/// Attend jusqu'à un instant : sommeil jusqu'à `SPIN_MARGIN` avant, puis attente active.
+///
+/// # Arguments
+///
+/// * `deadline` - L'instant à atteindre, dans l'horloge de `get_time`.
+#[cfg(not(target_arch = "wasm32"))]
+fn wait_until(deadline: f64) {
+    loop {
+        let remaining = deadline - get_time();
+        if remaining <= 0. {
+            break;
+        }
+        if remaining > SPIN_MARGIN {
+            std::thread::sleep(std::time::Duration::from_secs_f64(remaining - SPIN_MARGIN));
+        } else {
+            std::hint::spin_loop();
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use crate::events::{EventListener, GameEvent};
+use crate::rng::{self, GameRng};
+use ::rand::Rng;
+use macroquad::prelude::*;
+use std::f32::consts::PI;
+
+/// Part de sa vitesse qu'une particule garde toutes les 1/60 s : un léger freinage pour que
+/// les gerbes retombent.
+const DRAG: f32 = 0.98;
+
+/// Niveau de qualité des particules.
+/// Chaque niveau fixe le nombre maximal de particules vivantes en même temps,
+/// ce qui permet de limiter le coût d'affichage sur les machines modestes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticleQuality {
+    /// Aucune particule n'est émise.
+    Off,
+    /// Peu de particules (machines lentes).
+    Low,
+    /// Valeur par défaut.
+    Medium,
+    /// Beaucoup de particules.
+    High,
+}
+
+impl ParticleQuality {
+    /// Retourne le nombre maximal de particules pour ce niveau de qualité.
+    ///
+    /// # Retour
+    ///
+    /// La taille du pool de particules.
+    pub fn max_particles(self) -> usize {
+        match self {
+            ParticleQuality::Off => 0,
+            ParticleQuality::Low => 200,
+            ParticleQuality::Medium => 800,
+            ParticleQuality::High => 2500,
+        }
+    }
+
+    /// Retourne le niveau de qualité suivant (en boucle), utilisé par le bouton de l'écran de configuration.
+    ///
+    /// # Retour
+    ///
+    /// Le niveau de qualité suivant.
+    pub fn next(self) -> Self {
+        match self {
+            ParticleQuality::Off => ParticleQuality::Low,
+            ParticleQuality::Low => ParticleQuality::Medium,
+            ParticleQuality::Medium => ParticleQuality::High,
+            ParticleQuality::High => ParticleQuality::Off,
+        }
+    }
+
+    /// Retourne l'identifiant du niveau utilisé dans le fichier de préférences.
+    ///
+    /// # Retour
+    ///
+    /// Un identifiant court, sans espace.
+    pub fn key(self) -> &'static str {
+        match self {
+            ParticleQuality::Off => "off",
+            ParticleQuality::Low => "low",
+            ParticleQuality::Medium => "medium",
+            ParticleQuality::High => "high",
+        }
+    }
+
+    /// Retrouve un niveau de qualité à partir de son identifiant.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - L'identifiant lu dans le fichier de préférences.
+    ///
+    /// # Retour
+    ///
+    /// Le niveau correspondant, ou `None` si l'identifiant est inconnu.
+    pub fn from_key(key: &str) -> Option<Self> {
+        [
+            ParticleQuality::Off,
+            ParticleQuality::Low,
+            ParticleQuality::Medium,
+            ParticleQuality::High,
+        ]
+        .into_iter()
+        .find(|quality| quality.key() == key)
+    }
+
+    /// Retourne le nom affichable du niveau de qualité.
+    ///
+    /// # Retour
+    ///
+    /// Le nom du niveau en français.
+    pub fn label(self) -> &'static str {
+        match self {
+            ParticleQuality::Off => "Désactivées",
+            ParticleQuality::Low => "Basse",
+            ParticleQuality::Medium => "Moyenne",
+            ParticleQuality::High => "Haute",
+        }
+    }
+}
+
+/// Décrit une gerbe de particules : combien en émettre et avec quelles propriétés.
+///
+/// Les intervalles `(min, max)` sont tirés aléatoirement pour chaque particule.
+#[derive(Clone, Copy, Debug)]
+pub struct Emitter {
+    /// Nombre de particules émises à chaque déclenchement.
+    pub count: usize,
+    /// Durée de vie d'une particule, en secondes.
+    pub lifetime: (f32, f32),
+    /// Vitesse initiale d'une particule, en pixels par seconde.
+    pub speed: (f32, f32),
+    /// Ouverture du cône d'émission autour de la direction donnée (en radians, `2π` = toutes directions).
+    pub spread: f32,
+    /// Couleur à la naissance de la particule.
+    pub start_color: Color,
+    /// Couleur à la mort de la particule (la couleur est interpolée entre les deux).
+    pub end_color: Color,
+    /// Taille au début et à la fin de la vie de la particule.
+    pub size: (f32, f32),
+}
+
+impl Emitter {
+    /// Explosion d'un astéroïde détruit, plus grosse pour les astéroïdes de haut niveau.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - Niveau de l'astéroïde détruit (1, 2 ou 3).
+    pub fn asteroid_destroyed(level: u8) -> Self {
+        Self {
+            count: 12 * level as usize,
+            lifetime: (0.4, 0.9 + 0.2 * level as f32),
+            speed: (30., 60. + 40. * level as f32),
+            spread: 2. * PI,
+            start_color: Color::new(0.85, 0.75, 0.6, 1.),
+            end_color: Color::new(0.4, 0.3, 0.25, 0.),
+            size: (1.5 + level as f32, 0.5),
+        }
+    }
+
+    /// Étincelles lorsque le vaisseau est touché par un astéroïde.
+    pub fn ship_hit() -> Self {
+        Self {
+            count: 20,
+            lifetime: (0.2, 0.5),
+            speed: (80., 180.),
+            spread: 2. * PI,
+            start_color: Color::new(0.6, 0.9, 1., 1.),
+            end_color: Color::new(0.1, 0.3, 1., 0.),
+            size: (2.5, 0.5),
+        }
+    }
+
+    /// Petit éclat à l'endroit où un missile touche un astéroïde.
+    pub fn missile_impact() -> Self {
+        Self {
+            count: 8,
+            lifetime: (0.1, 0.3),
+            speed: (40., 120.),
+            spread: PI / 2.,
+            start_color: Color::new(1., 1., 0.7, 1.),
+            end_color: Color::new(1., 0.5, 0., 0.),
+            size: (2., 0.5),
+        }
+    }
+
+    /// Flamme du réacteur, émise vers l'arrière du vaisseau tant qu'il accélère.
+    pub fn thrust() -> Self {
+        Self {
+            count: 2,
+            lifetime: (0.15, 0.35),
+            speed: (60., 120.),
+            spread: PI / 6.,
+            start_color: Color::new(1., 0.85, 0.3, 1.),
+            end_color: Color::new(1., 0.2, 0., 0.),
+            size: (3., 0.5),
+        }
+    }
+
+    /// Grande explosion lorsque le bouclier du vaisseau est épuisé.
+    pub fn ship_death() -> Self {
+        Self {
+            count: 120,
+            lifetime: (0.6, 1.8),
+            speed: (40., 260.),
+            spread: 2. * PI,
+            start_color: Color::new(1., 0.9, 0.5, 1.),
+            end_color: Color::new(0.8, 0.1, 0., 0.),
+            size: (4., 1.),
+        }
+    }
+}
+
+/// Une particule du pool. Une particule morte (`age >= lifetime`) peut être réutilisée.
+#[derive(Clone, Copy)]
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    start_color: Color,
+    end_color: Color,
+    start_size: f32,
+    end_size: f32,
+}
+
+impl Particle {
+    fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+}
+
+/// Système de particules à pool fixe.
+///
+/// Les particules sont stockées dans un vecteur de taille bornée par la qualité choisie ;
+/// les emplacements des particules mortes sont recyclés au lieu de réallouer de la mémoire.
+/// Lorsque le pool est plein, les nouvelles particules sont simplement ignorées.
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    free: Vec<usize>,
+    quality: ParticleQuality,
+    /// Générateur des gerbes, distinct de celui de la partie : les effets visuels ne changent
+    /// pas les tirages de la simulation.
+    rng: GameRng,
+}
+
+impl ParticleSystem {
+    /// Crée un système de particules vide pour un niveau de qualité donné.
+    ///
+    /// # Arguments
+    ///
+    /// * `quality` - Le niveau de qualité, qui fixe la taille du pool.
+    pub fn new(quality: ParticleQuality) -> Self {
+        Self {
+            particles: Vec::with_capacity(quality.max_particles()),
+            free: Vec::new(),
+            quality,
+            rng: GameRng::new(rng::random_seed()),
+        }
+    }
+
+    /// Change le niveau de qualité. Les particules en cours sont supprimées.
+    ///
+    /// # Arguments
+    ///
+    /// * `quality` - Le nouveau niveau de qualité.
+    pub fn set_quality(&mut self, quality: ParticleQuality) {
+        if quality != self.quality {
+            *self = Self::new(quality);
+        }
+    }
+
+    /// Retourne le nombre de particules vivantes.
+    pub fn get_count(&self) -> usize {
+        self.particles.iter().filter(|p| p.is_alive()).count()
+    }
+
+    /// Supprime toutes les particules.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.free.clear();
+    }
+
+    /// Émet une gerbe de particules.
+    ///
+    /// # Arguments
+    ///
+    /// * `emitter` - La description de la gerbe.
+    /// * `position` - Le point d'émission.
+    /// * `direction` - La direction centrale du cône d'émission (ignorée si `spread` vaut `2π`).
+    pub fn emit(&mut self, emitter: &Emitter, position: Vec2, direction: Vec2) {
+        let rng = &mut self.rng;
+        let base_angle = direction.y.atan2(direction.x);
+
+        for _ in 0..emitter.count {
+            let angle = base_angle + rng.gen_range(-0.5..=0.5) * emitter.spread;
+            let speed = rng.gen_range(emitter.speed.0..=emitter.speed.1);
+            let particle = Particle {
+                position,
+                velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+                age: 0.,
+                lifetime: rng.gen_range(emitter.lifetime.0..=emitter.lifetime.1),
+                start_color: emitter.start_color,
+                end_color: emitter.end_color,
+                start_size: emitter.size.0,
+                end_size: emitter.size.1,
+            };
+
+            if let Some(index) = self.free.pop() {
+                self.particles[index] = particle;
+            } else if self.particles.len() < self.quality.max_particles() {
+                self.particles.push(particle);
+            } else {
+                // Pool plein : on n'émet pas plus de particules.
+                return;
+            }
+        }
+    }
+
+    /// Fait vieillir et avancer toutes les particules vivantes.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - Le temps écoulé depuis la dernière mise à jour, en secondes.
+    pub fn update(&mut self, dt: f32) {
+        for (index, particle) in self.particles.iter_mut().enumerate() {
+            if !particle.is_alive() {
+                continue;
+            }
+            particle.age += dt;
+            particle.position += particle.velocity * dt;
+            // Freinage rapporté au temps écoulé, identique quel que soit le rythme des images
+            particle.velocity *= DRAG.powf(dt * 60.);
+            if !particle.is_alive() {
+                self.free.push(index);
+            }
+        }
+    }
+
+    /// Dessine toutes les particules vivantes.
+    ///
+    /// La couleur est interpolée linéairement entre la couleur de départ et celle d'arrivée,
+    /// la taille suit une courbe quadratique (rétrécit vite en fin de vie).
+    pub fn draw(&self) {
+        for particle in self.particles.iter().filter(|p| p.is_alive()) {
+            let t = (particle.age / particle.lifetime).clamp(0., 1.);
+            let color = Color::new(
+                particle.start_color.r + (particle.end_color.r - particle.start_color.r) * t,
+                particle.start_color.g + (particle.end_color.g - particle.start_color.g) * t,
+                particle.start_color.b + (particle.end_color.b - particle.start_color.b) * t,
+                particle.start_color.a + (particle.end_color.a - particle.start_color.a) * t,
+            );
+            let size = particle.start_size + (particle.end_size - particle.start_size) * (t * t);
+            draw_circle(particle.position.x, particle.position.y, size, color);
+        }
+    }
+}
+
+impl EventListener for ParticleSystem {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::AsteroidDestroyed {
+                level, position, ..
+            } => self.emit(&Emitter::asteroid_destroyed(level), position, Vec2::ZERO),
+            GameEvent::MissileImpact {
+                position,
+                direction,
+            } => self.emit(&Emitter::missile_impact(), position, -direction),
+            GameEvent::ShipHit { position, .. } => {
+                self.emit(&Emitter::ship_hit(), position, Vec2::ZERO)
+            }
+            GameEvent::ShipThrust {
+                position,
+                direction,
+            } => self.emit(&Emitter::thrust(), position, direction),
+            GameEvent::ShipDestroyed { position, .. } => {
+                self.emit(&Emitter::ship_death(), position, Vec2::ZERO)
+            }
+            _ => {}
+        }
+    }
+}
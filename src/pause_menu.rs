@@ -0,0 +1,237 @@
+use crate::controls::{ControlScheme, StickMode};
+use crate::particles::ParticleQuality;
+use crate::text::{draw_ui_text, measure_ui_text};
+use macroquad::prelude::*;
+
+/// Durée (en secondes) au-delà de laquelle une frame est considérée comme anormalement longue
+/// et met la partie en pause.
+const LONG_FRAME_TIME: f32 = 0.5;
+
+/// Action choisie par le joueur dans le menu pause.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseAction {
+    /// Reprendre la partie en cours.
+    Resume,
+    /// Recommencer une partie avec les mêmes paramètres.
+    Restart,
+    /// Passer à la qualité de particules suivante (depuis le panneau des réglages).
+    CycleParticles,
+    /// Passer au schéma de contrôle suivant (depuis le panneau des réglages).
+    CycleControlScheme,
+    /// Passer au mode de stick suivant (depuis le panneau des réglages).
+    CycleStickMode,
+    /// Sauvegarder la partie puis revenir à l'écran de configuration.
+    SaveAndQuit,
+    /// Abandonner la partie et revenir à l'écran de configuration.
+    QuitToMenu,
+}
+
+/// Menu affiché par-dessus la partie figée lorsque le jeu est en pause.
+///
+/// Le menu possède deux vues : la liste principale (Reprendre / Recommencer / Réglages /
+/// Sauvegarder / Quitter) et un panneau de réglages accessible sans quitter la partie.
+pub struct PauseMenu {
+    button_size: Vec2,   // Taille des boutons
+    show_settings: bool, // Vrai si le panneau des réglages est affiché
+}
+
+impl PauseMenu {
+    /// Crée un menu pause affichant la liste principale.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `PauseMenu`.
+    pub fn new() -> Self {
+        PauseMenu {
+            button_size: Vec2::new(260.0, 50.0),
+            show_settings: false,
+        }
+    }
+
+    /// Revient à la liste principale (appelé à chaque mise en pause).
+    pub fn reset(&mut self) {
+        self.show_settings = false;
+    }
+
+    /// Retourne les libellés des boutons de la vue actuelle.
+    fn labels(
+        &self,
+        quality: ParticleQuality,
+        controls: (ControlScheme, StickMode),
+    ) -> Vec<String> {
+        if self.show_settings {
+            vec![
+                format!("Particules : {}", quality.label()),
+                format!("Contrôles : {}", controls.0.label()),
+                format!("Stick : {}", controls.1.label()),
+                "Retour".to_string(),
+            ]
+        } else {
+            vec![
+                "Reprendre".to_string(),
+                "Recommencer".to_string(),
+                "Réglages".to_string(),
+                "Sauvegarder et quitter".to_string(),
+                "Quitter vers le menu".to_string(),
+            ]
+        }
+    }
+
+    /// Retourne la position du coin supérieur gauche du bouton d'indice `index`.
+    fn button_position(&self, index: usize) -> Vec2 {
+        Vec2::new(
+            screen_width() * 0.5 - self.button_size.x / 2.0,
+            screen_height() * 0.35 + index as f32 * (self.button_size.y + 10.0),
+        )
+    }
+
+    /// Retourne l'indice du bouton cliqué pendant cette frame, s'il y en a un.
+    fn clicked_button(&self, count: usize) -> Option<usize> {
+        if !is_mouse_button_pressed(MouseButton::Left) {
+            return None;
+        }
+        let mouse = mouse_position();
+        (0..count).find(|&index| {
+            let position = self.button_position(index);
+            mouse.0 > position.x
+                && mouse.0 < position.x + self.button_size.x
+                && mouse.1 > position.y
+                && mouse.1 < position.y + self.button_size.y
+        })
+    }
+
+    /// Met à jour le menu en fonction des clics et du clavier.
+    ///
+    /// # Arguments
+    ///
+    /// * `pause_pressed` - `true` si le joueur a appuyé sur sa touche ou son bouton de pause.
+    ///
+    /// # Retour
+    ///
+    /// L'action choisie par le joueur pendant cette frame, s'il y en a une.
+    pub fn update(&mut self, pause_pressed: bool) -> Option<PauseAction> {
+        let count = if self.show_settings { 4 } else { 5 };
+        let clicked = self.clicked_button(count);
+        self.handle(pause_pressed, is_key_pressed(KeyCode::Escape), clicked)
+    }
+
+    /// Choisit l'action correspondant aux commandes de la frame, sans lire le clavier ni la
+    /// souris.
+    ///
+    /// La touche de pause reprend la partie depuis la liste principale, et `Escape` revient
+    /// à la liste principale depuis le panneau des réglages.
+    ///
+    /// # Arguments
+    ///
+    /// * `pause_pressed` - `true` si le joueur a appuyé sur sa touche ou son bouton de pause.
+    /// * `escape_pressed` - `true` si la touche `Escape` a été enfoncée.
+    /// * `clicked` - L'indice du bouton cliqué dans la vue actuelle, s'il y en a un.
+    ///
+    /// # Retour
+    ///
+    /// L'action choisie, s'il y en a une.
+    pub fn handle(
+        &mut self,
+        pause_pressed: bool,
+        escape_pressed: bool,
+        clicked: Option<usize>,
+    ) -> Option<PauseAction> {
+        if self.show_settings {
+            if escape_pressed {
+                self.show_settings = false;
+                return None;
+            }
+            match clicked {
+                Some(0) => Some(PauseAction::CycleParticles),
+                Some(1) => Some(PauseAction::CycleControlScheme),
+                Some(2) => Some(PauseAction::CycleStickMode),
+                Some(_) => {
+                    self.show_settings = false;
+                    None
+                }
+                None => None,
+            }
+        } else {
+            if pause_pressed {
+                return Some(PauseAction::Resume);
+            }
+            match clicked {
+                Some(0) => Some(PauseAction::Resume),
+                Some(1) => Some(PauseAction::Restart),
+                Some(2) => {
+                    self.show_settings = true;
+                    None
+                }
+                Some(3) => Some(PauseAction::SaveAndQuit),
+                Some(_) => Some(PauseAction::QuitToMenu),
+                None => None,
+            }
+        }
+    }
+
+    /// Dessine le voile sombre et les boutons du menu par-dessus la partie figée.
+    ///
+    /// # Arguments
+    ///
+    /// * `quality` - La qualité de particules actuelle, affichée dans le panneau des réglages.
+    /// * `controls` - Le schéma de contrôle et le mode de stick actuels, affichés dans le
+    ///   panneau des réglages.
+    pub fn draw(&self, quality: ParticleQuality, controls: (ControlScheme, StickMode)) {
+        draw_rectangle(
+            0.0,
+            0.0,
+            screen_width(),
+            screen_height(),
+            Color::new(0.0, 0.0, 0.0, 0.6), // Voile semi-transparent
+        );
+
+        let title = if self.show_settings {
+            "Réglages"
+        } else {
+            "Pause"
+        };
+        let title_width = measure_ui_text(title, 50).width;
+        draw_ui_text(
+            title,
+            screen_width() * 0.5 - title_width / 2.0,
+            screen_height() * 0.25,
+            50.0,
+            WHITE,
+        );
+
+        for (index, label) in self.labels(quality, controls).iter().enumerate() {
+            let position = self.button_position(index);
+            draw_rectangle(
+                position.x,
+                position.y,
+                self.button_size.x,
+                self.button_size.y,
+                GRAY,
+            );
+            draw_ui_text(label, position.x + 15.0, position.y + 32.0, 25.0, WHITE);
+        }
+    }
+}
+
+/// Indique si la dernière frame a duré anormalement longtemps, ce qui met la partie en pause.
+///
+/// C'est une heuristique, pas une détection du focus : macroquad ne transmet pas les
+/// événements de focus ni de minimisation de la fenêtre. Une fenêtre dont le rendu a été
+/// suspendu (déplacée, masquée ou minimisée sur certains systèmes) produit une longue frame
+/// au retour. Mais un simple ralentissement (chargement, machine très lente) met aussi la
+/// partie en pause, et une perte de focus qui n'interrompt pas le rendu, ou qui dure moins
+/// de `LONG_FRAME_TIME`, n'est pas détectée.
+///
+/// La musique n'est pas figée pendant la pause : macroquad ne sait pas suspendre un son, si
+/// bien qu'elle s'arrête en fondu et reprend du début.
+///
+/// # Arguments
+///
+/// * `frame_time` - La durée de la dernière frame, en secondes.
+///
+/// # Retour
+///
+/// `true` si la frame a duré plus de `LONG_FRAME_TIME`.
+pub fn is_long_frame(frame_time: f32) -> bool {
+    frame_time > LONG_FRAME_TIME
+}
@@ -0,0 +1,124 @@
+use crate::world::STEP_RATE;
+use macroquad::prelude::*;
+
+/// Nombre maximal de vaisseaux dans l'arène.
+pub const MAX_PLAYERS: usize = 4;
+
+/// Teinte appliquée au sprite et aux missiles de chaque joueur (le joueur 1 garde les
+/// couleurs d'origine du sprite).
+pub const PLAYER_COLORS: [Color; MAX_PLAYERS] = [WHITE, ORANGE, LIME, PINK];
+
+/// Vies supplémentaires de chaque joueur en coopération (3 vies au total).
+pub const COOP_EXTRA_LIVES: u32 = 2;
+
+/// Nombre de pas de simulation d'invincibilité d'un vaisseau qui réapparaît (2 secondes).
+pub const RESPAWN_INVULNERABILITY: u32 = 2 * STEP_RATE as u32;
+
+/// Retourne la position d'apparition d'un vaisseau : les vaisseaux sont répartis sur une
+/// ligne horizontale au milieu de l'écran (un vaisseau seul apparaît au centre).
+///
+/// # Arguments
+///
+/// * `player` - Le numéro du joueur (à partir de 0).
+/// * `players` - Le nombre de joueurs de la partie.
+/// * `arena` - La taille de l'arène.
+///
+/// # Retour
+///
+/// La position d'apparition.
+pub fn spawn_position(player: usize, players: usize, arena: Vec2) -> Vec2 {
+    vec2(
+        arena.x * (player + 1) as f32 / (players + 1) as f32,
+        arena.y / 2.,
+    )
+}
+
+/// Vies restantes des joueurs : chaque vie permet à un vaisseau détruit de réapparaître.
+///
+/// Les vies sont soit propres à chaque joueur, soit mises en commun : dans ce cas, n'importe
+/// quel joueur peut utiliser une vie de la réserve de l'équipe.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lives {
+    /// Vies mises en commun.
+    shared: bool,
+    /// Vies restantes de chaque joueur, ou de l'équipe (index 0) si elles sont communes.
+    counts: [u32; MAX_PLAYERS],
+}
+
+impl Lives {
+    /// Crée les vies d'une nouvelle partie.
+    ///
+    /// # Arguments
+    ///
+    /// * `players` - Le nombre de joueurs.
+    /// * `per_player` - Le nombre de vies supplémentaires de chaque joueur.
+    /// * `shared` - `true` pour mettre toutes les vies en commun.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `Lives`.
+    pub fn new(players: usize, per_player: u32, shared: bool) -> Self {
+        let players = players.min(MAX_PLAYERS);
+        let mut counts = [0; MAX_PLAYERS];
+        if shared {
+            counts[0] = per_player * players as u32;
+        } else {
+            counts[..players].fill(per_player);
+        }
+        Self { shared, counts }
+    }
+
+    /// Recrée des vies à partir de valeurs sauvegardées.
+    ///
+    /// # Arguments
+    ///
+    /// * `shared` - `true` si les vies sont communes.
+    /// * `counts` - Les vies restantes (index 0 = équipe si elles sont communes).
+    pub fn from_counts(shared: bool, counts: [u32; MAX_PLAYERS]) -> Self {
+        Self { shared, counts }
+    }
+
+    /// Indique si les vies sont mises en commun.
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
+    /// Retourne les vies restantes, telles qu'elles sont sauvegardées.
+    pub fn get_counts(&self) -> [u32; MAX_PLAYERS] {
+        self.counts
+    }
+
+    /// Retourne les vies restantes d'un joueur.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur.
+    ///
+    /// # Retour
+    ///
+    /// Les vies du joueur, ou celles de l'équipe si elles sont communes.
+    pub fn get(&self, player: usize) -> u32 {
+        let index = if self.shared { 0 } else { player };
+        self.counts.get(index).copied().unwrap_or(0)
+    }
+
+    /// Utilise une vie pour faire réapparaître le vaisseau d'un joueur.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur dont le vaisseau a été détruit.
+    ///
+    /// # Retour
+    ///
+    /// `true` si une vie a été utilisée, `false` s'il n'en restait plus.
+    pub fn try_respawn(&mut self, player: usize) -> bool {
+        let index = if self.shared { 0 } else { player };
+        match self.counts.get_mut(index) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
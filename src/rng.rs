@@ -0,0 +1,90 @@
+use ::rand::{Error, RngCore};
+#[cfg(target_arch = "wasm32")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Générateur aléatoire de la partie (algorithme SplitMix64).
+///
+/// Contrairement aux générateurs de `rand`, son état tient dans un seul entier que l'on peut
+/// lire et restaurer, ce qui permet de reprendre une partie sauvegardée exactement là où
+/// elle s'était arrêtée : les tirages suivants sont identiques.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    /// Crée un générateur à partir d'une graine.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - La graine de la partie.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `GameRng`.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Retourne l'état interne du générateur, pour la sauvegarde.
+    pub fn get_state(&self) -> u64 {
+        self.state
+    }
+
+    /// Restaure un état interne lu dans une sauvegarde.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - L'état retourné par `get_state`.
+    pub fn set_state(&mut self, state: u64) {
+        self.state = state;
+    }
+}
+
+/// Retourne une graine imprévisible, pour une nouvelle partie ou des effets visuels.
+///
+/// Le navigateur n'offre pas la source d'entropie de `rand` sans wasm-bindgen, que le
+/// chargeur de macroquad ne fournit pas : la version web mélange l'heure à un compteur.
+///
+/// # Retour
+///
+/// Une graine différente à chaque appel.
+pub fn random_seed() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        ::rand::random()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        static CALLS: AtomicU64 = AtomicU64::new(0);
+        let time = (macroquad::miniquad::date::now() * 1e6) as u64;
+        let calls = CALLS.fetch_add(1, Ordering::Relaxed);
+        GameRng::new(time ^ calls.rotate_left(32)).next_u64()
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
@@ -0,0 +1,587 @@
+use crate::asteroid::Asteroid;
+use crate::missile::Missile;
+use crate::players::{Lives, MAX_PLAYERS};
+use crate::stats::GameStats;
+use crate::stellarobject::StellarObject;
+use crate::storage;
+use crate::vaisseau::Vaisseau;
+use crate::versus::{PendingRespawn, Scoreboard, VersusRules, RESPAWN_DELAY};
+use crate::world::World;
+use macroquad::prelude::*;
+use std::io;
+
+/// Nom du fichier de sauvegarde dans le répertoire de données.
+const FILE_NAME: &str = "savegame.txt";
+
+/// Retourne le nom du fichier d'un emplacement de sauvegarde.
+///
+/// # Arguments
+///
+/// * `slot` - Le nom de l'emplacement : lettres, chiffres, `-` et `_` uniquement.
+///
+/// # Retour
+///
+/// Le nom du fichier dans le répertoire de données, ou un message d'erreur si le nom de
+/// l'emplacement est vide ou contient d'autres caractères.
+fn slot_file_name(slot: &str) -> Result<String, String> {
+    let valid = !slot.is_empty()
+        && slot
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("nom d'emplacement invalide : \"{}\"", slot));
+    }
+    Ok(format!("savegame-{}.txt", slot))
+}
+
+/// Version du format de sauvegarde. À incrémenter à chaque changement de format :
+/// une sauvegarde d'une autre version est refusée plutôt que mal relue.
+const VERSION: u32 = 6;
+
+/// Première ligne du fichier, suivie du numéro de version.
+const HEADER: &str = "asteroid-save";
+
+/// État d'un vaisseau dans une sauvegarde.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SavedShip {
+    /// Numéro du joueur qui pilote le vaisseau.
+    pub player: usize,
+    /// Position du vaisseau.
+    pub position: Vec2,
+    /// Angle de rotation, en radians.
+    pub rotation: f32,
+    /// Vitesse du vaisseau.
+    pub speed: Vec2,
+    /// Points de bouclier restants.
+    pub shield: f32,
+    /// Temps de simulation écoulé depuis le dernier tir, en secondes.
+    pub last_shot_age: f64,
+}
+
+/// Partie en cours sauvegardée.
+///
+/// Les instants (dernier tir, début de la partie et de la vague) sont enregistrés sous forme
+/// de durées écoulées, puisque l'horloge de macroquad repart de zéro à chaque lancement.
+/// Les nombres à virgule sont écrits avec toutes leurs décimales significatives, de sorte
+/// que la partie relue est identique à la partie sauvegardée.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaveGame {
+    /// Graine de la partie.
+    pub seed: u64,
+    /// État du générateur aléatoire au moment de la sauvegarde.
+    pub rng_state: u64,
+    /// Numéro de la vague en cours.
+    pub wave: u32,
+    /// Score total au moment de la sauvegarde.
+    pub score: i32,
+    /// Points marqués par chaque joueur (le reste du score total vient des bonus communs).
+    pub player_scores: [i32; MAX_PLAYERS],
+    /// Nombre de joueurs de la partie.
+    pub players: usize,
+    /// Vies restantes des joueurs.
+    pub lives: Lives,
+    /// Tir ami activé pour cette partie.
+    pub friendly_fire: bool,
+    /// Règles du match si la partie est un versus.
+    pub versus: Option<VersusRules>,
+    /// Temps de jeu écoulé depuis le début du match en versus, sans les pauses, en secondes.
+    pub match_elapsed: f64,
+    /// Frags et morts de chaque joueur.
+    pub scoreboard: Scoreboard,
+    /// Temps écoulé depuis le début de la partie, en secondes.
+    pub elapsed: f64,
+    /// Temps écoulé depuis le début de la vague, en secondes.
+    pub wave_elapsed: f64,
+    /// Nombre d'astéroïdes de la première vague choisi pour cette partie.
+    pub asteroid_count: i32,
+    /// Vitesse des astéroïdes choisie pour cette partie.
+    pub asteroid_speed: f32,
+    /// Nombre de vagues à terminer pour gagner cette partie.
+    pub waves: u32,
+    /// La partie a utilisé la triche : son score n'entre pas au tableau des meilleurs scores.
+    pub cheated: bool,
+    /// Statistiques de la partie.
+    pub stats: GameStats,
+    /// Les vaisseaux encore en jeu.
+    pub ships: Vec<SavedShip>,
+    /// Les astéroïdes : niveau, position et vitesse.
+    pub asteroids: Vec<(u8, Vec2, Vec2)>,
+    /// Les missiles : joueur qui les a tirés, position et vitesse.
+    pub missiles: Vec<(usize, Vec2, Vec2)>,
+}
+
+impl SaveGame {
+    /// Capture l'état du monde. Les informations propres à la partie (scores, temps écoulés,
+    /// réglages, statistiques) sont laissées à zéro et remplies par l'appelant.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - Le monde à sauvegarder.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `SaveGame`.
+    pub fn capture(world: &World) -> Self {
+        Self {
+            seed: world.seed,
+            rng_state: world.rng.get_state(),
+            wave: world.wave,
+            score: 0,
+            player_scores: [0; MAX_PLAYERS],
+            players: world.players,
+            lives: world.lives.clone(),
+            friendly_fire: world.friendly_fire,
+            versus: None,
+            match_elapsed: 0.,
+            scoreboard: Scoreboard::new(),
+            elapsed: 0.,
+            wave_elapsed: 0.,
+            asteroid_count: 0,
+            asteroid_speed: 0.,
+            waves: 1,
+            cheated: false,
+            stats: GameStats::new(),
+            ships: world
+                .ships
+                .iter()
+                .map(|vaisseau| SavedShip {
+                    player: vaisseau.get_player(),
+                    position: vaisseau.get_position(),
+                    rotation: vaisseau.get_rotation(),
+                    speed: vaisseau.get_speed(),
+                    shield: vaisseau.get_shield(),
+                    last_shot_age: world.time - vaisseau.get_last_shot(),
+                })
+                .collect(),
+            asteroids: world
+                .asteroids
+                .iter()
+                .map(|asteroid| {
+                    (
+                        asteroid.get_level(),
+                        asteroid.get_position(),
+                        asteroid.get_speed(),
+                    )
+                })
+                .collect(),
+            missiles: world
+                .missiles
+                .iter()
+                .map(|missile| {
+                    (
+                        missile.get_owner(),
+                        missile.get_position(),
+                        missile.get_speed(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Remplace le contenu du monde par celui de la sauvegarde. Les touches des vaisseaux
+    /// restent à leur valeur par défaut et sont attribuées par l'appelant.
+    ///
+    /// En versus, les joueurs dont le vaisseau attendait de réapparaître réapparaissent après
+    /// un délai complet.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - Le monde à restaurer.
+    pub fn restore(&self, world: &mut World) {
+        world.reseed(self.seed);
+        world.rng.set_state(self.rng_state);
+        world.wave = self.wave;
+        world.players = self.players;
+        world.lives = self.lives.clone();
+        world.friendly_fire = self.friendly_fire;
+        world.versus = self.versus.is_some();
+
+        world.ships = self
+            .ships
+            .iter()
+            .map(|ship| {
+                let mut vaisseau =
+                    Vaisseau::new(Some(ship.position), Some(world.time - ship.last_shot_age));
+                vaisseau.set_player(ship.player);
+                vaisseau.set_rotation(ship.rotation);
+                vaisseau.set_speed(ship.speed);
+                vaisseau.set_shield(ship.shield);
+                vaisseau
+            })
+            .collect();
+        world.respawns.clear();
+        if world.versus {
+            for player in 0..self.players {
+                if !self.ships.iter().any(|ship| ship.player == player) {
+                    world.respawns.push(PendingRespawn {
+                        player,
+                        keys: Default::default(),
+                        steps: RESPAWN_DELAY,
+                    });
+                }
+            }
+        }
+
+        world.asteroids = self
+            .asteroids
+            .iter()
+            .map(|&(level, position, speed)| {
+                Asteroid::new(level, speed, world.level_size, Some(position))
+            })
+            .collect();
+        world.missiles = self
+            .missiles
+            .iter()
+            .map(|&(owner, position, speed)| {
+                let mut missile = Missile::new(position, 0.);
+                missile.set_owner(owner);
+                missile.set_speed(speed);
+                missile
+            })
+            .collect();
+    }
+
+    /// Indique si une sauvegarde existe dans le répertoire de données.
+    pub fn exists() -> bool {
+        storage::read_data_file(FILE_NAME).is_some()
+    }
+
+    /// Charge la sauvegarde du répertoire de données.
+    ///
+    /// # Retour
+    ///
+    /// La sauvegarde, ou un message d'erreur si elle est absente, illisible ou d'une autre version.
+    pub fn load() -> Result<Self, String> {
+        let text =
+            storage::read_data_file(FILE_NAME).ok_or_else(|| "aucune sauvegarde".to_string())?;
+        Self::parse(&text)
+    }
+
+    /// Enregistre la sauvegarde dans le répertoire de données.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur d'entrée/sortie si le fichier n'a pas pu être écrit.
+    pub fn save(&self) -> io::Result<()> {
+        storage::write_data_file(FILE_NAME, &self.serialize())
+    }
+
+    /// Supprime la sauvegarde du répertoire de données.
+    pub fn delete() {
+        storage::remove_data_file(FILE_NAME);
+    }
+
+    /// Enregistre la sauvegarde dans un emplacement nommé, distinct de la sauvegarde reprise
+    /// depuis l'écran de configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - Le nom de l'emplacement.
+    ///
+    /// # Retour
+    ///
+    /// Un message d'erreur si le nom est invalide ou si le fichier n'a pas pu être écrit.
+    pub fn save_slot(&self, slot: &str) -> Result<(), String> {
+        let name = slot_file_name(slot)?;
+        storage::write_data_file(&name, &self.serialize()).map_err(|error| error.to_string())
+    }
+
+    /// Charge la sauvegarde d'un emplacement nommé.
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - Le nom de l'emplacement.
+    ///
+    /// # Retour
+    ///
+    /// La sauvegarde, ou un message d'erreur si le nom est invalide ou si la sauvegarde est
+    /// absente, illisible ou d'une autre version.
+    pub fn load_slot(slot: &str) -> Result<Self, String> {
+        let name = slot_file_name(slot)?;
+        let text =
+            storage::read_data_file(&name).ok_or_else(|| format!("emplacement vide : {}", slot))?;
+        Self::parse(&text)
+    }
+
+    /// Convertit la sauvegarde au format du fichier : un en-tête avec la version, puis une
+    /// ligne par élément (`clé valeurs...`).
+    ///
+    /// # Retour
+    ///
+    /// Le contenu à écrire dans le fichier.
+    pub fn serialize(&self) -> String {
+        let stats = &self.stats;
+        let scores = &self.player_scores;
+        let lives = self.lives.get_counts();
+        let frags = self.scoreboard.get_frags();
+        let deaths = self.scoreboard.get_deaths();
+        let mut lines = vec![
+            format!("{} {}", HEADER, VERSION),
+            format!("seed {}", self.seed),
+            format!("rng {}", self.rng_state),
+            format!("wave {}", self.wave),
+            format!("score {}", self.score),
+            format!(
+                "scores {} {} {} {}",
+                scores[0], scores[1], scores[2], scores[3]
+            ),
+            format!("players {} {}", self.players, self.friendly_fire),
+            format!(
+                "lives {} {} {} {} {}",
+                self.lives.is_shared(),
+                lives[0],
+                lives[1],
+                lives[2],
+                lives[3]
+            ),
+            format!(
+                "frags {} {} {} {} {} {} {} {}",
+                frags[0], frags[1], frags[2], frags[3], deaths[0], deaths[1], deaths[2], deaths[3]
+            ),
+            format!("elapsed {} {}", self.elapsed, self.wave_elapsed),
+            format!(
+                "config {} {} {} {}",
+                self.asteroid_count, self.asteroid_speed, self.waves, self.cheated
+            ),
+            format!(
+                "stats {} {} {} {} {} {} {}",
+                stats.shots_fired,
+                stats.shots_hit,
+                stats.asteroids_destroyed[0],
+                stats.asteroids_destroyed[1],
+                stats.asteroids_destroyed[2],
+                stats.hits_taken,
+                stats.damage_taken
+            ),
+        ];
+        if let Some(rules) = &self.versus {
+            lines.push(format!(
+                "versus {} {} {}",
+                rules.frag_limit, rules.time_limit, self.match_elapsed
+            ));
+        }
+        for ship in &self.ships {
+            lines.push(format!(
+                "ship {} {} {} {} {} {} {} {}",
+                ship.player,
+                ship.position.x,
+                ship.position.y,
+                ship.rotation,
+                ship.speed.x,
+                ship.speed.y,
+                ship.shield,
+                ship.last_shot_age
+            ));
+        }
+        for (level, position, speed) in &self.asteroids {
+            lines.push(format!(
+                "asteroid {} {} {} {} {}",
+                level, position.x, position.y, speed.x, speed.y
+            ));
+        }
+        for (owner, position, speed) in &self.missiles {
+            lines.push(format!(
+                "missile {} {} {} {} {}",
+                owner, position.x, position.y, speed.x, speed.y
+            ));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Lit une sauvegarde depuis le contenu d'un fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Le contenu du fichier.
+    ///
+    /// # Retour
+    ///
+    /// La sauvegarde, ou un message d'erreur indiquant la ligne fautive.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate();
+        match lines
+            .next()
+            .map(|(_, line)| line.split_whitespace().collect::<Vec<_>>())
+        {
+            Some(header) if header.len() == 2 && header[0] == HEADER => {
+                if header[1] != VERSION.to_string() {
+                    return Err(format!(
+                        "version de sauvegarde {} non prise en charge (attendue : {})",
+                        header[1], VERSION
+                    ));
+                }
+            }
+            _ => return Err("ce fichier n'est pas une sauvegarde".to_string()),
+        }
+
+        let mut save = Self {
+            seed: 0,
+            rng_state: 0,
+            wave: 1,
+            score: 0,
+            player_scores: [0; MAX_PLAYERS],
+            players: 1,
+            lives: Lives::new(1, 0, false),
+            friendly_fire: false,
+            versus: None,
+            match_elapsed: 0.,
+            scoreboard: Scoreboard::new(),
+            elapsed: 0.,
+            wave_elapsed: 0.,
+            asteroid_count: 0,
+            asteroid_speed: 0.,
+            waves: 1,
+            cheated: false,
+            stats: GameStats::new(),
+            ships: Vec::new(),
+            asteroids: Vec::new(),
+            missiles: Vec::new(),
+        };
+
+        for (number, line) in lines {
+            let mut fields = line.split_whitespace();
+            let Some(key) = fields.next() else {
+                continue;
+            };
+            let values: Vec<&str> = fields.collect();
+            let error = || format!("ligne {} invalide : \"{}\"", number + 1, line);
+            let expect = |count: usize| {
+                if values.len() == count {
+                    Ok(())
+                } else {
+                    Err(error())
+                }
+            };
+            let float = |index: usize| values[index].parse::<f32>().map_err(|_| error());
+
+            match key {
+                "seed" => {
+                    expect(1)?;
+                    save.seed = values[0].parse().map_err(|_| error())?;
+                }
+                "rng" => {
+                    expect(1)?;
+                    save.rng_state = values[0].parse().map_err(|_| error())?;
+                }
+                "wave" => {
+                    expect(1)?;
+                    save.wave = values[0].parse().map_err(|_| error())?;
+                }
+                "score" => {
+                    expect(1)?;
+                    save.score = values[0].parse().map_err(|_| error())?;
+                }
+                "scores" => {
+                    expect(MAX_PLAYERS)?;
+                    for (index, score) in save.player_scores.iter_mut().enumerate() {
+                        *score = values[index].parse().map_err(|_| error())?;
+                    }
+                }
+                "players" => {
+                    expect(2)?;
+                    save.players = values[0].parse().map_err(|_| error())?;
+                    if !(1..=MAX_PLAYERS).contains(&save.players) {
+                        return Err(error());
+                    }
+                    save.friendly_fire = values[1].parse().map_err(|_| error())?;
+                }
+                "lives" => {
+                    expect(MAX_PLAYERS + 1)?;
+                    let shared = values[0].parse().map_err(|_| error())?;
+                    let mut counts = [0; MAX_PLAYERS];
+                    for (index, count) in counts.iter_mut().enumerate() {
+                        *count = values[index + 1].parse().map_err(|_| error())?;
+                    }
+                    save.lives = Lives::from_counts(shared, counts);
+                }
+                "frags" => {
+                    expect(2 * MAX_PLAYERS)?;
+                    let mut frags = [0; MAX_PLAYERS];
+                    let mut deaths = [0; MAX_PLAYERS];
+                    for player in 0..MAX_PLAYERS {
+                        frags[player] = values[player].parse().map_err(|_| error())?;
+                        deaths[player] =
+                            values[MAX_PLAYERS + player].parse().map_err(|_| error())?;
+                    }
+                    save.scoreboard = Scoreboard::from_counts(frags, deaths);
+                }
+                "versus" => {
+                    expect(3)?;
+                    save.versus = Some(VersusRules {
+                        frag_limit: values[0].parse().map_err(|_| error())?,
+                        time_limit: values[1].parse().map_err(|_| error())?,
+                    });
+                    save.match_elapsed = values[2].parse().map_err(|_| error())?;
+                }
+                "elapsed" => {
+                    expect(2)?;
+                    save.elapsed = values[0].parse().map_err(|_| error())?;
+                    save.wave_elapsed = values[1].parse().map_err(|_| error())?;
+                }
+                "config" => {
+                    expect(4)?;
+                    save.asteroid_count = values[0].parse().map_err(|_| error())?;
+                    save.asteroid_speed = float(1)?;
+                    save.waves = values[2].parse().map_err(|_| error())?;
+                    save.cheated = values[3].parse().map_err(|_| error())?;
+                }
+                "stats" => {
+                    expect(7)?;
+                    let count = |index: usize| values[index].parse::<u32>().map_err(|_| error());
+                    save.stats = GameStats {
+                        shots_fired: count(0)?,
+                        shots_hit: count(1)?,
+                        asteroids_destroyed: [count(2)?, count(3)?, count(4)?],
+                        hits_taken: count(5)?,
+                        damage_taken: float(6)?,
+                    };
+                }
+                "ship" => {
+                    expect(8)?;
+                    let player: usize = values[0].parse().map_err(|_| error())?;
+                    if player >= MAX_PLAYERS {
+                        return Err(error());
+                    }
+                    save.ships.push(SavedShip {
+                        player,
+                        position: vec2(float(1)?, float(2)?),
+                        rotation: float(3)?,
+                        speed: vec2(float(4)?, float(5)?),
+                        shield: float(6)?,
+                        last_shot_age: values[7].parse().map_err(|_| error())?,
+                    });
+                }
+                "asteroid" => {
+                    expect(5)?;
+                    let level: u8 = values[0].parse().map_err(|_| error())?;
+                    if !(1..=3).contains(&level) {
+                        return Err(error());
+                    }
+                    save.asteroids.push((
+                        level,
+                        vec2(float(1)?, float(2)?),
+                        vec2(float(3)?, float(4)?),
+                    ));
+                }
+                "missile" => {
+                    expect(5)?;
+                    let owner: usize = values[0].parse().map_err(|_| error())?;
+                    if owner >= MAX_PLAYERS {
+                        return Err(error());
+                    }
+                    save.missiles.push((
+                        owner,
+                        vec2(float(1)?, float(2)?),
+                        vec2(float(3)?, float(4)?),
+                    ));
+                }
+                _ => return Err(error()),
+            }
+        }
+
+        if save.ships.is_empty() {
+            return Err("la sauvegarde ne contient pas de vaisseau".to_string());
+        }
+        Ok(save)
+    }
+}
@@ -0,0 +1,416 @@
+use crate::events::EventQueue;
+use crate::net::{Message, Snapshot, MAX_DATAGRAM, PROTOCOL_VERSION, TICK_RATE};
+use crate::players::{Lives, COOP_EXTRA_LIVES, MAX_PLAYERS};
+use crate::settings::KeyBindings;
+use crate::stats::Score;
+use crate::vaisseau::ShipInput;
+use crate::versus::Scoreboard;
+use crate::world::World;
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// Nombre de pas sans aucun paquet d'un client avant de le considérer comme déconnecté
+/// (5 secondes).
+const CLIENT_TIMEOUT: u32 = (5. * TICK_RATE) as u32;
+
+/// Nombre maximal de commandes en attente par client. Au-delà, les plus anciennes sont
+/// abandonnées pour que le retard du client ne s'accumule pas.
+const MAX_BUFFERED_INPUTS: usize = 8;
+
+/// Un instantané est envoyé aux clients tous les `SNAPSHOT_INTERVAL` pas.
+const SNAPSHOT_INTERVAL: u64 = 4;
+
+/// Réglages de la partie hébergée par le serveur.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ServerConfig {
+    /// Nombre maximal de clients (un vaisseau chacun).
+    pub max_players: usize,
+    /// Match en versus plutôt qu'en coopération.
+    pub versus: bool,
+    /// Tir ami en coopération.
+    pub friendly_fire: bool,
+    /// Nombre d'astéroïdes de la première vague.
+    pub asteroid_count: i32,
+    /// Vitesse des astéroïdes.
+    pub asteroid_speed: f32,
+    /// Taille de l'arène, identique pour tous les clients.
+    pub arena: Vec2,
+    /// Graine aléatoire de la partie.
+    pub seed: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            max_players: MAX_PLAYERS,
+            versus: false,
+            friendly_fire: false,
+            asteroid_count: 5,
+            asteroid_speed: 1.,
+            arena: vec2(800., 600.),
+            seed: 0,
+        }
+    }
+}
+
+/// Client connecté au serveur.
+struct Peer {
+    /// Adresse du client.
+    address: SocketAddr,
+    /// Joueur attribué au client.
+    player: usize,
+    /// Commandes reçues et pas encore appliquées, avec leur numéro.
+    inputs: VecDeque<(u32, ShipInput)>,
+    /// Numéro de la dernière commande reçue.
+    last_received: u32,
+    /// Numéro de la dernière commande appliquée, renvoyé dans les instantanés.
+    last_applied: u32,
+    /// Commandes appliquées au dernier pas, répétées si aucune nouvelle n'est arrivée.
+    input: ShipInput,
+    /// Nombre de pas depuis le dernier paquet reçu.
+    silent_ticks: u32,
+}
+
+/// Serveur d'une partie en réseau : il fait avancer la simulation à `TICK_RATE` pas par
+/// seconde avec les commandes reçues des clients et leur renvoie l'état de la partie.
+///
+/// Le serveur fait autorité : les clients ne font que prédire le mouvement de leur propre
+/// vaisseau en attendant sa réponse. Il n'ouvre aucune fenêtre.
+pub struct Server {
+    socket: UdpSocket,
+    config: ServerConfig,
+    world: World,
+    events: EventQueue,
+    score: Score,
+    scoreboard: Scoreboard,
+    peers: Vec<Peer>,
+    tick: u64,
+}
+
+impl Server {
+    /// Ouvre le serveur sur une adresse et prépare une partie.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - L'adresse d'écoute (le port 0 laisse le système en choisir un).
+    /// * `config` - Les réglages de la partie.
+    ///
+    /// # Retour
+    ///
+    /// Le serveur, ou une erreur d'entrée/sortie si l'adresse n'a pas pu être ouverte.
+    pub fn bind(address: impl ToSocketAddrs, config: ServerConfig) -> io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+        socket.set_nonblocking(true)?;
+        let mut server = Self {
+            socket,
+            config,
+            world: World::with_arena(config.arena),
+            events: EventQueue::new(),
+            score: Score::new(),
+            scoreboard: Scoreboard::new(),
+            peers: Vec::new(),
+            tick: 0,
+        };
+        server.reset_match();
+        Ok(server)
+    }
+
+    /// Retourne l'adresse sur laquelle le serveur écoute.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Retourne la simulation du serveur.
+    pub fn get_world(&self) -> &World {
+        &self.world
+    }
+
+    /// Retourne le nombre de clients connectés.
+    pub fn get_client_count(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Commence une nouvelle partie, sans vaisseau : chaque client reçoit le sien en se
+    /// connectant.
+    fn reset_match(&mut self) {
+        let config = self.config;
+        let world = &mut self.world;
+        world.players = config.max_players;
+        world.versus = config.versus;
+        world.friendly_fire = config.friendly_fire && !config.versus;
+        let extra_lives = if config.versus { 0 } else { COOP_EXTRA_LIVES };
+        world.lives = Lives::new(config.max_players, extra_lives, false);
+        world.respawns.clear();
+        world.wave = 1;
+        world.reseed(config.seed);
+        crate::reset_game(
+            &mut world.asteroids,
+            &mut world.ships,
+            0,
+            &mut world.missiles,
+            world.level_size,
+            world.arena,
+            config.asteroid_count,
+            config.asteroid_speed,
+            &mut self.score,
+            false,
+            &mut world.rng,
+        );
+        self.scoreboard = Scoreboard::new();
+    }
+
+    /// Fait tourner le serveur jusqu'à une erreur réseau, à `TICK_RATE` pas par seconde.
+    ///
+    /// # Retour
+    ///
+    /// L'erreur d'entrée/sortie qui a arrêté le serveur.
+    pub fn run(&mut self) -> io::Result<()> {
+        let step = Duration::from_secs_f64(1. / TICK_RATE);
+        let mut next_tick = Instant::now();
+        loop {
+            self.poll()?;
+            self.tick()?;
+            next_tick += step;
+            let now = Instant::now();
+            if next_tick > now {
+                std::thread::sleep(next_tick - now);
+            } else {
+                // Le serveur est en retard : repartir de maintenant plutôt que rattraper
+                next_tick = now;
+            }
+        }
+    }
+
+    /// Lit tous les paquets reçus depuis le dernier appel.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur d'entrée/sortie si la lecture a échoué.
+    pub fn poll(&mut self) -> io::Result<()> {
+        let mut buffer = vec![0; MAX_DATAGRAM];
+        loop {
+            let (length, address) = match self.socket.recv_from(&mut buffer) {
+                Ok(received) => received,
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return Ok(()),
+                // Un client disparu peut provoquer une erreur ICMP sur certains systèmes
+                Err(error) if error.kind() == ErrorKind::ConnectionReset => continue,
+                Err(error) => return Err(error),
+            };
+            let text = String::from_utf8_lossy(&buffer[..length]);
+            match Message::decode(&text) {
+                Ok(message) => self.handle_message(message, address)?,
+                Err(error) => eprintln!("Paquet ignoré de {} : {}", address, error),
+            }
+        }
+    }
+
+    /// Traite un message reçu d'un client.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Le message reçu.
+    /// * `address` - L'adresse du client.
+    fn handle_message(&mut self, message: Message, address: SocketAddr) -> io::Result<()> {
+        let peer = self.peers.iter_mut().find(|peer| peer.address == address);
+        match (message, peer) {
+            (Message::Hello { version }, _) if version != PROTOCOL_VERSION => {
+                let reason = format!("version {} attendue", PROTOCOL_VERSION);
+                self.send(&Message::Refused { reason }, address)
+            }
+            (Message::Hello { .. }, Some(peer)) => {
+                // Le client n'a pas reçu la réponse : la renvoyer
+                peer.silent_ticks = 0;
+                let player = peer.player;
+                self.send_welcome(player, address)
+            }
+            (Message::Hello { .. }, None) => {
+                let free = (0..self.config.max_players.min(MAX_PLAYERS))
+                    .find(|&player| self.peers.iter().all(|peer| peer.player != player));
+                match free {
+                    Some(player) => {
+                        self.join(player, address);
+                        self.send_welcome(player, address)
+                    }
+                    None => {
+                        let reason = "partie pleine".to_string();
+                        self.send(&Message::Refused { reason }, address)
+                    }
+                }
+            }
+            (Message::Input { sequence, inputs }, Some(peer)) => {
+                peer.silent_ticks = 0;
+                // Les commandes arrivent de la plus récente à la plus ancienne
+                for (age, &input) in inputs.iter().enumerate().rev() {
+                    let Some(number) = sequence.checked_sub(age as u32) else {
+                        continue;
+                    };
+                    if number > peer.last_received {
+                        peer.inputs.push_back((number, input));
+                        peer.last_received = number;
+                    }
+                }
+                while peer.inputs.len() > MAX_BUFFERED_INPUTS {
+                    peer.inputs.pop_front();
+                }
+                Ok(())
+            }
+            (Message::Bye, Some(_)) => {
+                self.leave(address);
+                Ok(())
+            }
+            // Message d'un client inconnu ou réservé au serveur : ignoré
+            _ => Ok(()),
+        }
+    }
+
+    /// Ajoute un client et fait apparaître son vaisseau.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le joueur attribué au client.
+    /// * `address` - L'adresse du client.
+    fn join(&mut self, player: usize, address: SocketAddr) {
+        self.peers.push(Peer {
+            address,
+            player,
+            inputs: VecDeque::new(),
+            last_received: 0,
+            last_applied: 0,
+            input: ShipInput::default(),
+            silent_ticks: 0,
+        });
+        self.world
+            .ships
+            .retain(|vaisseau| vaisseau.get_player() != player);
+        self.world
+            .respawns
+            .retain(|respawn| respawn.player != player);
+        let arena = self.world.arena;
+        let vaisseau = self.world.spawn_ship(player, KeyBindings::default(), arena);
+        self.world.ships.push(vaisseau);
+        println!(
+            "Joueur {} connecté depuis {} ({} joueurs)",
+            player + 1,
+            address,
+            self.get_client_count()
+        );
+    }
+
+    /// Retire un client et son vaisseau. La partie recommence quand le dernier client part.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - L'adresse du client.
+    fn leave(&mut self, address: SocketAddr) {
+        let Some(index) = self.peers.iter().position(|peer| peer.address == address) else {
+            return;
+        };
+        let player = self.peers.remove(index).player;
+        self.world
+            .ships
+            .retain(|vaisseau| vaisseau.get_player() != player);
+        self.world
+            .respawns
+            .retain(|respawn| respawn.player != player);
+        println!(
+            "Joueur {} déconnecté ({} joueurs)",
+            player + 1,
+            self.get_client_count()
+        );
+        if self.peers.is_empty() {
+            self.reset_match();
+        }
+    }
+
+    /// Fait avancer la simulation d'un pas et envoie un instantané aux clients si c'est
+    /// le moment.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur d'entrée/sortie si l'envoi a échoué.
+    pub fn tick(&mut self) -> io::Result<()> {
+        // Déconnecter les clients silencieux
+        let silent: Vec<SocketAddr> = self
+            .peers
+            .iter_mut()
+            .filter_map(|peer| {
+                peer.silent_ticks += 1;
+                (peer.silent_ticks > CLIENT_TIMEOUT).then_some(peer.address)
+            })
+            .collect();
+        for address in silent {
+            self.leave(address);
+        }
+
+        // Une commande par client et par pas, dans l'ordre d'envoi
+        for peer in self.peers.iter_mut() {
+            if let Some((number, input)) = peer.inputs.pop_front() {
+                peer.input = input;
+                peer.last_applied = number;
+            }
+        }
+        for vaisseau in self.world.ships.iter_mut() {
+            let input = self
+                .peers
+                .iter()
+                .find(|peer| peer.player == vaisseau.get_player())
+                .map_or(ShipInput::default(), |peer| peer.input);
+            vaisseau.set_input(input);
+        }
+
+        if self.world.asteroids.is_empty() && !self.peers.is_empty() {
+            self.world.wave += 1;
+            let world = &mut self.world;
+            crate::spawn_asteroids(
+                &mut world.asteroids,
+                world.level_size,
+                world.arena,
+                self.config.asteroid_count + 2 * (world.wave as i32 - 1),
+                self.config.asteroid_speed,
+                None,
+                &mut world.rng,
+            );
+        }
+        self.world.step(&mut self.events);
+        self.events
+            .dispatch(&mut [&mut self.score, &mut self.scoreboard]);
+
+        if self.tick.is_multiple_of(SNAPSHOT_INTERVAL) {
+            self.send_snapshots()?;
+        }
+        self.tick += 1;
+        Ok(())
+    }
+
+    /// Envoie l'état de la partie à chaque client, avec le numéro de sa dernière commande
+    /// appliquée.
+    fn send_snapshots(&self) -> io::Result<()> {
+        let mut snapshot = Snapshot::capture(self.get_world(), self.tick);
+        for (player, score) in snapshot.scores.iter_mut().enumerate() {
+            *score = self.score.get_player(player);
+        }
+        snapshot.frags = self.scoreboard.get_frags();
+        for peer in &self.peers {
+            snapshot.ack = peer.last_applied;
+            self.send(&Message::Snapshot(snapshot.clone()), peer.address)?;
+        }
+        Ok(())
+    }
+
+    /// Envoie à un client l'acceptation de sa demande.
+    fn send_welcome(&self, player: usize, address: SocketAddr) -> io::Result<()> {
+        let arena = self.world.arena;
+        self.send(&Message::Welcome { player, arena }, address)
+    }
+
+    /// Envoie un message à un client.
+    fn send(&self, message: &Message, address: SocketAddr) -> io::Result<()> {
+        match self.socket.send_to(message.encode().as_bytes(), address) {
+            Err(error) if error.kind() == ErrorKind::WouldBlock => Ok(()),
+            result => result.map(|_| ()),
+        }
+    }
+}
@@ -0,0 +1,786 @@
+use crate::camera::ArenaAspect;
+use crate::controls::{ControlScheme, GamepadBindings, PadButton, StickMode};
+use crate::pacing::FrameLimit;
+use crate::particles::ParticleQuality;
+use crate::storage;
+use macroquad::prelude::KeyCode;
+use std::io;
+
+/// Nom du fichier de préférences dans le répertoire de données.
+const FILE_NAME: &str = "settings.cfg";
+
+/// Mode d'affichage de la fenêtre.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowMode {
+    /// Fenêtre redimensionnable.
+    Windowed,
+    /// Plein écran.
+    Fullscreen,
+}
+
+impl WindowMode {
+    /// Retourne l'identifiant du mode utilisé dans le fichier de préférences.
+    pub fn key(self) -> &'static str {
+        match self {
+            WindowMode::Windowed => "windowed",
+            WindowMode::Fullscreen => "fullscreen",
+        }
+    }
+
+    /// Retrouve un mode d'affichage à partir de son identifiant.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - L'identifiant lu dans le fichier de préférences.
+    ///
+    /// # Retour
+    ///
+    /// Le mode correspondant, ou `None` si l'identifiant est inconnu.
+    pub fn from_key(key: &str) -> Option<Self> {
+        [WindowMode::Windowed, WindowMode::Fullscreen]
+            .into_iter()
+            .find(|mode| mode.key() == key)
+    }
+}
+
+/// Touches utilisées pour piloter le vaisseau et mettre la partie en pause.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBindings {
+    /// Accélérer.
+    pub thrust: KeyCode,
+    /// Reculer.
+    pub brake: KeyCode,
+    /// Tourner vers la gauche.
+    pub rotate_left: KeyCode,
+    /// Tourner vers la droite.
+    pub rotate_right: KeyCode,
+    /// Tirer un missile.
+    pub fire: KeyCode,
+    /// Mettre la partie en pause.
+    pub pause: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            thrust: KeyCode::Up,
+            brake: KeyCode::Down,
+            rotate_left: KeyCode::Left,
+            rotate_right: KeyCode::Right,
+            fire: KeyCode::Space,
+            pause: KeyCode::Escape,
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Retourne les touches par défaut du deuxième joueur, à gauche du clavier.
+    ///
+    /// # Retour
+    ///
+    /// Les touches `W`, `S`, `A`, `D`, `LeftShift` pour tirer et `P` pour la pause.
+    pub fn player_two() -> Self {
+        Self {
+            thrust: KeyCode::W,
+            brake: KeyCode::S,
+            rotate_left: KeyCode::A,
+            rotate_right: KeyCode::D,
+            fire: KeyCode::LeftShift,
+            pause: KeyCode::P,
+        }
+    }
+
+    /// Retourne les touches par défaut du troisième joueur, au milieu du clavier.
+    ///
+    /// # Retour
+    ///
+    /// Les touches `I`, `K`, `J`, `L`, `U` pour tirer et `F3` pour la pause.
+    pub fn player_three() -> Self {
+        Self {
+            thrust: KeyCode::I,
+            brake: KeyCode::K,
+            rotate_left: KeyCode::J,
+            rotate_right: KeyCode::L,
+            fire: KeyCode::U,
+            pause: KeyCode::F3,
+        }
+    }
+
+    /// Retourne les touches par défaut du quatrième joueur.
+    ///
+    /// # Retour
+    ///
+    /// Les touches `T`, `G`, `F`, `H`, `R` pour tirer et `F4` pour la pause.
+    pub fn player_four() -> Self {
+        Self {
+            thrust: KeyCode::T,
+            brake: KeyCode::G,
+            rotate_left: KeyCode::F,
+            rotate_right: KeyCode::H,
+            fire: KeyCode::R,
+            pause: KeyCode::F4,
+        }
+    }
+
+    /// Retourne toutes les touches, pour vérifier qu'aucune n'est attribuée deux fois.
+    fn all(&self) -> [KeyCode; 6] {
+        [
+            self.thrust,
+            self.brake,
+            self.rotate_left,
+            self.rotate_right,
+            self.fire,
+            self.pause,
+        ]
+    }
+}
+
+/// Préférences du joueur, lues au démarrage et enregistrées à la fermeture du jeu.
+///
+/// Le fichier est un texte lisible de la forme `clé = valeur`, une préférence par ligne ;
+/// les lignes commençant par `#` sont des commentaires. Une valeur absente ou invalide est
+/// remplacée par sa valeur par défaut, et une valeur hors limites est ramenée dans ses limites.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    /// Nombre d'astéroïdes de la première vague (1 à 25).
+    pub asteroid_count: i32,
+    /// Vitesse des astéroïdes (0.3 à 5.0).
+    pub asteroid_speed: f32,
+    /// Nombre de vagues d'une partie (1 à 20) ; avec une seule vague, la partie est gagnée
+    /// dès que le champ d'astéroïdes est vidé.
+    pub waves: u32,
+    /// Volume général (0.0 à 1.0).
+    pub master_volume: f32,
+    /// Volume de la musique (0.0 à 1.0).
+    pub music_volume: f32,
+    /// Volume des effets sonores (0.0 à 1.0).
+    pub sfx_volume: f32,
+    /// Sourdine : coupe tous les sons sans perdre les volumes choisis.
+    pub muted: bool,
+    /// Tirs et explosions synthétisés pendant le jeu (`false` : fichiers WAV du manifeste).
+    pub synth_sounds: bool,
+    /// Touches du clavier.
+    pub keys: KeyBindings,
+    /// Touches du deuxième joueur.
+    pub keys_p2: KeyBindings,
+    /// Touches du troisième joueur (versus).
+    pub keys_p3: KeyBindings,
+    /// Touches du quatrième joueur (versus).
+    pub keys_p4: KeyBindings,
+    /// Schéma de contrôle du premier joueur (clavier seul ou visée à la souris).
+    pub control_scheme: ControlScheme,
+    /// Rôle du stick gauche des manettes.
+    pub stick_mode: StickMode,
+    /// Boutons des manettes, communs à tous les joueurs.
+    pub pad_buttons: GamepadBindings,
+    /// Nombre de vaisseaux dans l'arène (1 ou 2 en coopération, 2 à 4 en versus).
+    pub players: usize,
+    /// Les joueurs s'affrontent (versus) au lieu de coopérer.
+    pub versus: bool,
+    /// Nombre de frags qui termine un match en versus (1 à 50).
+    pub frag_limit: u32,
+    /// Durée d'un match en versus, en secondes (0 : pas de limite, jusqu'à 1800).
+    pub time_limit: u32,
+    /// Vies mises en commun entre les joueurs (`false` : chaque joueur a ses propres vies).
+    pub shared_lives: bool,
+    /// Les missiles d'un joueur touchent aussi les vaisseaux de ses coéquipiers.
+    pub friendly_fire: bool,
+    /// Mode d'affichage de la fenêtre.
+    pub window_mode: WindowMode,
+    /// Largeur de la fenêtre au démarrage, en pixels.
+    pub window_width: i32,
+    /// Hauteur de la fenêtre au démarrage, en pixels.
+    pub window_height: i32,
+    /// Format de l'arène, affichée avec des bandes noires si la fenêtre a un autre format.
+    pub arena_aspect: ArenaAspect,
+    /// Rythme des images : synchronisation verticale, sans limite ou limite d'images par
+    /// seconde (20 à 1000).
+    pub frame_limit: FrameLimit,
+    /// Graphe des images par seconde affiché au démarrage.
+    pub show_frame_graph: bool,
+    /// Qualité des effets de particules.
+    pub particle_quality: ParticleQuality,
+    /// Racine des ressources (vide : recherche automatique près de l'exécutable).
+    pub asset_root: String,
+    /// Pack de ressources qui remplace certaines ressources (vide : aucun).
+    pub resource_pack: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            asteroid_count: 10,
+            asteroid_speed: 1.0,
+            waves: 1,
+            master_volume: 1.0,
+            music_volume: 0.1,
+            sfx_volume: 0.1,
+            muted: false,
+            synth_sounds: true,
+            keys: KeyBindings::default(),
+            keys_p2: KeyBindings::player_two(),
+            keys_p3: KeyBindings::player_three(),
+            keys_p4: KeyBindings::player_four(),
+            control_scheme: ControlScheme::Keyboard,
+            stick_mode: StickMode::Absolute,
+            pad_buttons: GamepadBindings::default(),
+            players: 1,
+            versus: false,
+            frag_limit: 10,
+            time_limit: 180,
+            shared_lives: false,
+            friendly_fire: false,
+            window_mode: WindowMode::Windowed,
+            window_width: 800,
+            window_height: 600,
+            arena_aspect: ArenaAspect::Standard,
+            frame_limit: FrameLimit::VSync,
+            show_frame_graph: false,
+            particle_quality: ParticleQuality::Medium,
+            asset_root: String::new(),
+            resource_pack: String::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Charge les préférences depuis le répertoire de données.
+    ///
+    /// Les problèmes rencontrés dans le fichier sont affichés sur la sortie d'erreur.
+    ///
+    /// # Retour
+    ///
+    /// Les préférences lues, ou les préférences par défaut si le fichier n'existe pas.
+    pub fn load() -> Self {
+        let Some(text) = storage::read_data_file(FILE_NAME) else {
+            return Self::default();
+        };
+        let (settings, warnings) = Self::parse(&text);
+        for warning in warnings {
+            eprintln!("{} : {}", FILE_NAME, warning);
+        }
+        settings
+    }
+
+    /// Enregistre les préférences dans le répertoire de données.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur d'entrée/sortie si le fichier n'a pas pu être écrit.
+    pub fn save(&self) -> io::Result<()> {
+        storage::write_data_file(FILE_NAME, &self.serialize())
+    }
+
+    /// Lit les préférences depuis le contenu d'un fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Le contenu du fichier.
+    ///
+    /// # Retour
+    ///
+    /// Les préférences validées, et la liste des problèmes rencontrés (lignes illisibles,
+    /// clés inconnues, valeurs invalides ou hors limites).
+    pub fn parse(text: &str) -> (Self, Vec<String>) {
+        let mut settings = Self::default();
+        let mut warnings = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                warnings.push(format!("ligne {} ignorée : \"{}\"", number + 1, line));
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            if let Err(message) = settings.set(key, value) {
+                warnings.push(format!("ligne {} : {}", number + 1, message));
+            }
+        }
+
+        // Deux actions ne peuvent pas partager la même touche, même entre deux joueurs
+        let keys = (0..4)
+            .flat_map(|player| settings.player_keys(player).all())
+            .collect::<Vec<_>>();
+        if (1..keys.len()).any(|i| keys[..i].contains(&keys[i])) {
+            warnings.push("une touche est attribuée deux fois, touches par défaut".to_string());
+            let defaults = Self::default();
+            settings.keys = defaults.keys;
+            settings.keys_p2 = defaults.keys_p2;
+            settings.keys_p3 = defaults.keys_p3;
+            settings.keys_p4 = defaults.keys_p4;
+        }
+        let buttons = settings.pad_buttons.all();
+        if (1..buttons.len()).any(|i| buttons[..i].contains(&buttons[i])) {
+            warnings.push(
+                "un bouton de manette est attribué deux fois, boutons par défaut".to_string(),
+            );
+            settings.pad_buttons = GamepadBindings::default();
+        }
+        (settings, warnings)
+    }
+
+    /// Modifie une préférence à partir de sa clé et de sa valeur textuelle.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Le nom de la préférence.
+    /// * `value` - La valeur lue dans le fichier.
+    ///
+    /// # Retour
+    ///
+    /// Un message d'erreur si la clé est inconnue ou la valeur invalide. Une valeur hors
+    /// limites est ramenée dans ses limites et signalée.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let invalid = || format!("valeur invalide pour {} : \"{}\"", key, value);
+        match key {
+            "asteroid_count" => {
+                let count: i32 = value.parse().map_err(|_| invalid())?;
+                clamp_into(&mut self.asteroid_count, key, count, 1, 25)?;
+            }
+            "asteroid_speed" => {
+                let speed: f32 = value.parse().map_err(|_| invalid())?;
+                clamp_into(&mut self.asteroid_speed, key, speed, 0.3, 5.0)?;
+            }
+            "waves" => {
+                let waves: u32 = value.parse().map_err(|_| invalid())?;
+                clamp_into(&mut self.waves, key, waves, 1, 20)?;
+            }
+            "master_volume" | "music_volume" | "sfx_volume" => {
+                let volume: f32 = value.parse().map_err(|_| invalid())?;
+                let target = match key {
+                    "master_volume" => &mut self.master_volume,
+                    "music_volume" => &mut self.music_volume,
+                    _ => &mut self.sfx_volume,
+                };
+                clamp_into(target, key, volume, 0.0, 1.0)?;
+            }
+            "muted" => self.muted = value.parse().map_err(|_| invalid())?,
+            "synth_sounds" => self.synth_sounds = value.parse().map_err(|_| invalid())?,
+            "players" => {
+                let players: usize = value.parse().map_err(|_| invalid())?;
+                clamp_into(&mut self.players, key, players, 1, 4)?;
+            }
+            "versus" => self.versus = value.parse().map_err(|_| invalid())?,
+            "frag_limit" => {
+                let limit: u32 = value.parse().map_err(|_| invalid())?;
+                clamp_into(&mut self.frag_limit, key, limit, 1, 50)?;
+            }
+            "time_limit" => {
+                let limit: u32 = value.parse().map_err(|_| invalid())?;
+                clamp_into(&mut self.time_limit, key, limit, 0, 1800)?;
+            }
+            "shared_lives" => self.shared_lives = value.parse().map_err(|_| invalid())?,
+            "friendly_fire" => self.friendly_fire = value.parse().map_err(|_| invalid())?,
+            "window_mode" => {
+                self.window_mode = WindowMode::from_key(value).ok_or_else(invalid)?;
+            }
+            "window_width" => {
+                let width: i32 = value.parse().map_err(|_| invalid())?;
+                clamp_into(&mut self.window_width, key, width, 320, 7680)?;
+            }
+            "window_height" => {
+                let height: i32 = value.parse().map_err(|_| invalid())?;
+                clamp_into(&mut self.window_height, key, height, 240, 4320)?;
+            }
+            "arena_aspect" => {
+                self.arena_aspect = ArenaAspect::from_key(value).ok_or_else(invalid)?;
+            }
+            "frame_limit" => self.frame_limit = FrameLimit::from_key(value).ok_or_else(invalid)?,
+            "show_frame_graph" => self.show_frame_graph = value.parse().map_err(|_| invalid())?,
+            "particle_quality" => {
+                self.particle_quality = ParticleQuality::from_key(value).ok_or_else(invalid)?;
+            }
+            "control_scheme" => {
+                self.control_scheme = ControlScheme::from_key(value).ok_or_else(invalid)?;
+            }
+            "stick_mode" => self.stick_mode = StickMode::from_key(value).ok_or_else(invalid)?,
+            "pad_thrust" | "pad_brake" | "pad_fire" | "pad_pause" => {
+                let target = match key {
+                    "pad_thrust" => &mut self.pad_buttons.thrust,
+                    "pad_brake" => &mut self.pad_buttons.brake,
+                    "pad_fire" => &mut self.pad_buttons.fire,
+                    _ => &mut self.pad_buttons.pause,
+                };
+                *target = button_from_name(value).ok_or_else(invalid)?;
+            }
+            "asset_root" => self.asset_root = value.to_string(),
+            "resource_pack" => self.resource_pack = value.to_string(),
+            _ => {
+                let Some(binding) = self.key_binding_mut(key) else {
+                    return Err(format!("préférence inconnue : \"{}\"", key));
+                };
+                *binding = key_from_name(value).ok_or_else(invalid)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Retourne la touche associée à une clé `key_*` (premier joueur) ou `key2_*` à `key4_*`
+    /// (joueurs suivants) du fichier.
+    fn key_binding_mut(&mut self, key: &str) -> Option<&mut KeyCode> {
+        let (keys, action) = if let Some(action) = key.strip_prefix("key2_") {
+            (&mut self.keys_p2, action)
+        } else if let Some(action) = key.strip_prefix("key3_") {
+            (&mut self.keys_p3, action)
+        } else if let Some(action) = key.strip_prefix("key4_") {
+            (&mut self.keys_p4, action)
+        } else {
+            (&mut self.keys, key.strip_prefix("key_")?)
+        };
+        match action {
+            "thrust" => Some(&mut keys.thrust),
+            "brake" => Some(&mut keys.brake),
+            "left" => Some(&mut keys.rotate_left),
+            "right" => Some(&mut keys.rotate_right),
+            "fire" => Some(&mut keys.fire),
+            "pause" => Some(&mut keys.pause),
+            _ => None,
+        }
+    }
+
+    /// Retourne les touches d'un joueur.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur (0 à 3).
+    ///
+    /// # Retour
+    ///
+    /// Les touches du joueur, ou celles du premier joueur pour un numéro inconnu.
+    pub fn player_keys(&self, player: usize) -> KeyBindings {
+        match player {
+            1 => self.keys_p2,
+            2 => self.keys_p3,
+            3 => self.keys_p4,
+            _ => self.keys,
+        }
+    }
+
+    /// Convertit les préférences au format du fichier, avec des commentaires pour
+    /// faciliter la modification à la main.
+    ///
+    /// # Retour
+    ///
+    /// Le contenu à écrire dans le fichier.
+    pub fn serialize(&self) -> String {
+        format!(
+            "# Préférences du jeu asteroid.\n\
+             # Ce fichier peut être modifié à la main ; une valeur invalide est remplacée\n\
+             # par sa valeur par défaut au prochain démarrage.\n\
+             \n\
+             # Partie : nombre (1 à 25) et vitesse (0.3 à 5.0) des astéroïdes ; nombre de\n\
+             # vagues à terminer pour gagner (1 à 20, 1 = vider le champ d'astéroïdes)\n\
+             asteroid_count = {}\n\
+             asteroid_speed = {:.2}\n\
+             waves = {}\n\
+             \n\
+             # Volumes, de 0.0 (muet) à 1.0 ; sourdine : true ou false\n\
+             master_volume = {:.2}\n\
+             music_volume = {:.2}\n\
+             sfx_volume = {:.2}\n\
+             muted = {}\n\
+             # Tirs et explosions synthétisés (true) ou lus dans les fichiers WAV (false)\n\
+             synth_sounds = {}\n\
+             \n\
+             # Joueurs : 1 ou 2 vaisseaux en coopération, 2 à 4 en versus (versus = true) ;\n\
+             # vies communes et tir ami (coopération) : true ou false ; fin d'un match en\n\
+             # versus : nombre de frags (1 à 50) et durée en secondes (0 = sans limite)\n\
+             players = {}\n\
+             versus = {}\n\
+             shared_lives = {}\n\
+             friendly_fire = {}\n\
+             frag_limit = {}\n\
+             time_limit = {}\n\
+             \n\
+             # Affichage : windowed ou fullscreen ; taille de la fenêtre au démarrage ; format\n\
+             # de l'arène : 4:3, 16:9 ou 1:1 (bandes noires si la fenêtre a un autre format)\n\
+             window_mode = {}\n\
+             window_width = {}\n\
+             window_height = {}\n\
+             arena_aspect = {}\n\
+             # Images : vsync (synchronisation verticale), uncapped (sans limite) ou limite\n\
+             # d'images par seconde (20 à 1000) ; graphe des images par seconde (touche F9)\n\
+             frame_limit = {}\n\
+             show_frame_graph = {}\n\
+             \n\
+             # Effets de particules : off, low, medium ou high\n\
+             particle_quality = {}\n\
+             \n\
+             # Ressources : dossier racine (vide = à côté de l'exécutable) et pack de\n\
+             # ressources facultatif, relatif à la racine, qui remplace certains fichiers\n\
+             asset_root = {}\n\
+             resource_pack = {}\n\
+             \n\
+             # Touches : A à Z, 0 à 9, Up, Down, Left, Right, Space, Enter, Escape, Tab,\n\
+             # LeftShift, RightShift, LeftControl, RightControl, LeftAlt, RightAlt, F1 à F12\n\
+             {}\
+             # Touches du deuxième joueur\n\
+             {}\
+             # Touches des troisième et quatrième joueurs (versus)\n\
+             {}\
+             {}\
+             \n\
+             # Contrôles du premier joueur : keyboard, mouse_aim (le vaisseau vise la souris,\n\
+             # clic gauche pour tirer, clic droit pour accélérer) ou touch (souris ou écran\n\
+             # tactile seuls, avec des boutons à l'écran)\n\
+             control_scheme = {}\n\
+             # Manettes (une par joueur, dans l'ordre de branchement) : stick gauche en mode\n\
+             # absolute (le vaisseau suit la direction du stick) ou tank (il tourne) ; boutons\n\
+             # South, East, North, West, C, Z, LeftTrigger, LeftTrigger2, RightTrigger,\n\
+             # RightTrigger2, Select, Start, Mode, LeftThumb, RightThumb, DPadUp, DPadDown,\n\
+             # DPadLeft, DPadRight ; une gâchette donne une accélération progressive\n\
+             stick_mode = {}\n\
+             pad_thrust = {}\n\
+             pad_brake = {}\n\
+             pad_fire = {}\n\
+             pad_pause = {}\n",
+            self.asteroid_count,
+            self.asteroid_speed,
+            self.waves,
+            self.master_volume,
+            self.music_volume,
+            self.sfx_volume,
+            self.muted,
+            self.synth_sounds,
+            self.players,
+            self.versus,
+            self.shared_lives,
+            self.friendly_fire,
+            self.frag_limit,
+            self.time_limit,
+            self.window_mode.key(),
+            self.window_width,
+            self.window_height,
+            self.arena_aspect.key(),
+            self.frame_limit.key(),
+            self.show_frame_graph,
+            self.particle_quality.key(),
+            self.asset_root,
+            self.resource_pack,
+            key_lines("key", &self.keys),
+            key_lines("key2", &self.keys_p2),
+            key_lines("key3", &self.keys_p3),
+            key_lines("key4", &self.keys_p4),
+            self.control_scheme.key(),
+            self.stick_mode.key(),
+            button_name(self.pad_buttons.thrust),
+            button_name(self.pad_buttons.brake),
+            button_name(self.pad_buttons.fire),
+            button_name(self.pad_buttons.pause),
+        )
+    }
+}
+
+/// Écrit les touches d'un joueur au format du fichier de préférences.
+///
+/// # Arguments
+///
+/// * `prefix` - Le préfixe des clés du joueur (`key`, `key2`...).
+/// * `keys` - Les touches du joueur.
+///
+/// # Retour
+///
+/// Une ligne `clé = touche` par action.
+fn key_lines(prefix: &str, keys: &KeyBindings) -> String {
+    [
+        ("thrust", keys.thrust),
+        ("brake", keys.brake),
+        ("left", keys.rotate_left),
+        ("right", keys.rotate_right),
+        ("fire", keys.fire),
+        ("pause", keys.pause),
+    ]
+    .iter()
+    .map(|(action, key)| format!("{}_{} = {}\n", prefix, action, key_name(*key)))
+    .collect()
+}
+
+/// Écrit une valeur dans une préférence en la ramenant dans ses limites.
+///
+/// # Arguments
+///
+/// * `target` - La préférence à modifier.
+/// * `key` - Le nom de la préférence, pour le message d'erreur.
+/// * `value` - La valeur lue dans le fichier.
+/// * `min`, `max` - Les limites de la préférence.
+///
+/// # Retour
+///
+/// Un message d'erreur si la valeur a dû être corrigée. Une valeur `NaN` laisse la
+/// préférence inchangée.
+fn clamp_into<T: PartialOrd + Copy + std::fmt::Display>(
+    target: &mut T,
+    key: &str,
+    value: T,
+    min: T,
+    max: T,
+) -> Result<(), String> {
+    if value >= min && value <= max {
+        *target = value;
+        Ok(())
+    } else if value < min {
+        *target = min;
+        Err(format!("{} trop petit, ramené à {}", key, min))
+    } else if value > max {
+        *target = max;
+        Err(format!("{} trop grand, ramené à {}", key, max))
+    } else {
+        Err(format!("valeur invalide pour {}", key))
+    }
+}
+
+/// Noms des touches acceptées dans le fichier de préférences.
+const KEY_NAMES: [(&str, KeyCode); 62] = [
+    ("A", KeyCode::A),
+    ("B", KeyCode::B),
+    ("C", KeyCode::C),
+    ("D", KeyCode::D),
+    ("E", KeyCode::E),
+    ("F", KeyCode::F),
+    ("G", KeyCode::G),
+    ("H", KeyCode::H),
+    ("I", KeyCode::I),
+    ("J", KeyCode::J),
+    ("K", KeyCode::K),
+    ("L", KeyCode::L),
+    ("M", KeyCode::M),
+    ("N", KeyCode::N),
+    ("O", KeyCode::O),
+    ("P", KeyCode::P),
+    ("Q", KeyCode::Q),
+    ("R", KeyCode::R),
+    ("S", KeyCode::S),
+    ("T", KeyCode::T),
+    ("U", KeyCode::U),
+    ("V", KeyCode::V),
+    ("W", KeyCode::W),
+    ("X", KeyCode::X),
+    ("Y", KeyCode::Y),
+    ("Z", KeyCode::Z),
+    ("0", KeyCode::Key0),
+    ("1", KeyCode::Key1),
+    ("2", KeyCode::Key2),
+    ("3", KeyCode::Key3),
+    ("4", KeyCode::Key4),
+    ("5", KeyCode::Key5),
+    ("6", KeyCode::Key6),
+    ("7", KeyCode::Key7),
+    ("8", KeyCode::Key8),
+    ("9", KeyCode::Key9),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Space", KeyCode::Space),
+    ("Enter", KeyCode::Enter),
+    ("Escape", KeyCode::Escape),
+    ("Tab", KeyCode::Tab),
+    ("LeftShift", KeyCode::LeftShift),
+    ("RightShift", KeyCode::RightShift),
+    ("LeftControl", KeyCode::LeftControl),
+    ("RightControl", KeyCode::RightControl),
+    ("LeftAlt", KeyCode::LeftAlt),
+    ("RightAlt", KeyCode::RightAlt),
+    ("F1", KeyCode::F1),
+    ("F2", KeyCode::F2),
+    ("F3", KeyCode::F3),
+    ("F4", KeyCode::F4),
+    ("F5", KeyCode::F5),
+    ("F6", KeyCode::F6),
+    ("F7", KeyCode::F7),
+    ("F8", KeyCode::F8),
+    ("F9", KeyCode::F9),
+    ("F10", KeyCode::F10),
+    ("F11", KeyCode::F11),
+    ("F12", KeyCode::F12),
+];
+
+/// Retourne le nom d'une touche tel qu'il est écrit dans le fichier de préférences.
+///
+/// # Arguments
+///
+/// * `key` - La touche.
+///
+/// # Retour
+///
+/// Le nom de la touche, ou `"?"` si elle n'a pas de nom.
+pub fn key_name(key: KeyCode) -> &'static str {
+    KEY_NAMES
+        .iter()
+        .find(|(_, code)| *code == key)
+        .map_or("?", |(name, _)| name)
+}
+
+/// Retrouve une touche à partir de son nom (sans tenir compte des majuscules).
+///
+/// # Arguments
+///
+/// * `name` - Le nom de la touche.
+///
+/// # Retour
+///
+/// La touche correspondante, ou `None` si le nom est inconnu.
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+        .map(|(_, code)| *code)
+}
+
+/// Noms des boutons de manette acceptés dans le fichier de préférences.
+const BUTTON_NAMES: [(&str, PadButton); 19] = [
+    ("South", PadButton::South),
+    ("East", PadButton::East),
+    ("North", PadButton::North),
+    ("West", PadButton::West),
+    ("C", PadButton::C),
+    ("Z", PadButton::Z),
+    ("LeftTrigger", PadButton::LeftTrigger),
+    ("LeftTrigger2", PadButton::LeftTrigger2),
+    ("RightTrigger", PadButton::RightTrigger),
+    ("RightTrigger2", PadButton::RightTrigger2),
+    ("Select", PadButton::Select),
+    ("Start", PadButton::Start),
+    ("Mode", PadButton::Mode),
+    ("LeftThumb", PadButton::LeftThumb),
+    ("RightThumb", PadButton::RightThumb),
+    ("DPadUp", PadButton::DPadUp),
+    ("DPadDown", PadButton::DPadDown),
+    ("DPadLeft", PadButton::DPadLeft),
+    ("DPadRight", PadButton::DPadRight),
+];
+
+/// Retourne le nom d'un bouton de manette tel qu'il est écrit dans le fichier de préférences.
+///
+/// # Arguments
+///
+/// * `button` - Le bouton.
+///
+/// # Retour
+///
+/// Le nom du bouton, ou `"?"` s'il n'a pas de nom.
+pub fn button_name(button: PadButton) -> &'static str {
+    BUTTON_NAMES
+        .iter()
+        .find(|(_, code)| *code == button)
+        .map_or("?", |(name, _)| name)
+}
+
+/// Retrouve un bouton de manette à partir de son nom (sans tenir compte des majuscules).
+///
+/// # Arguments
+///
+/// * `name` - Le nom du bouton.
+///
+/// # Retour
+///
+/// Le bouton correspondant, ou `None` si le nom est inconnu.
+pub fn button_from_name(name: &str) -> Option<PadButton> {
+    BUTTON_NAMES
+        .iter()
+        .find(|(button_name, _)| button_name.eq_ignore_ascii_case(name))
+        .map(|(_, code)| *code)
+}
@@ -0,0 +1,557 @@
+use crate::net::{Message, Snapshot, TICK_RATE};
+use crate::pacing::FramePacer;
+use crate::players::{MAX_PLAYERS, PLAYER_COLORS};
+use crate::settings::Settings;
+use crate::text::draw_ui_text;
+use crate::world::World;
+use crate::Textures;
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::Path;
+
+/// Port TCP du flux des spectateurs par défaut.
+pub const DEFAULT_STREAM_PORT: u16 = 7778;
+
+/// Séparateur entre deux messages du flux : une ligne vide (un message n'en contient jamais).
+const FRAME_END: &str = "\n\n";
+
+/// Quantité maximale de données en attente pour un spectateur. Un spectateur qui ne lit pas
+/// assez vite est déconnecté plutôt que de ralentir la partie.
+const MAX_PENDING_BYTES: usize = 1 << 20;
+
+/// Vitesse de déplacement de la caméra libre, en pixels d'écran par seconde.
+const PAN_SPEED: f32 = 500.;
+
+/// Limites du zoom de la caméra.
+const ZOOM_RANGE: (f32, f32) = (0.25, 4.);
+
+/// Spectateur connecté au flux.
+struct Viewer {
+    stream: TcpStream,
+    /// Données pas encore acceptées par la connexion.
+    pending: Vec<u8>,
+}
+
+/// Destination du flux.
+enum Sink {
+    /// Spectateurs connectés en TCP.
+    Tcp {
+        listener: TcpListener,
+        viewers: Vec<Viewer>,
+    },
+    /// Enregistrement dans un fichier, relu plus tard.
+    File(BufWriter<File>),
+}
+
+/// Flux des instantanés d'une partie, publié à chaque frame pour les spectateurs.
+///
+/// Le flux est à sens unique : rien de ce qu'envoient les spectateurs n'est lu, ils ne
+/// peuvent donc pas influencer la partie. Il contient les messages du jeu en réseau
+/// (`Message::Arena` puis un `Message::Snapshot` par frame), séparés par une ligne vide.
+pub struct SnapshotStream {
+    sink: Sink,
+    arena: Option<Vec2>,
+}
+
+impl SnapshotStream {
+    /// Ouvre un flux auquel les spectateurs se connectent en TCP.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - L'adresse d'écoute.
+    ///
+    /// # Retour
+    ///
+    /// Le flux, ou une erreur d'entrée/sortie si l'adresse n'a pas pu être ouverte.
+    pub fn listen(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            sink: Sink::Tcp {
+                listener,
+                viewers: Vec::new(),
+            },
+            arena: None,
+        })
+    }
+
+    /// Enregistre le flux dans un fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier, remplacé s'il existe.
+    ///
+    /// # Retour
+    ///
+    /// Le flux, ou une erreur d'entrée/sortie si le fichier n'a pas pu être créé.
+    pub fn record(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            sink: Sink::File(BufWriter::new(File::create(path)?)),
+            arena: None,
+        })
+    }
+
+    /// Retourne l'adresse sur laquelle les spectateurs peuvent se connecter.
+    ///
+    /// # Retour
+    ///
+    /// L'adresse d'écoute, ou `None` pour un enregistrement dans un fichier.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        match &self.sink {
+            Sink::Tcp { listener, .. } => listener.local_addr().ok(),
+            Sink::File(_) => None,
+        }
+    }
+
+    /// Publie l'état de la partie, précédé de la taille de l'arène si elle a changé.
+    ///
+    /// # Arguments
+    ///
+    /// * `arena` - La taille de l'arène.
+    /// * `snapshot` - L'état de la partie.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur d'entrée/sortie si le fichier n'a pas pu être écrit, ou si le flux TCP
+    /// ne peut plus accepter de spectateurs.
+    pub fn publish(&mut self, arena: Vec2, snapshot: &Snapshot) -> io::Result<()> {
+        let arena_frame = frame(&Message::Arena { size: arena });
+        let mut frames = String::new();
+        let resized = self.arena != Some(arena);
+        if resized {
+            self.arena = Some(arena);
+            frames += &arena_frame;
+        }
+        frames += &frame(&Message::Snapshot(snapshot.clone()));
+
+        match &mut self.sink {
+            Sink::File(file) => {
+                file.write_all(frames.as_bytes())?;
+                file.flush()
+            }
+            Sink::Tcp { listener, viewers } => {
+                loop {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            stream.set_nonblocking(true)?;
+                            // Un nouveau spectateur reçoit d'abord la taille de l'arène
+                            let pending = if resized {
+                                Vec::new()
+                            } else {
+                                arena_frame.clone().into_bytes()
+                            };
+                            viewers.push(Viewer { stream, pending });
+                        }
+                        Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                        Err(error) => return Err(error),
+                    }
+                }
+                viewers.retain_mut(|viewer| {
+                    viewer.pending.extend_from_slice(frames.as_bytes());
+                    viewer.flush() && viewer.pending.len() <= MAX_PENDING_BYTES
+                });
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Viewer {
+    /// Envoie autant de données en attente que la connexion en accepte.
+    ///
+    /// # Retour
+    ///
+    /// `false` si le spectateur s'est déconnecté.
+    fn flush(&mut self) -> bool {
+        while !self.pending.is_empty() {
+            match self.stream.write(&self.pending) {
+                Ok(0) => return false,
+                Ok(written) => {
+                    self.pending.drain(..written);
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => return true,
+                Err(_) => return false,
+            }
+        }
+        true
+    }
+}
+
+/// Convertit un message en trame du flux.
+fn frame(message: &Message) -> String {
+    message.encode() + FRAME_END
+}
+
+/// Lecture d'un flux d'instantanés, en direct (TCP) ou depuis un enregistrement.
+pub struct SnapshotReader {
+    source: Box<dyn Read>,
+    live: bool,
+    buffer: Vec<u8>,
+    messages: VecDeque<Message>,
+    ended: bool,
+}
+
+impl SnapshotReader {
+    /// Se connecte au flux d'une partie en cours.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - L'adresse du flux.
+    ///
+    /// # Retour
+    ///
+    /// Le lecteur, ou une erreur d'entrée/sortie si la connexion a échoué.
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nonblocking(true)?;
+        Ok(Self::new(Box::new(stream), true))
+    }
+
+    /// Ouvre un enregistrement.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Le chemin du fichier.
+    ///
+    /// # Retour
+    ///
+    /// Le lecteur, ou une erreur d'entrée/sortie si le fichier n'a pas pu être ouvert.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(Box::new(File::open(path)?), false))
+    }
+
+    /// Crée un lecteur sur une source de données.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - La source, non bloquante si le flux est en direct.
+    /// * `live` - `true` pour un flux en direct.
+    fn new(source: Box<dyn Read>, live: bool) -> Self {
+        Self {
+            source,
+            live,
+            buffer: Vec::new(),
+            messages: VecDeque::new(),
+            ended: false,
+        }
+    }
+
+    /// Indique si le flux est en direct (sinon, c'est un enregistrement à rejouer).
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    /// Indique si la source est épuisée : partie terminée ou fin de l'enregistrement.
+    pub fn is_ended(&self) -> bool {
+        self.ended && self.messages.is_empty()
+    }
+
+    /// Lit les données disponibles et découpe les messages complets.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur d'entrée/sortie si la lecture a échoué.
+    pub fn fill(&mut self) -> io::Result<()> {
+        let mut chunk = [0; 64 * 1024];
+        // Un enregistrement est lu morceau par morceau, au rythme où il est rejoué
+        while !self.ended && (self.live || self.messages.len() < 2) {
+            match self.source.read(&mut chunk) {
+                Ok(0) => self.ended = true,
+                Ok(length) => {
+                    self.buffer.extend_from_slice(&chunk[..length]);
+                    self.split_frames();
+                }
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+
+    /// Décode les messages complets du tampon de lecture.
+    fn split_frames(&mut self) {
+        let end = FRAME_END.as_bytes();
+        while let Some(index) = self
+            .buffer
+            .windows(end.len())
+            .position(|window| window == end)
+        {
+            let bytes: Vec<u8> = self.buffer.drain(..index + end.len()).collect();
+            let text = String::from_utf8_lossy(&bytes[..index]);
+            match Message::decode(&text) {
+                Ok(message) => self.messages.push_back(message),
+                Err(error) => eprintln!("Message du flux ignoré : {}", error),
+            }
+        }
+    }
+
+    /// Retire le prochain message lu.
+    pub fn next_message(&mut self) -> Option<Message> {
+        self.messages.pop_front()
+    }
+}
+
+/// Caméra d'un spectateur : libre (déplacement et zoom) ou centrée sur un joueur.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectatorCamera {
+    /// Taille de l'arène observée.
+    pub arena: Vec2,
+    /// Point de l'arène au centre de l'écran.
+    pub center: Vec2,
+    /// Zoom, 1 affichant toute l'arène.
+    pub zoom: f32,
+    /// Joueur suivi, ou `None` en caméra libre.
+    pub follow: Option<usize>,
+}
+
+impl SpectatorCamera {
+    /// Crée une caméra libre qui montre toute l'arène.
+    ///
+    /// # Arguments
+    ///
+    /// * `arena` - La taille de l'arène.
+    pub fn new(arena: Vec2) -> Self {
+        Self {
+            arena,
+            center: arena / 2.,
+            zoom: 1.,
+            follow: None,
+        }
+    }
+
+    /// Déplace la caméra ; elle cesse alors de suivre un joueur.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - Le déplacement, en unités de l'arène.
+    pub fn pan(&mut self, delta: Vec2) {
+        self.follow = None;
+        self.center = (self.center + delta).clamp(Vec2::ZERO, self.arena);
+    }
+
+    /// Multiplie le zoom, dans les limites de `ZOOM_RANGE`.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - Le facteur de zoom (supérieur à 1 pour grossir).
+    pub fn zoom_by(&mut self, factor: f32) {
+        self.zoom = (self.zoom * factor).clamp(ZOOM_RANGE.0, ZOOM_RANGE.1);
+    }
+
+    /// Passe au joueur suivant parmi les vaisseaux en jeu, puis revient en caméra libre.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - L'état de la partie affiché.
+    pub fn cycle_follow(&mut self, snapshot: &Snapshot) {
+        let mut players: Vec<usize> = snapshot.ships.iter().map(|ship| ship.player).collect();
+        players.sort_unstable();
+        self.follow = match self.follow {
+            None => players.first().copied(),
+            Some(current) => players.into_iter().find(|&player| player > current),
+        };
+    }
+
+    /// Recentre la caméra sur le joueur suivi, s'il a un vaisseau en jeu.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - L'état de la partie affiché.
+    pub fn update(&mut self, snapshot: &Snapshot) {
+        let followed = self
+            .follow
+            .and_then(|player| snapshot.ships.iter().find(|ship| ship.player == player));
+        if let Some(ship) = followed {
+            self.center = ship.position;
+        }
+    }
+
+    /// Retourne la caméra de macroquad correspondante.
+    ///
+    /// # Arguments
+    ///
+    /// * `screen` - La taille de la fenêtre.
+    ///
+    /// # Retour
+    ///
+    /// La caméra, qui montre toute l'arène au zoom 1 quelle que soit la taille de la fenêtre.
+    pub fn to_camera(&self, screen: Vec2) -> Camera2D {
+        let fit = (screen.x / self.arena.x).min(screen.y / self.arena.y);
+        let scale = fit * self.zoom;
+        Camera2D {
+            target: self.center,
+            zoom: vec2(2. * scale / screen.x, 2. * scale / screen.y),
+            ..Default::default()
+        }
+    }
+}
+
+/// Boucle d'un spectateur, dans la fenêtre du jeu.
+///
+/// Commandes : flèches ou glisser à la souris pour déplacer la caméra, molette pour zoomer,
+/// `1` à `4` pour suivre un joueur, `Tab` pour passer au joueur suivant, `0` pour revenir en
+/// caméra libre et `Échap` pour quitter.
+///
+/// # Arguments
+///
+/// * `reader` - Le flux à afficher.
+/// * `textures` - Les textures chargées au démarrage.
+/// * `settings` - Les préférences du joueur, pour le rythme des images.
+pub async fn watch(mut reader: SnapshotReader, textures: Textures, settings: &Settings) {
+    let mut world = World::new(settings.arena_aspect);
+    world.ships.clear();
+    let mut camera = SpectatorCamera::new(world.arena);
+    let mut latest: Option<Snapshot> = None;
+    let mut accumulator = 0.;
+    let mut last_mouse = Vec2::from(mouse_position());
+    let mut pacer = FramePacer::new(settings.frame_limit, settings.show_frame_graph);
+    prevent_quit();
+
+    loop {
+        if is_key_pressed(KeyCode::Escape) || is_quit_requested() {
+            break;
+        }
+        pacer.update();
+        if let Err(error) = reader.fill() {
+            eprintln!("Lecture du flux impossible : {}", error);
+            break;
+        }
+
+        // En direct, afficher le dernier état reçu ; un enregistrement est rejoué au rythme
+        // où il a été enregistré
+        let mut budget = if reader.is_live() {
+            usize::MAX
+        } else {
+            accumulator = (accumulator + get_frame_time() as f64).min(0.25);
+            let steps = (accumulator * TICK_RATE) as usize;
+            accumulator -= steps as f64 / TICK_RATE;
+            steps
+        };
+        while budget > 0 {
+            match reader.next_message() {
+                Some(Message::Arena { size }) => {
+                    world.arena = size;
+                    camera.arena = size;
+                }
+                Some(Message::Snapshot(snapshot)) => {
+                    latest = Some(snapshot);
+                    budget -= 1;
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+
+        // Commandes de la caméra
+        let frame_pan = PAN_SPEED * get_frame_time() / camera.zoom;
+        let mut pan = Vec2::ZERO;
+        if is_key_down(KeyCode::Left) {
+            pan.x -= frame_pan;
+        }
+        if is_key_down(KeyCode::Right) {
+            pan.x += frame_pan;
+        }
+        if is_key_down(KeyCode::Up) {
+            pan.y -= frame_pan;
+        }
+        if is_key_down(KeyCode::Down) {
+            pan.y += frame_pan;
+        }
+        let mouse = Vec2::from(mouse_position());
+        if is_mouse_button_down(MouseButton::Left) {
+            let screen = vec2(screen_width(), screen_height());
+            let fit = (screen.x / camera.arena.x).min(screen.y / camera.arena.y);
+            pan -= (mouse - last_mouse) / (fit * camera.zoom);
+        }
+        last_mouse = mouse;
+        if pan != Vec2::ZERO {
+            camera.pan(pan);
+        }
+        let wheel = mouse_wheel().1;
+        if wheel != 0. {
+            camera.zoom_by(if wheel > 0. { 1.1 } else { 1. / 1.1 });
+        }
+        if is_key_pressed(KeyCode::Key0) {
+            camera.follow = None;
+        }
+        let follow_keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4];
+        for (player, &key) in follow_keys.iter().enumerate().take(MAX_PLAYERS) {
+            if is_key_pressed(key) {
+                camera.follow = Some(player);
+            }
+        }
+
+        crate::draw_background(&textures.sprite_background);
+        if let Some(snapshot) = &latest {
+            if is_key_pressed(KeyCode::Tab) {
+                camera.cycle_follow(snapshot);
+            }
+            camera.update(snapshot);
+            snapshot.restore(&mut world);
+
+            set_camera(&camera.to_camera(vec2(screen_width(), screen_height())));
+            draw_rectangle_lines(0., 0., world.arena.x, world.arena.y, 2., DARKGRAY);
+            for vaisseau in &world.ships {
+                crate::draw_vaisseau(vaisseau, &textures.sprite_vaisseau, world.hauteur_vaisseau);
+            }
+            crate::draw_missiles(
+                &world.missiles,
+                world.rayon_missile,
+                &textures.sprite_meteor,
+            );
+            crate::draw_asteroids_batched(
+                &mut world.asteroids,
+                world.level_size,
+                [
+                    &textures.sprite_asteroid_1,
+                    &textures.sprite_asteroid_2,
+                    &textures.sprite_asteroid_3,
+                ],
+            );
+            set_default_camera();
+            draw_spectator_hud(snapshot, &camera);
+        }
+        if reader.is_ended() {
+            draw_ui_text("Fin du flux", 10., screen_height() - 20., 25., GRAY);
+        } else if latest.is_none() {
+            draw_ui_text("En attente du flux...", 10., 30., 30., WHITE);
+        }
+        pacer.draw();
+        next_frame().await;
+        pacer.end_frame();
+    }
+}
+
+/// Affiche le mode de la caméra, puis le score de chaque joueur en jeu, dans sa couleur.
+///
+/// # Arguments
+///
+/// * `snapshot` - L'état de la partie affiché.
+/// * `camera` - La caméra du spectateur.
+fn draw_spectator_hud(snapshot: &Snapshot, camera: &SpectatorCamera) {
+    let mode = match camera.follow {
+        Some(player) => format!("Spectateur - suit le joueur {}", player + 1),
+        None => "Spectateur - caméra libre".to_string(),
+    };
+    draw_ui_text(&mode, 10., 30., 30., WHITE);
+    draw_ui_text(&format!("Vague: {}", snapshot.wave), 10., 65., 25., WHITE);
+    let mut y = 95.;
+    for ship in &snapshot.ships {
+        let player = ship.player;
+        let mut text = format!(
+            "J{} : {} pts, bouclier {:.0}",
+            player + 1,
+            snapshot.scores[player],
+            ship.shield.max(0.)
+        );
+        if snapshot.frags[player] != 0 {
+            text += &format!(", {} frags", snapshot.frags[player]);
+        }
+        draw_ui_text(&text, 10., y, 25., PLAYER_COLORS[player]);
+        y += 30.;
+    }
+}
@@ -0,0 +1,192 @@
+use crate::events::{EventListener, GameEvent};
+use crate::players::MAX_PLAYERS;
+
+/// Score de la partie, calculé à partir des événements de la partie.
+///
+/// Chaque astéroïde détruit rapporte `niveau * 10` points au joueur qui l'a détruit. Les
+/// points bonus communs (temps de la vague) sont comptés à part, dans le total de l'équipe.
+#[derive(Default)]
+pub struct Score {
+    players: [i32; MAX_PLAYERS],
+    bonus: i32,
+}
+
+impl Score {
+    /// Crée un score à zéro.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retourne le score total de l'équipe.
+    ///
+    /// # Retour
+    ///
+    /// Le nombre de points.
+    pub fn get(&self) -> i32 {
+        self.players.iter().sum::<i32>() + self.bonus
+    }
+
+    /// Retourne les points marqués par un joueur (sans les bonus communs).
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur.
+    ///
+    /// # Retour
+    ///
+    /// Le nombre de points du joueur.
+    pub fn get_player(&self, player: usize) -> i32 {
+        self.players.get(player).copied().unwrap_or(0)
+    }
+
+    /// Ajoute des points bonus communs à l'équipe (temps...).
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Le nombre de points à ajouter (peut être négatif).
+    pub fn add(&mut self, points: i32) {
+        self.bonus += points;
+    }
+
+    /// Ajoute des points à un joueur (bouclier restant...).
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur.
+    /// * `points` - Le nombre de points à ajouter (peut être négatif).
+    pub fn add_player(&mut self, player: usize, points: i32) {
+        if let Some(score) = self.players.get_mut(player) {
+            *score += points;
+        }
+    }
+
+    /// Remet le score à zéro pour une nouvelle partie.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl EventListener for Score {
+    fn on_event(&mut self, event: &GameEvent) {
+        if let GameEvent::AsteroidDestroyed { level, player, .. } = event {
+            self.add_player(*player, *level as i32 * 10);
+        }
+    }
+}
+
+/// Statistiques de la partie en cours.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameStats {
+    /// Nombre de missiles tirés.
+    pub shots_fired: u32,
+    /// Nombre de missiles ayant touché un astéroïde.
+    pub shots_hit: u32,
+    /// Nombre d'astéroïdes détruits, par niveau (index 0 = niveau 1).
+    pub asteroids_destroyed: [u32; 3],
+    /// Nombre de collisions subies par le vaisseau.
+    pub hits_taken: u32,
+    /// Total des points de bouclier perdus.
+    pub damage_taken: f32,
+}
+
+impl GameStats {
+    /// Crée des statistiques vides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retourne la précision du joueur (missiles touchés / missiles tirés).
+    ///
+    /// # Retour
+    ///
+    /// Un pourcentage entre 0 et 100, ou 0 si aucun missile n'a été tiré.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.
+        } else {
+            self.shots_hit as f32 / self.shots_fired as f32 * 100.
+        }
+    }
+}
+
+impl EventListener for GameStats {
+    fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::MissileFired { .. } => self.shots_fired += 1,
+            GameEvent::MissileImpact { .. } => self.shots_hit += 1,
+            GameEvent::AsteroidDestroyed { level, .. } => {
+                if let Some(count) = self
+                    .asteroids_destroyed
+                    .get_mut((*level as usize).wrapping_sub(1))
+                {
+                    *count += 1;
+                }
+            }
+            GameEvent::ShipHit { damage, .. } => {
+                self.hits_taken += 1;
+                self.damage_taken += damage;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Succès débloqués pendant la partie.
+///
+/// Les succès récemment débloqués sont gardés dans une liste que l'interface
+/// peut afficher puis vider.
+#[derive(Default)]
+pub struct Achievements {
+    destroyed: u32,
+    hit_this_game: bool,
+    unlocked: Vec<&'static str>,
+    recent: Vec<&'static str>,
+}
+
+impl Achievements {
+    /// Crée une liste de succès vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Prépare le suivi d'une nouvelle partie (les succès déjà débloqués sont conservés).
+    pub fn start_game(&mut self) {
+        self.destroyed = 0;
+        self.hit_this_game = false;
+    }
+
+    /// Retire et retourne les succès débloqués depuis le dernier appel.
+    ///
+    /// # Retour
+    ///
+    /// Les noms des succès nouvellement débloqués.
+    pub fn take_recent(&mut self) -> Vec<&'static str> {
+        std::mem::take(&mut self.recent)
+    }
+
+    fn unlock(&mut self, name: &'static str) {
+        if !self.unlocked.contains(&name) {
+            self.unlocked.push(name);
+            self.recent.push(name);
+        }
+    }
+}
+
+impl EventListener for Achievements {
+    fn on_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::AsteroidDestroyed { .. } => {
+                self.destroyed += 1;
+                if self.destroyed == 1 {
+                    self.unlock("Premier tir réussi");
+                }
+                if self.destroyed == 50 {
+                    self.unlock("Démolisseur : 50 astéroïdes");
+                }
+            }
+            GameEvent::ShipHit { .. } => self.hit_this_game = true,
+            GameEvent::WaveCleared if !self.hit_this_game => self.unlock("Intouchable"),
+            _ => {}
+        }
+    }
+}
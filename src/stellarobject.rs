@@ -38,8 +38,12 @@ pub trait StellarObject {
     /// Met à jour la position de l'objet stellaire.
     ///
     /// Cette méthode applique la vitesse actuelle à la position de l'objet
-    /// et peut inclure des ajustements pour gérer les limites de l'écran ou d'autres règles.
-    fn update_position(&mut self);
+    /// et peut inclure des ajustements pour gérer les limites de l'arène ou d'autres règles.
+    ///
+    /// # Arguments
+    ///
+    /// * `arena` - La taille de l'arène, pour ramener de l'autre côté un objet qui en sort.
+    fn update_position(&mut self, arena: Vec2);
 
     /// Gère une collision impliquant l'objet stellaire.
     ///
@@ -50,7 +54,7 @@ pub trait StellarObject {
     /// # Arguments
     ///
     /// * `asteroid_level` - Le niveau de l'astéroïde impliqué dans la collision
-    ///                      (utilisé pour calculer les effets de la collision).
+    ///   (utilisé pour calculer les effets de la collision).
     /// * `status` - Un indicateur booléen pour signaler si une collision s'est produite.
     /// * `speed_missile` - La vitesse de l'objet impliqué dans la collision (par exemple, un missile).
     ///
@@ -0,0 +1,182 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+use std::io;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+// Les données du joueur sont des fichiers du répertoire de données dans la version native,
+// et des entrées du stockage local du navigateur (`localStorage`) dans la version web.
+// Le reste du jeu ne passe que par `read_data_file`, `write_data_file` et `remove_data_file`.
+
+/// Nom du dossier de l'application dans les répertoires de l'utilisateur.
+#[cfg(not(target_arch = "wasm32"))]
+const APP_DIR: &str = "asteroid";
+
+/// Retourne le répertoire de données de l'utilisateur pour le jeu (scores, sauvegardes...).
+///
+/// - Linux : `$XDG_DATA_HOME/asteroid` ou `~/.local/share/asteroid`
+/// - macOS : `~/Library/Application Support/asteroid`
+/// - Windows : `%APPDATA%\asteroid`
+///
+/// Si aucune de ces variables n'est définie, le dossier courant est utilisé.
+///
+/// # Retour
+///
+/// Le chemin du répertoire (il n'est pas forcément encore créé).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn data_dir() -> PathBuf {
+    let base = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        })
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .map(|home| PathBuf::from(home).join(".local").join("share"))
+            })
+    };
+    base.unwrap_or_else(|| PathBuf::from(".")).join(APP_DIR)
+}
+
+/// Lit un fichier texte du répertoire de données.
+///
+/// # Arguments
+///
+/// * `name` - Le nom du fichier dans le répertoire de données.
+///
+/// # Retour
+///
+/// Le contenu du fichier, ou `None` s'il n'existe pas ou ne peut pas être lu.
+pub fn read_data_file(name: &str) -> Option<String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        fs::read_to_string(data_dir().join(name)).ok()
+    }
+    #[cfg(target_arch = "wasm32")]
+    web::read(name)
+}
+
+/// Écrit un fichier texte dans le répertoire de données, en créant le répertoire si besoin.
+///
+/// # Arguments
+///
+/// * `name` - Le nom du fichier dans le répertoire de données.
+/// * `contents` - Le contenu à écrire.
+///
+/// # Retour
+///
+/// Une erreur d'entrée/sortie si le fichier n'a pas pu être écrit.
+pub fn write_data_file(name: &str, contents: &str) -> io::Result<()> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let dir = data_dir();
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(name), contents)
+    }
+    #[cfg(target_arch = "wasm32")]
+    web::write(name, contents)
+}
+
+/// Supprime un fichier du répertoire de données, s'il existe.
+///
+/// # Arguments
+///
+/// * `name` - Le nom du fichier dans le répertoire de données.
+pub fn remove_data_file(name: &str) {
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = fs::remove_file(data_dir().join(name));
+    #[cfg(target_arch = "wasm32")]
+    web::remove(name);
+}
+
+/// Stockage local du navigateur, fourni par le greffon JavaScript `web/storage.js`.
+///
+/// Les clés sont préfixées par `asteroid/` pour ne pas se mêler aux autres pages du site.
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::io;
+
+    extern "C" {
+        fn asteroid_storage_length(key: *const u8, key_len: usize) -> i32;
+        fn asteroid_storage_read(key: *const u8, key_len: usize, out: *mut u8, out_len: usize);
+        fn asteroid_storage_write(
+            key: *const u8,
+            key_len: usize,
+            value: *const u8,
+            len: usize,
+        ) -> i32;
+        fn asteroid_storage_remove(key: *const u8, key_len: usize);
+    }
+
+    /// Version du greffon attendue ; `gl.js` la vérifie au chargement de la page.
+    #[no_mangle]
+    pub extern "C" fn asteroid_storage_crate_version() -> u32 {
+        1
+    }
+
+    /// Retourne la clé du stockage local d'un fichier de données.
+    fn key(name: &str) -> String {
+        format!("asteroid/{}", name)
+    }
+
+    /// Lit une entrée du stockage local.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Le nom du fichier de données.
+    ///
+    /// # Retour
+    ///
+    /// Le contenu de l'entrée, ou `None` si elle n'existe pas.
+    pub fn read(name: &str) -> Option<String> {
+        let key = key(name);
+        // SAFETY : le greffon ne lit que les `key.len()` octets de la clé et n'écrit que les
+        // `length` octets du tampon, tous deux vivants pendant l'appel.
+        let length = unsafe { asteroid_storage_length(key.as_ptr(), key.len()) };
+        let length = usize::try_from(length).ok()?;
+        let mut buffer = vec![0u8; length];
+        unsafe { asteroid_storage_read(key.as_ptr(), key.len(), buffer.as_mut_ptr(), length) };
+        String::from_utf8(buffer).ok()
+    }
+
+    /// Écrit une entrée du stockage local.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Le nom du fichier de données.
+    /// * `contents` - Le contenu à écrire.
+    ///
+    /// # Retour
+    ///
+    /// Une erreur si le navigateur refuse l'écriture (stockage plein ou désactivé).
+    pub fn write(name: &str, contents: &str) -> io::Result<()> {
+        let key = key(name);
+        // SAFETY : le greffon ne lit que les octets de la clé et du contenu pendant l'appel.
+        let written = unsafe {
+            asteroid_storage_write(key.as_ptr(), key.len(), contents.as_ptr(), contents.len())
+        };
+        if written == 0 {
+            return Err(io::Error::other(
+                "stockage local du navigateur indisponible",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Supprime une entrée du stockage local, si elle existe.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Le nom du fichier de données.
+    pub fn remove(name: &str) {
+        let key = key(name);
+        // SAFETY : le greffon ne lit que les `key.len()` octets de la clé pendant l'appel.
+        unsafe { asteroid_storage_remove(key.as_ptr(), key.len()) };
+    }
+}
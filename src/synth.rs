@@ -0,0 +1,230 @@
+use crate::assets::SAMPLE_RATE;
+use crate::rng::GameRng;
+use ::rand::RngCore;
+use std::f32::consts::TAU;
+
+/// Durée (en secondes) de la boucle du grondement du réacteur.
+const THRUST_LOOP_DURATION: f32 = 1.0;
+
+/// Durée (en secondes) des boucles des couches de musique synthétisées : toutes les couches
+/// ont la même durée pour rester synchronisées en boucle.
+const MUSIC_LOOP_DURATION: f32 = 4.0;
+
+/// Intervalle (en secondes) entre deux battements au début d'une vague.
+const HEARTBEAT_SLOWEST: f64 = 1.0;
+
+/// Intervalle (en secondes) entre deux battements lorsqu'il ne reste presque plus d'astéroïdes.
+const HEARTBEAT_FASTEST: f64 = 0.25;
+
+/// Retourne le nombre d'échantillons correspondant à une durée.
+fn sample_count(duration: f32) -> usize {
+    (duration * SAMPLE_RATE as f32) as usize
+}
+
+/// Tire un bruit blanc entre -1 et 1.
+fn noise(rng: &mut GameRng) -> f32 {
+    rng.next_u32() as f32 / u32::MAX as f32 * 2. - 1.
+}
+
+/// Synthétise un tir laser : une onde carrée dont la hauteur chute rapidement.
+///
+/// # Arguments
+///
+/// * `pitch` - Fréquence de départ, en Hz (plus elle est haute, plus le tir est aigu).
+///
+/// # Retour
+///
+/// Les échantillons, entre -1 et 1.
+pub fn laser(pitch: f32) -> Vec<f32> {
+    let count = sample_count(0.12);
+    let mut phase = 0.0f32;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / count as f32;
+            // La fréquence descend jusqu'au quart de sa valeur de départ
+            phase = (phase + pitch * (1. - 0.75 * t) / SAMPLE_RATE as f32).fract();
+            let square = if phase < 0.5 { 1. } else { -1. };
+            square * (1. - t).powi(2) * 0.3
+        })
+        .collect()
+}
+
+/// Synthétise une explosion : une rafale de bruit filtré. Plus l'astéroïde est gros, plus
+/// l'explosion est longue, grave et forte.
+///
+/// # Arguments
+///
+/// * `level` - Niveau de l'astéroïde détruit (3 = grand, 1 = petit).
+/// * `seed` - Graine du bruit, pour obtenir toujours le même son.
+///
+/// # Retour
+///
+/// Les échantillons, entre -1 et 1.
+pub fn explosion(level: u8, seed: u64) -> Vec<f32> {
+    let level = level.clamp(1, 3) as f32;
+    let count = sample_count(0.2 + 0.25 * level);
+    // Coefficient du filtre passe-bas : plus petit pour un son plus grave
+    let smoothing = 0.5 / level;
+    let volume = 0.4 + 0.2 * level;
+    let mut rng = GameRng::new(seed);
+    let mut filtered = 0.0f32;
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / count as f32;
+            filtered += (noise(&mut rng) - filtered) * smoothing;
+            (filtered * volume * (-5. * t).exp() * 2.).clamp(-1., 1.)
+        })
+        .collect()
+}
+
+/// Synthétise le grondement du réacteur : un bruit très grave, conçu pour être joué en boucle
+/// (la fin du son se fond dans son début).
+///
+/// # Arguments
+///
+/// * `seed` - Graine du bruit.
+///
+/// # Retour
+///
+/// Les échantillons, entre -1 et 1.
+pub fn thrust_rumble(seed: u64) -> Vec<f32> {
+    let count = sample_count(THRUST_LOOP_DURATION);
+    let fade = count / 10;
+    let mut rng = GameRng::new(seed);
+    let mut filtered = 0.0f32;
+    let raw: Vec<f32> = (0..count + fade)
+        .map(|_| {
+            filtered += (noise(&mut rng) - filtered) * 0.03;
+            filtered * 4.
+        })
+        .collect();
+
+    // Fondu enchaîné : le surplus généré après la fin recouvre le début de la boucle
+    let mut samples = raw[..count].to_vec();
+    for i in 0..fade {
+        let mix = i as f32 / fade as f32;
+        samples[i] = raw[i] * mix + raw[count + i] * (1. - mix);
+    }
+    samples.iter().map(|s| (s * 0.5).clamp(-1., 1.)).collect()
+}
+
+/// Synthétise un battement du rythme de fond, à la manière de la borne d'arcade : deux notes
+/// graves qui alternent.
+///
+/// # Arguments
+///
+/// * `high` - `true` pour la note haute, `false` pour la note basse.
+///
+/// # Retour
+///
+/// Les échantillons, entre -1 et 1.
+pub fn heartbeat(high: bool) -> Vec<f32> {
+    let frequency = if high { 82. } else { 65. };
+    let count = sample_count(0.15);
+    (0..count)
+        .map(|i| {
+            let t = i as f32 / count as f32;
+            let time = i as f32 / SAMPLE_RATE as f32;
+            // Une harmonique rend la note audible sur de petits haut-parleurs
+            let wave = (time * frequency * TAU).sin() + 0.5 * (time * frequency * 2. * TAU).sin();
+            wave * (1. - t).powi(3) * 0.5
+        })
+        .collect()
+}
+
+/// Calcule l'intervalle entre deux battements : le rythme accélère à mesure que les
+/// astéroïdes de la vague disparaissent.
+///
+/// # Arguments
+///
+/// * `remaining` - Nombre de tirs encore nécessaires pour détruire tous les astéroïdes.
+/// * `total` - Nombre de tirs nécessaires au début de la vague.
+///
+/// # Retour
+///
+/// L'intervalle, en secondes, entre `HEARTBEAT_FASTEST` et `HEARTBEAT_SLOWEST`.
+pub fn heartbeat_interval(remaining: u32, total: u32) -> f64 {
+    let ratio = if total == 0 {
+        0.
+    } else {
+        (remaining as f64 / total as f64).min(1.)
+    };
+    HEARTBEAT_FASTEST + (HEARTBEAT_SLOWEST - HEARTBEAT_FASTEST) * ratio
+}
+
+/// Retourne le nombre de tirs nécessaires pour détruire un astéroïde et tous ses fragments
+/// (un astéroïde se divise en deux astéroïdes du niveau inférieur).
+///
+/// # Arguments
+///
+/// * `level` - Niveau de l'astéroïde.
+pub fn hits_to_clear(level: u8) -> u32 {
+    (1 << level) - 1
+}
+
+/// Synthétise la couche de base de la musique : un bourdonnement grave qui ondule lentement.
+///
+/// Les fréquences font un nombre entier de périodes sur la boucle, qui se raccorde donc
+/// sans claquement.
+///
+/// # Retour
+///
+/// Les échantillons, entre -1 et 1.
+pub fn music_base() -> Vec<f32> {
+    let count = sample_count(MUSIC_LOOP_DURATION);
+    (0..count)
+        .map(|i| {
+            let time = i as f32 / SAMPLE_RATE as f32;
+            let swell = 0.75 + 0.25 * (time / MUSIC_LOOP_DURATION * TAU).sin();
+            let drone = (time * 55. * TAU).sin() + 0.6 * (time * 82.5 * TAU).sin();
+            drone * swell * 0.3
+        })
+        .collect()
+}
+
+/// Synthétise la couche rythmique de la musique : une basse pulsée sur chaque croche.
+///
+/// # Retour
+///
+/// Les échantillons, entre -1 et 1.
+pub fn music_pulse() -> Vec<f32> {
+    music_notes(
+        &[110., 110., 130.81, 110., 146.83, 110., 130.81, 98.],
+        16,
+        6.,
+    )
+}
+
+/// Synthétise la couche de tension de la musique : un arpège aigu et rapide.
+///
+/// # Retour
+///
+/// Les échantillons, entre -1 et 1.
+pub fn music_tension() -> Vec<f32> {
+    music_notes(&[440., 523.25, 659.25, 523.25], 32, 10.)
+}
+
+/// Synthétise une suite de notes brèves qui se répète sur toute la boucle de musique.
+///
+/// # Arguments
+///
+/// * `notes` - Les fréquences des notes, en Hz, jouées dans l'ordre puis répétées.
+/// * `steps` - Le nombre de notes dans la boucle.
+/// * `decay` - La vitesse de décroissance du volume de chaque note.
+///
+/// # Retour
+///
+/// Les échantillons, entre -1 et 1.
+fn music_notes(notes: &[f32], steps: usize, decay: f32) -> Vec<f32> {
+    let count = sample_count(MUSIC_LOOP_DURATION);
+    let step_length = count / steps;
+    (0..count)
+        .map(|i| {
+            let step = (i / step_length).min(steps - 1);
+            let frequency = notes[step % notes.len()];
+            // Temps écoulé depuis le début de la note
+            let time = (i - step * step_length) as f32 / SAMPLE_RATE as f32;
+            (time * frequency * TAU).sin() * (-decay * time * steps as f32 / 4.).exp() * 0.35
+        })
+        .collect()
+}
@@ -0,0 +1,54 @@
+use macroquad::prelude::*;
+use std::cell::RefCell;
+
+thread_local! {
+    /// Police de l'interface, ou `None` pour la police par défaut de macroquad.
+    static UI_FONT: RefCell<Option<Font>> = const { RefCell::new(None) };
+}
+
+/// Change la police utilisée par tous les textes de l'interface.
+///
+/// # Arguments
+///
+/// * `font` - La police chargée depuis le manifeste, ou `None` pour la police par défaut.
+pub fn set_ui_font(font: Option<Font>) {
+    UI_FONT.with(|ui_font| *ui_font.borrow_mut() = font);
+}
+
+/// Dessine un texte avec la police de l'interface (équivalent de `draw_text`).
+///
+/// # Arguments
+///
+/// * `text` - Le texte à afficher.
+/// * `x`, `y` - La position du début de la ligne de base du texte.
+/// * `font_size` - La taille de la police.
+/// * `color` - La couleur du texte.
+pub fn draw_ui_text(text: &str, x: f32, y: f32, font_size: f32, color: Color) {
+    UI_FONT.with(|ui_font| {
+        draw_text_ex(
+            text,
+            x,
+            y,
+            TextParams {
+                font: ui_font.borrow().as_ref(),
+                font_size: font_size as u16,
+                color,
+                ..Default::default()
+            },
+        );
+    });
+}
+
+/// Mesure un texte écrit avec la police de l'interface (équivalent de `measure_text`).
+///
+/// # Arguments
+///
+/// * `text` - Le texte à mesurer.
+/// * `font_size` - La taille de la police.
+///
+/// # Retour
+///
+/// Les dimensions du texte.
+pub fn measure_ui_text(text: &str, font_size: u16) -> TextDimensions {
+    UI_FONT.with(|ui_font| measure_text(text, ui_font.borrow().as_ref(), font_size, 1.0))
+}
@@ -1,11 +1,137 @@
 use crate::asteroid::Asteroid;
 use crate::missile::Missile;
+use crate::settings::KeyBindings;
 use crate::stellarobject::StellarObject;
 use macroquad::prelude::*;
+use std::f32::consts::{PI, TAU};
+
+/// Points de bouclier de le vaisseau en début de partie.
+pub const MAX_SHIELD: f32 = 5.;
+
+/// Rotation maximale de le vaisseau en un pas de simulation, en radians.
+const ROTATION_SPEED: f32 = 0.1;
+
+/// Commandes d'un vaisseau pendant un pas de simulation.
+///
+/// En local, elles sont lues au clavier avec `from_keys`, puis complétées par la manette ou
+/// la souris (voir `controls`) ; en réseau, elles arrivent du client dans les trames d'entrée.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ShipInput {
+    /// Accélérer.
+    pub thrust: bool,
+    /// Reculer.
+    pub brake: bool,
+    /// Tourner vers la gauche.
+    pub rotate_left: bool,
+    /// Tourner vers la droite.
+    pub rotate_right: bool,
+    /// Tirer un missile.
+    pub fire: bool,
+    /// Accélération analogique (0.0 à 1.0, gâchette de la manette), utilisée si `thrust` est
+    /// relâché.
+    pub throttle: f32,
+    /// Rotation analogique (-1.0 vers la gauche à 1.0 vers la droite), ajoutée aux touches
+    /// de rotation.
+    pub turn: f32,
+    /// Direction visée, en radians : le vaisseau tourne vers elle au lieu d'obéir aux
+    /// commandes de rotation (stick en mode absolu, visée à la souris).
+    pub heading: Option<f32>,
+}
+
+impl ShipInput {
+    /// Lit les commandes d'un joueur au clavier.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - Les touches du joueur.
+    ///
+    /// # Retour
+    ///
+    /// Les commandes dont la touche est enfoncée.
+    pub fn from_keys(keys: &KeyBindings) -> Self {
+        Self {
+            thrust: is_key_down(keys.thrust),
+            brake: is_key_down(keys.brake),
+            rotate_left: is_key_down(keys.rotate_left),
+            rotate_right: is_key_down(keys.rotate_right),
+            fire: is_key_down(keys.fire),
+            ..Default::default()
+        }
+    }
+
+    /// Combine ces commandes avec celles d'une autre source (manette, souris).
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Les commandes de l'autre source.
+    ///
+    /// # Retour
+    ///
+    /// Les commandes actives sur l'une ou l'autre source ; la direction visée de `other`
+    /// l'emporte.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            thrust: self.thrust || other.thrust,
+            brake: self.brake || other.brake,
+            rotate_left: self.rotate_left || other.rotate_left,
+            rotate_right: self.rotate_right || other.rotate_right,
+            fire: self.fire || other.fire,
+            throttle: self.throttle.max(other.throttle),
+            turn: (self.turn + other.turn).clamp(-1., 1.),
+            heading: other.heading.or(self.heading),
+        }
+    }
+
+    /// Encode les commandes tout-ou-rien sur un octet (un bit par commande), pour le réseau.
+    ///
+    /// # Retour
+    ///
+    /// L'octet des commandes.
+    pub fn to_bits(self) -> u8 {
+        [
+            self.thrust,
+            self.brake,
+            self.rotate_left,
+            self.rotate_right,
+            self.fire,
+        ]
+        .iter()
+        .enumerate()
+        .fold(0, |bits, (bit, &down)| bits | ((down as u8) << bit))
+    }
+
+    /// Décode les commandes encodées par `to_bits`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - L'octet des commandes.
+    ///
+    /// # Retour
+    ///
+    /// Les commandes correspondantes, sans commande analogique.
+    pub fn from_bits(bits: u8) -> Self {
+        let down = |bit: u8| bits & (1 << bit) != 0;
+        Self {
+            thrust: down(0),
+            brake: down(1),
+            rotate_left: down(2),
+            rotate_right: down(3),
+            fire: down(4),
+            ..Default::default()
+        }
+    }
+
+    /// Indique si les commandes contiennent une commande analogique, que `to_bits` ne
+    /// transmet pas.
+    pub fn is_analog(&self) -> bool {
+        self.throttle > 0. || self.turn != 0. || self.heading.is_some()
+    }
+}
 
 /// Représente un Vaisseau contrôlé par le joueur.
 /// Le vaisseau peut se déplacer, tirer des missiles, et subir des dégâts lorsqu'il entre en collision
 /// avec des astéroïdes. Il possède également un bouclier pour encaisser les dégâts.
+#[derive(Clone)]
 pub struct Vaisseau {
     /// Position actuelle de le vaisseau dans l'espace de jeu.
     position: Vec2,
@@ -15,15 +141,28 @@ pub struct Vaisseau {
     speed: Vec2,
     /// Points de bouclier restant de le vaisseau.
     shield: f32,
-    /// Heure du dernier tir (en secondes depuis le début de l'exécution).
+    /// Instant du dernier tir, sur l'horloge de la simulation (`World::time`), en secondes.
     last_shot: f64,
+    /// Indique si le réacteur de le vaisseau est allumé (touche d'accélération enfoncée).
+    thrusting: bool,
+    /// Touches qui pilotent le vaisseau.
+    keys: KeyBindings,
+    /// Commandes appliquées à la prochaine mise à jour.
+    input: ShipInput,
+    /// Numéro du joueur qui pilote le vaisseau (à partir de 0).
+    player: usize,
+    /// Nombre de pas de simulation pendant lesquels le vaisseau ne subit aucun dégât.
+    invulnerable_steps: u32,
+    /// Joueur dont le missile a touché le vaisseau en dernier (`None` après un astéroïde).
+    last_attacker: Option<usize>,
 }
 
 impl Vaisseau {
     /// Crée une nouvelle instance de `Vaisseau` avec des paramètres par défaut.
     ///
     /// Le vaisseau démarre au centre de l'écran, sans rotation, avec une vitesse nulle, un bouclier
-    /// de 5 points, et un temps initial pour le dernier tir.
+    /// de `MAX_SHIELD` points, et un temps initial pour le dernier tir (par défaut 0, le début
+    /// de l'horloge de la simulation).
     ///
     /// # Retour
     ///
@@ -33,8 +172,14 @@ impl Vaisseau {
             position: position.unwrap_or_else(|| vec2(screen_width() / 2., screen_height() / 2.)),
             rotation: 0.,
             speed: Vec2::new(0., 0.),
-            shield: 5.,
-            last_shot: last_shot.unwrap_or_else(get_time),
+            shield: MAX_SHIELD,
+            last_shot: last_shot.unwrap_or(0.),
+            thrusting: false,
+            keys: KeyBindings::default(),
+            input: ShipInput::default(),
+            player: 0,
+            invulnerable_steps: 0,
+            last_attacker: None,
         }
     }
 
@@ -47,6 +192,15 @@ impl Vaisseau {
         self.shield
     }
 
+    /// Fixe les points de bouclier de le vaisseau (utilisé pour restaurer une sauvegarde).
+    ///
+    /// # Arguments
+    ///
+    /// * `shield` - Nombre de points de bouclier.
+    pub fn set_shield(&mut self, shield: f32) {
+        self.shield = shield;
+    }
+
     /// Réduit les points de bouclier de le vaisseau.
     ///
     /// # Arguments
@@ -65,19 +219,139 @@ impl Vaisseau {
         self.rotation
     }
 
-    /// Tente de tirer un missile si le temps de recharge est écoulé.
+    /// Fixe l'angle de rotation de le vaisseau (utilisé pour restaurer une sauvegarde).
     ///
     /// # Arguments
     ///
-    /// * `current_time` - Temps actuel (en secondes depuis le début de l'exécution).
+    /// * `rotation` - L'angle de rotation en radians.
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    /// Retourne l'heure du dernier tir.
+    ///
+    /// # Retour
+    ///
+    /// L'instant du dernier tir, sur l'horloge de la simulation, en secondes.
+    pub fn get_last_shot(&self) -> f64 {
+        self.last_shot
+    }
+
+    /// Indique si le vaisseau accélère, pour afficher la flamme du réacteur.
+    ///
+    /// # Retour
+    ///
+    /// `true` si le vaisseau a accéléré lors de la dernière mise à jour, sinon `false`.
+    pub fn is_thrusting(&self) -> bool {
+        self.thrusting
+    }
+
+    /// Retourne les touches qui pilotent le vaisseau.
+    ///
+    /// # Retour
+    ///
+    /// Les touches du joueur.
+    pub fn get_key_bindings(&self) -> KeyBindings {
+        self.keys
+    }
+
+    /// Change les touches qui pilotent le vaisseau.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - Les nouvelles touches.
+    pub fn set_key_bindings(&mut self, keys: KeyBindings) {
+        self.keys = keys;
+    }
+
+    /// Change les commandes appliquées à la prochaine mise à jour.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Les commandes du joueur, lues au clavier ou reçues du réseau.
+    pub fn set_input(&mut self, input: ShipInput) {
+        self.input = input;
+    }
+
+    /// Retourne le numéro du joueur qui pilote le vaisseau.
+    ///
+    /// # Retour
+    ///
+    /// Le numéro du joueur (à partir de 0).
+    pub fn get_player(&self) -> usize {
+        self.player
+    }
+
+    /// Attribue le vaisseau à un joueur.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur (à partir de 0).
+    pub fn set_player(&mut self, player: usize) {
+        self.player = player;
+    }
+
+    /// Indique si le vaisseau vient de réapparaître et ne peut pas encore être touché.
+    ///
+    /// # Retour
+    ///
+    /// `true` si le vaisseau est invincible, sinon `false`.
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_steps > 0
+    }
+
+    /// Retourne le joueur dont le missile a touché le vaisseau en dernier.
+    ///
+    /// # Retour
+    ///
+    /// Le numéro du joueur, ou `None` si le dernier coup venait d'un astéroïde.
+    pub fn get_last_attacker(&self) -> Option<usize> {
+        self.last_attacker
+    }
+
+    /// Enregistre le joueur dont le missile vient de toucher le vaisseau.
+    ///
+    /// # Arguments
+    ///
+    /// * `attacker` - Le numéro du joueur qui a tiré.
+    pub fn set_last_attacker(&mut self, attacker: Option<usize>) {
+        self.last_attacker = attacker;
+    }
+
+    /// Retourne le nombre de pas de simulation d'invincibilité restants.
+    ///
+    /// # Retour
+    ///
+    /// Le nombre de pas, 0 si le vaisseau peut être touché.
+    pub fn get_invulnerable_steps(&self) -> u32 {
+        self.invulnerable_steps
+    }
+
+    /// Rend le vaisseau invincible pendant un certain nombre de pas de simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - La durée de l'invincibilité, en pas de simulation.
+    pub fn set_invulnerable(&mut self, steps: u32) {
+        self.invulnerable_steps = steps;
+    }
+
+    /// Tente de tirer un missile si la commande de tir est active et que le temps de
+    /// recharge est écoulé.
+    ///
+    /// # Arguments
+    ///
+    /// * `current_time` - L'horloge de la simulation (`World::time`), en secondes.
     ///
     /// # Retour
     ///
     /// Une instance de `Missile` si le tir est possible, sinon `None`.
     pub fn fire_missile(&mut self, current_time: f64) -> Option<Missile> {
-        if is_key_down(KeyCode::Space) && (current_time - self.last_shot >= 0.5) {
+        if self.input.fire && (current_time - self.last_shot >= 0.5) {
             self.last_shot = current_time;
-            Some(Missile::new(self.position, self.rotation))
+            let mut missile = Missile::new(self.position, self.rotation);
+            missile.set_owner(self.player);
+            Some(missile)
         } else {
             None
         }
@@ -90,14 +364,15 @@ impl Vaisseau {
     /// # Arguments
     ///
     /// * `pos` - La position à contraindre.
+    /// * `arena` - La taille de l'arène.
     ///
     /// # Retour
     ///
-    /// Une nouvelle position contrainte dans les limites de l'écran.
-    fn bound_position(pos: Vec2) -> Vec2 {
+    /// Une nouvelle position contrainte dans les limites de l'arène.
+    fn bound_position(pos: Vec2, arena: Vec2) -> Vec2 {
         Vec2::new(
-            Self::wrap_position(pos.x, screen_width()),
-            Self::wrap_position(pos.y, screen_height()),
+            Self::wrap_position(pos.x, arena.x),
+            Self::wrap_position(pos.y, arena.y),
         )
     }
 
@@ -161,24 +436,34 @@ impl StellarObject for Vaisseau {
         self.speed = new_speed;
     }
 
-    /// Met à jour la position de le vaisseau en fonction de sa vitesse et de l'entrée du joueur.
+    /// Met à jour la position de le vaisseau en fonction de sa vitesse et des commandes du joueur.
     ///
-    /// Les touches directionnelles (`Up`, `Down`, `Left`, `Right`) contrôlent la rotation et
-    /// l'accélération de le vaisseau. Un effet de friction est appliqué pour ralentir naturellement le vaisseau.
-    fn update_position(&mut self) {
+    /// Les commandes (voir `set_input`) contrôlent la rotation et l'accélération de le vaisseau.
+    /// Une direction visée fait tourner le vaisseau vers elle par le plus court chemin, et
+    /// l'accélération analogique est proportionnelle à la pression de la gâchette.
+    /// Un effet de friction est appliqué pour ralentir naturellement le vaisseau.
+    fn update_position(&mut self, arena: Vec2) {
         let mut acceleration = Vec2::ZERO;
-
-        if is_key_down(KeyCode::Right) {
-            self.rotation += 0.1;
+        let throttle = if self.input.thrust {
+            1.
+        } else {
+            self.input.throttle.clamp(0., 1.)
         };
+        self.thrusting = throttle > 0.;
+        self.invulnerable_steps = self.invulnerable_steps.saturating_sub(1);
 
-        if is_key_down(KeyCode::Left) {
-            self.rotation -= 0.1;
+        if let Some(heading) = self.input.heading {
+            let difference = (heading - self.rotation + PI).rem_euclid(TAU) - PI;
+            self.rotation += difference.clamp(-ROTATION_SPEED, ROTATION_SPEED);
+        } else {
+            let keys = self.input.rotate_right as i32 - self.input.rotate_left as i32;
+            let turn = (keys as f32 + self.input.turn).clamp(-1., 1.);
+            self.rotation += turn * ROTATION_SPEED;
         }
 
-        if is_key_down(KeyCode::Up) {
-            acceleration -= Vec2::new(self.rotation.sin(), self.rotation.cos());
-        } else if is_key_down(KeyCode::Down) {
+        if throttle > 0. {
+            acceleration -= Vec2::new(self.rotation.sin(), self.rotation.cos()) * throttle;
+        } else if self.input.brake {
             acceleration += Vec2::new(self.rotation.sin(), self.rotation.cos());
         } else if self.speed.length() > 0.01 {
             self.speed *= 0.995; // Friction : ralentir progressivement
@@ -194,7 +479,7 @@ impl StellarObject for Vaisseau {
             self.set_speed(new_speed);
         }
 
-        let new_position = Self::bound_position(self.position + self.speed);
+        let new_position = Self::bound_position(self.position + self.speed, arena);
         self.set_position(new_position);
     }
 
@@ -224,6 +509,7 @@ impl StellarObject for Vaisseau {
             _ => 0.,
         };
         self.dmg_shield(dmg);
+        self.last_attacker = None;
         None
     }
 }
@@ -0,0 +1,163 @@
+use crate::events::{EventListener, GameEvent};
+use crate::players::MAX_PLAYERS;
+use crate::settings::KeyBindings;
+use crate::world::STEP_RATE;
+
+/// Nombre de pas de simulation entre la destruction d'un vaisseau et sa réapparition
+/// (3 secondes).
+pub const RESPAWN_DELAY: u32 = 3 * STEP_RATE as u32;
+
+/// Règles de fin d'un match en versus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersusRules {
+    /// Nombre de frags qui donne la victoire.
+    pub frag_limit: u32,
+    /// Durée du match, en secondes (0 : pas de limite de temps).
+    pub time_limit: u32,
+}
+
+impl Default for VersusRules {
+    fn default() -> Self {
+        Self {
+            frag_limit: 10,
+            time_limit: 180,
+        }
+    }
+}
+
+impl VersusRules {
+    /// Indique si le match est terminé.
+    ///
+    /// # Arguments
+    ///
+    /// * `scoreboard` - Le tableau des frags.
+    /// * `elapsed` - Le temps écoulé depuis le début du match, en secondes.
+    ///
+    /// # Retour
+    ///
+    /// `true` si un joueur a atteint la limite de frags ou si le temps est écoulé.
+    pub fn is_over(&self, scoreboard: &Scoreboard, elapsed: f64) -> bool {
+        scoreboard.top_frags() >= self.frag_limit as i32
+            || (self.time_limit > 0 && elapsed >= self.time_limit as f64)
+    }
+
+    /// Retourne le temps restant du match.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed` - Le temps écoulé depuis le début du match, en secondes.
+    ///
+    /// # Retour
+    ///
+    /// Le temps restant en secondes, ou `None` si le match n'a pas de limite de temps.
+    pub fn remaining_time(&self, elapsed: f64) -> Option<f64> {
+        (self.time_limit > 0).then(|| (self.time_limit as f64 - elapsed).max(0.))
+    }
+}
+
+/// Tableau des frags et des morts de chaque joueur, calculé à partir des événements.
+///
+/// Un vaisseau détruit par le missile d'un autre joueur rapporte un frag à ce joueur ; un
+/// vaisseau détruit par un astéroïde ne compte qu'une mort.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scoreboard {
+    frags: [i32; MAX_PLAYERS],
+    deaths: [u32; MAX_PLAYERS],
+}
+
+impl Scoreboard {
+    /// Crée un tableau vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recrée un tableau à partir de valeurs sauvegardées.
+    ///
+    /// # Arguments
+    ///
+    /// * `frags` - Les frags de chaque joueur.
+    /// * `deaths` - Les morts de chaque joueur.
+    pub fn from_counts(frags: [i32; MAX_PLAYERS], deaths: [u32; MAX_PLAYERS]) -> Self {
+        Self { frags, deaths }
+    }
+
+    /// Retourne les frags de chaque joueur.
+    pub fn get_frags(&self) -> [i32; MAX_PLAYERS] {
+        self.frags
+    }
+
+    /// Retourne les morts de chaque joueur.
+    pub fn get_deaths(&self) -> [u32; MAX_PLAYERS] {
+        self.deaths
+    }
+
+    /// Retourne le plus grand nombre de frags.
+    pub fn top_frags(&self) -> i32 {
+        self.frags.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Retourne le joueur en tête du match.
+    ///
+    /// # Arguments
+    ///
+    /// * `players` - Le nombre de joueurs du match.
+    ///
+    /// # Retour
+    ///
+    /// Le numéro du joueur qui a le plus de frags, ou `None` en cas d'égalité.
+    pub fn leader(&self, players: usize) -> Option<usize> {
+        let frags = &self.frags[..players.min(MAX_PLAYERS)];
+        let top = frags.iter().copied().max()?;
+        let mut leaders = (0..frags.len()).filter(|&player| frags[player] == top);
+        let leader = leaders.next();
+        if leaders.next().is_some() {
+            None
+        } else {
+            leader
+        }
+    }
+}
+
+impl EventListener for Scoreboard {
+    fn on_event(&mut self, event: &GameEvent) {
+        if let GameEvent::ShipDestroyed { player, killer, .. } = *event {
+            if let Some(deaths) = self.deaths.get_mut(player) {
+                *deaths += 1;
+            }
+            if let Some(killer) = killer.filter(|&killer| killer != player) {
+                if let Some(frags) = self.frags.get_mut(killer) {
+                    *frags += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Vaisseau détruit en attente de réapparition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PendingRespawn {
+    /// Le joueur dont le vaisseau va réapparaître.
+    pub player: usize,
+    /// Les touches du joueur, rendues au nouveau vaisseau.
+    pub keys: KeyBindings,
+    /// Nombre de pas de simulation avant la réapparition.
+    pub steps: u32,
+}
+
+/// Fait avancer d'un pas de simulation les réapparitions en attente.
+///
+/// # Arguments
+///
+/// * `pending` - Les réapparitions en attente ; celles qui sont prêtes en sont retirées.
+///
+/// # Retour
+///
+/// Les réapparitions dont le délai est écoulé.
+pub fn tick_respawns(pending: &mut Vec<PendingRespawn>) -> Vec<PendingRespawn> {
+    for respawn in pending.iter_mut() {
+        respawn.steps = respawn.steps.saturating_sub(1);
+    }
+    let (ready, waiting) = pending.drain(..).partition(|respawn| respawn.steps == 0);
+    *pending = waiting;
+    ready
+}
@@ -0,0 +1,262 @@
+use crate::asteroid::Asteroid;
+use crate::camera::ArenaAspect;
+use crate::events::{EventQueue, GameEvent};
+use crate::missile::Missile;
+use crate::players::{self, Lives, RESPAWN_INVULNERABILITY};
+use crate::rng::GameRng;
+use crate::settings::KeyBindings;
+use crate::stellarobject::StellarObject;
+use crate::vaisseau::{Vaisseau, MAX_SHIELD};
+use crate::versus::{self, PendingRespawn, RESPAWN_DELAY};
+use macroquad::prelude::*;
+
+/// Nombre de pas de simulation par seconde. Les vitesses, les délais de réapparition et les
+/// autres grandeurs de la simulation sont exprimés par pas.
+pub const STEP_RATE: f64 = 120.;
+
+/// État de la simulation : les vaisseaux, les astéroïdes, les missiles et les tailles des objets.
+///
+/// `World` ne dessine rien, ne joue aucun son et ne lit pas le clavier : il applique les
+/// commandes données à chaque vaisseau (`Vaisseau::set_input`) et publie des `GameEvent` dans
+/// la file fournie à `step`. Il peut donc tourner sans fenêtre, sur le serveur d'une partie
+/// en réseau.
+pub struct World {
+    /// Les vaisseaux encore en jeu, un par joueur. Un vaisseau détruit sans vie restante
+    /// est retiré de la liste.
+    pub ships: Vec<Vaisseau>,
+    /// Nombre de joueurs de la partie.
+    pub players: usize,
+    /// Vies restantes des joueurs.
+    pub lives: Lives,
+    /// Les missiles d'un joueur touchent aussi les vaisseaux des autres joueurs.
+    pub friendly_fire: bool,
+    /// Match en versus : les missiles touchent les vaisseaux adverses, et un vaisseau détruit
+    /// réapparaît toujours après `RESPAWN_DELAY` pas de simulation, sans utiliser de vie.
+    pub versus: bool,
+    /// Vaisseaux détruits en attente de réapparition (versus).
+    pub respawns: Vec<PendingRespawn>,
+    /// Les astéroïdes présents dans l'arène.
+    pub asteroids: Vec<Asteroid>,
+    /// Les missiles actifs.
+    pub missiles: Vec<Missile>,
+    /// Hauteur de le vaisseau (sert aussi de rayon de collision).
+    pub hauteur_vaisseau: f32,
+    /// Rayon des missiles.
+    pub rayon_missile: f32,
+    /// Tailles des astéroïdes de niveau 3, 2 et 1.
+    pub level_size: (f32, f32, f32),
+    /// Portée de la gravité des astéroïdes.
+    pub gravite_dist: f32,
+    /// Taille de l'arène, en unités du monde indépendantes de la fenêtre : les objets qui en
+    /// sortent réapparaissent de l'autre côté.
+    pub arena: Vec2,
+    /// Numéro de la vague en cours (à partir de 1).
+    pub wave: u32,
+    /// Graine aléatoire de la partie en cours.
+    pub seed: u64,
+    /// Générateur aléatoire de la partie, initialisé avec `seed`.
+    pub rng: GameRng,
+    /// Invincibilité (triche) : le bouclier des vaisseaux est rechargé à chaque pas, si bien
+    /// qu'ils subissent les chocs sans jamais être détruits.
+    pub god_mode: bool,
+    /// Horloge de la simulation, en secondes : elle avance de `1 / STEP_RATE` à chaque pas,
+    /// et sert au temps de recharge des tirs.
+    pub time: f64,
+}
+
+impl World {
+    /// Crée un monde vide avec les tailles d'objets par défaut, dans une arène d'un format
+    /// donné.
+    ///
+    /// # Arguments
+    ///
+    /// * `aspect` - Le format de l'arène.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `World` sans astéroïde ni missile.
+    pub fn new(aspect: ArenaAspect) -> Self {
+        Self::with_arena(aspect.get_size())
+    }
+
+    /// Crée un monde vide avec les tailles d'objets par défaut, dans une arène de taille
+    /// quelconque.
+    ///
+    /// # Arguments
+    ///
+    /// * `arena` - La taille de l'arène.
+    ///
+    /// # Retour
+    ///
+    /// Une instance de `World` sans astéroïde ni missile.
+    pub fn with_arena(arena: Vec2) -> Self {
+        Self {
+            ships: vec![Vaisseau::new(Some(arena / 2.), Some(0.))],
+            players: 1,
+            lives: Lives::new(1, 0, false),
+            friendly_fire: false,
+            versus: false,
+            respawns: Vec::new(),
+            asteroids: Vec::new(),
+            missiles: Vec::new(),
+            hauteur_vaisseau: 30.,
+            rayon_missile: 7.,
+            level_size: (40., 20., 10.),
+            gravite_dist: 30.,
+            arena,
+            wave: 1,
+            seed: 0,
+            rng: GameRng::new(0),
+            god_mode: false,
+            time: 0.,
+        }
+    }
+
+    /// Réinitialise le générateur aléatoire avec une nouvelle graine.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - La graine de la nouvelle partie.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = GameRng::new(seed);
+    }
+
+    /// Lance une vague : les missiles sont retirés, et la vague arrive avec deux astéroïdes
+    /// de plus que la précédente. Les astéroïdes encore présents restent en jeu.
+    ///
+    /// # Arguments
+    ///
+    /// * `wave` - Le numéro de la vague (à partir de 1).
+    /// * `asteroid_count` - Le nombre d'astéroïdes de la première vague.
+    /// * `asteroid_speed` - La vitesse des astéroïdes.
+    pub fn start_wave(&mut self, wave: u32, asteroid_count: i32, asteroid_speed: f32) {
+        self.wave = wave;
+        self.missiles.clear();
+        crate::spawn_asteroids(
+            &mut self.asteroids,
+            self.level_size,
+            self.arena,
+            asteroid_count + 2 * (wave as i32 - 1),
+            asteroid_speed,
+            None,
+            &mut self.rng,
+        );
+    }
+
+    /// Fait avancer la simulation d'un pas, et son horloge de `1 / STEP_RATE` seconde.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - La file dans laquelle publier les événements du pas.
+    pub fn step(&mut self, events: &mut EventQueue) {
+        let arena = self.arena;
+        for vaisseau in self.ships.iter_mut() {
+            crate::update_model_vaisseau(vaisseau, arena, self.hauteur_vaisseau, events);
+        }
+        crate::update_asteroids(&mut self.asteroids, arena);
+        crate::update_missiles(&mut self.missiles, arena);
+
+        // Tirer un missile si nécessaire
+        for vaisseau in self.ships.iter_mut() {
+            if let Some(missile) = vaisseau.fire_missile(self.time) {
+                events.push(GameEvent::MissileFired {
+                    player: missile.get_owner(),
+                    position: missile.get_position(),
+                });
+                self.missiles.push(missile);
+            }
+        }
+
+        crate::check_ships_asteroids(
+            &mut self.ships,
+            &mut self.asteroids,
+            self.hauteur_vaisseau,
+            self.level_size,
+            self.gravite_dist,
+            events,
+        );
+        crate::check_missiles_asteroids(
+            &mut self.missiles,
+            &mut self.asteroids,
+            self.rayon_missile,
+            self.level_size,
+            events,
+        );
+        if self.friendly_fire || self.versus {
+            crate::check_missiles_ships(
+                &mut self.missiles,
+                &mut self.ships,
+                self.rayon_missile,
+                self.hauteur_vaisseau,
+                events,
+            );
+        }
+        if self.god_mode {
+            for vaisseau in self.ships.iter_mut() {
+                vaisseau.set_shield(MAX_SHIELD);
+            }
+        }
+        self.handle_destroyed_ships(arena, events);
+        for respawn in versus::tick_respawns(&mut self.respawns) {
+            let vaisseau = self.spawn_ship(respawn.player, respawn.keys, arena);
+            self.ships.push(vaisseau);
+        }
+        self.time += 1. / STEP_RATE;
+    }
+
+    /// Crée le vaisseau d'un joueur qui réapparaît à son point de départ, avec un bouclier
+    /// plein et quelques instants d'invincibilité.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - Le numéro du joueur.
+    /// * `keys` - Les touches du joueur.
+    /// * `arena` - La taille de l'arène.
+    ///
+    /// # Retour
+    ///
+    /// Le nouveau vaisseau.
+    pub fn spawn_ship(&self, player: usize, keys: KeyBindings, arena: Vec2) -> Vaisseau {
+        let position = players::spawn_position(player, self.players, arena);
+        let mut vaisseau = Vaisseau::new(Some(position), Some(0.));
+        vaisseau.set_player(player);
+        vaisseau.set_key_bindings(keys);
+        vaisseau.set_invulnerable(RESPAWN_INVULNERABILITY);
+        vaisseau
+    }
+
+    /// Retire les vaisseaux dont le bouclier est épuisé. En versus, le vaisseau est mis en
+    /// attente de réapparition ; sinon, un vaisseau dont le joueur a encore une vie réapparaît
+    /// immédiatement (voir `spawn_ship`).
+    ///
+    /// # Arguments
+    ///
+    /// * `arena` - La taille de l'arène, pour placer les vaisseaux qui réapparaissent.
+    /// * `events` - La file dans laquelle publier un `GameEvent::ShipDestroyed` par vaisseau détruit.
+    pub fn handle_destroyed_ships(&mut self, arena: Vec2, events: &mut EventQueue) {
+        let (destroyed, alive): (Vec<Vaisseau>, Vec<Vaisseau>) = std::mem::take(&mut self.ships)
+            .into_iter()
+            .partition(|vaisseau| vaisseau.get_shield() < 0.);
+        self.ships = alive;
+
+        for vaisseau in destroyed {
+            let player = vaisseau.get_player();
+            events.push(GameEvent::ShipDestroyed {
+                player,
+                killer: vaisseau.get_last_attacker(),
+                position: vaisseau.get_position(),
+            });
+            if self.versus {
+                self.respawns.push(PendingRespawn {
+                    player,
+                    keys: vaisseau.get_key_bindings(),
+                    steps: RESPAWN_DELAY,
+                });
+            } else if self.lives.try_respawn(player) {
+                let respawned = self.spawn_ship(player, vaisseau.get_key_bindings(), arena);
+                self.ships.push(respawned);
+            }
+        }
+    }
+}
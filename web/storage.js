@@ -0,0 +1,54 @@
+// Greffon miniquad du stockage local : les préférences, scores et sauvegardes du jeu sont
+// gardés dans le localStorage du navigateur (voir src/storage.rs).
+"use strict";
+
+(function () {
+    var encoder = new TextEncoder();
+    var decoder = new TextDecoder();
+
+    function read_string(ptr, len) {
+        return decoder.decode(new Uint8Array(wasm_memory.buffer, ptr, len));
+    }
+
+    function get_item(key_ptr, key_len) {
+        try {
+            return window.localStorage.getItem(read_string(key_ptr, key_len));
+        } catch (error) {
+            return null;
+        }
+    }
+
+    miniquad_add_plugin({
+        name: "asteroid_storage",
+        version: 1,
+        register_plugin: function (importObject) {
+            // Taille en octets de l'entrée, ou -1 si elle n'existe pas
+            importObject.env.asteroid_storage_length = function (key_ptr, key_len) {
+                var value = get_item(key_ptr, key_len);
+                return value === null ? -1 : encoder.encode(value).length;
+            };
+            importObject.env.asteroid_storage_read = function (key_ptr, key_len, out_ptr, out_len) {
+                var value = get_item(key_ptr, key_len);
+                if (value !== null) {
+                    var bytes = encoder.encode(value).subarray(0, out_len);
+                    new Uint8Array(wasm_memory.buffer, out_ptr, out_len).set(bytes);
+                }
+            };
+            // 1 si l'entrée est écrite, 0 si le navigateur refuse (stockage plein ou désactivé)
+            importObject.env.asteroid_storage_write = function (key_ptr, key_len, value_ptr, value_len) {
+                try {
+                    window.localStorage.setItem(read_string(key_ptr, key_len), read_string(value_ptr, value_len));
+                    return 1;
+                } catch (error) {
+                    return 0;
+                }
+            };
+            importObject.env.asteroid_storage_remove = function (key_ptr, key_len) {
+                try {
+                    window.localStorage.removeItem(read_string(key_ptr, key_len));
+                } catch (error) {
+                }
+            };
+        }
+    });
+})();